@@ -0,0 +1,97 @@
+//! Reports how many allocations capturing an event makes, by number of
+//! fields, for a sink that takes owned `TracingEvent`s and for one that
+//! serializes the borrowed `TracingEventRef` directly.
+//!
+//! Run with `cargo bench --bench allocations --features json`. Unlike the
+//! other benchmarks, this one counts rather than times, so its numbers are
+//! exact and can be compared across changes directly.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    io,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use tracing_bridge::{BridgeLayer, EventSink, JsonLinesSink, TracingEvent};
+use tracing_subscriber::layer::SubscriberExt;
+
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+const ITERATIONS: usize = 1000;
+
+fn emit_0() {
+    tracing::info!("handled request");
+}
+
+fn emit_4() {
+    tracing::info!(
+        f0 = 42u64,
+        f1 = "/api/v1/orders",
+        f2 = 12.5,
+        f3 = false,
+        "handled request"
+    );
+}
+
+fn emit_16() {
+    tracing::info!(
+        f0 = 42u64,
+        f1 = "/api/v1/orders",
+        f2 = 12.5,
+        f3 = false,
+        f4 = ?["status", "created_at"],
+        f5 = 42u64,
+        f6 = "/api/v1/orders",
+        f7 = 12.5,
+        f8 = false,
+        f9 = ?["status", "created_at"],
+        f10 = 42u64,
+        f11 = "/api/v1/orders",
+        f12 = 12.5,
+        f13 = false,
+        f14 = ?["status", "created_at"],
+        f15 = 42u64,
+        "handled request"
+    );
+}
+
+/// Returns the average number of allocations per event `emit` makes with
+/// `sink` installed, after a first event has allocated anything needed on
+/// first use.
+fn per_event<K: EventSink>(sink: K, emit: fn()) -> f64 {
+    let subscriber = tracing_subscriber::registry().with(BridgeLayer::new(sink));
+    tracing::subscriber::with_default(subscriber, || {
+        emit();
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+        for _ in 0..ITERATIONS {
+            emit();
+        }
+        (ALLOCATIONS.load(Ordering::Relaxed) - before) as f64 / ITERATIONS as f64
+    })
+}
+
+fn main() {
+    let emits: [(usize, fn()); 3] = [(0, emit_0), (4, emit_4), (16, emit_16)];
+    println!("{:>6} {:>8} {:>8}", "fields", "owned", "borrowed");
+    for (fields, emit) in emits {
+        let owned = per_event(|event: TracingEvent| drop(event), emit);
+        let borrowed = per_event(JsonLinesSink::new(io::sink()), emit);
+        println!("{:>6} {:>8.1} {:>8.1}", fields, owned, borrowed);
+    }
+}
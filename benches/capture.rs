@@ -0,0 +1,45 @@
+//! Compares capturing events into owned `TracingEvent`s with serializing the
+//! borrowed `TracingEventRef` directly, for a sink that writes JSON lines.
+
+use std::io;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use tracing_bridge::{BridgeLayer, EventSink, JsonLinesSink, SinkError, TracingEvent};
+use tracing_subscriber::layer::SubscriberExt;
+
+/// A JSON lines sink that only accepts owned events, so the layer converts
+/// every event before it is serialized.
+struct Owned(JsonLinesSink<io::Sink>);
+
+impl EventSink for Owned {
+    fn emit(&self, event: TracingEvent) -> Result<(), SinkError> {
+        self.0.emit(event)
+    }
+}
+
+fn emit() {
+    tracing::info!(
+        user_id = 42u64,
+        path = "/api/v1/orders",
+        latency_ms = 12.5,
+        cached = false,
+        query = ?["status", "created_at"],
+        "handled request in {} ms",
+        12,
+    );
+}
+
+fn bench<K: EventSink>(c: &mut Criterion, name: &str, sink: K) {
+    let subscriber = tracing_subscriber::registry().with(BridgeLayer::new(sink));
+    tracing::subscriber::with_default(subscriber, || {
+        c.bench_function(name, |b| b.iter(emit));
+    });
+}
+
+fn capture(c: &mut Criterion) {
+    bench(c, "owned", Owned(JsonLinesSink::new(io::sink())));
+    bench(c, "borrowed", JsonLinesSink::new(io::sink()));
+}
+
+criterion_group!(benches, capture);
+criterion_main!(benches);
@@ -0,0 +1,158 @@
+//! Baselines for converting `tracing` events, serializing them, and running
+//! them through a `BridgeLayer`, for events with 0, 4 and 16 fields besides
+//! the message.
+
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use tracing_bridge::{BridgeLayer, TracingEvent, TracingEventRef};
+use tracing_core::{
+    callsite::Callsite,
+    field::{debug, Value},
+    metadata::Kind,
+    subscriber::Interest,
+    Event, Level, Metadata,
+};
+use tracing_subscriber::layer::SubscriberExt;
+
+const FIELD_COUNTS: [usize; 3] = [0, 4, 16];
+
+struct BenchCallsite;
+
+static CALLSITE: BenchCallsite = BenchCallsite;
+
+static METADATA: Metadata<'static> = tracing_core::metadata! {
+    name: "bench",
+    target: "bench",
+    level: Level::INFO,
+    fields: &[
+        "message", "f0", "f1", "f2", "f3", "f4", "f5", "f6", "f7", "f8", "f9", "f10", "f11",
+        "f12", "f13", "f14", "f15",
+    ],
+    callsite: &CALLSITE,
+    kind: Kind::EVENT,
+};
+
+impl Callsite for BenchCallsite {
+    fn set_interest(&self, _interest: Interest) {}
+
+    fn metadata(&self) -> &Metadata<'_> {
+        &METADATA
+    }
+}
+
+/// Calls `f` with an event that has a message and `fields` other fields, of
+/// a mix of types, without dispatching it.
+fn with_event(fields: usize, f: impl FnOnce(&Event<'_>)) {
+    let names: Vec<_> = METADATA.fields().iter().collect();
+    let list = debug(["status", "created_at"]);
+    let values: [&dyn Value; 5] = [&42u64, &"/api/v1/orders", &12.5f64, &false, &list];
+
+    let mut recorded: Vec<(_, Option<&dyn Value>)> = vec![(&names[0], Some(&"handled request"))];
+    recorded.extend((0..fields).map(|i| (&names[i + 1], Some(values[i % values.len()]))));
+    match fields {
+        0 => record::<1>(&recorded, f),
+        4 => record::<5>(&recorded, f),
+        16 => record::<17>(&recorded, f),
+        _ => unreachable!("only the field counts in `FIELD_COUNTS` are benchmarked"),
+    }
+}
+
+fn record<const N: usize>(
+    recorded: &[(&tracing_core::Field, Option<&dyn Value>)],
+    f: impl FnOnce(&Event<'_>),
+) {
+    let recorded: [_; N] = std::array::from_fn(|i| recorded[i]);
+    let values = METADATA.fields().value_set(&recorded);
+    f(&Event::new(&METADATA, &values));
+}
+
+fn conversion(c: &mut Criterion) {
+    let mut group = c.benchmark_group("conversion");
+    for fields in FIELD_COUNTS {
+        with_event(fields, |event| {
+            group.bench_with_input(BenchmarkId::from_parameter(fields), event, |b, event| {
+                b.iter(|| TracingEvent::from(black_box(event)))
+            });
+        });
+    }
+    group.finish();
+}
+
+fn serialization(c: &mut Criterion) {
+    let mut group = c.benchmark_group("json");
+    let mut buf = Vec::new();
+    for fields in FIELD_COUNTS {
+        with_event(fields, |event| {
+            let owned = TracingEvent::from(event);
+            group.bench_with_input(BenchmarkId::new("owned", fields), &owned, |b, owned| {
+                b.iter(|| {
+                    buf.clear();
+                    serde_json::to_writer(&mut buf, black_box(owned)).unwrap();
+                })
+            });
+            let borrowed = TracingEventRef::new(event);
+            group.bench_with_input(
+                BenchmarkId::new("borrowed", fields),
+                &borrowed,
+                |b, borrowed| {
+                    b.iter(|| {
+                        buf.clear();
+                        serde_json::to_writer(&mut buf, black_box(borrowed)).unwrap();
+                    })
+                },
+            );
+        });
+    }
+    group.finish();
+}
+
+fn layer(c: &mut Criterion) {
+    let subscriber = tracing_subscriber::registry().with(BridgeLayer::new(|event| {
+        black_box(event);
+    }));
+    tracing::subscriber::with_default(subscriber, || {
+        let mut group = c.benchmark_group("layer");
+        group.bench_function(BenchmarkId::from_parameter(0), |b| {
+            b.iter(|| tracing::info!("handled request"))
+        });
+        group.bench_function(BenchmarkId::from_parameter(4), |b| {
+            b.iter(|| {
+                tracing::info!(
+                    f0 = 42u64,
+                    f1 = "/api/v1/orders",
+                    f2 = 12.5,
+                    f3 = false,
+                    "handled request"
+                )
+            })
+        });
+        group.bench_function(BenchmarkId::from_parameter(16), |b| {
+            b.iter(|| {
+                tracing::info!(
+                    f0 = 42u64,
+                    f1 = "/api/v1/orders",
+                    f2 = 12.5,
+                    f3 = false,
+                    f4 = ?["status", "created_at"],
+                    f5 = 42u64,
+                    f6 = "/api/v1/orders",
+                    f7 = 12.5,
+                    f8 = false,
+                    f9 = ?["status", "created_at"],
+                    f10 = 42u64,
+                    f11 = "/api/v1/orders",
+                    f12 = 12.5,
+                    f13 = false,
+                    f14 = ?["status", "created_at"],
+                    f15 = 42u64,
+                    "handled request"
+                )
+            })
+        });
+        group.finish();
+    });
+}
+
+criterion_group!(benches, conversion, serialization, layer);
+criterion_main!(benches);
@@ -0,0 +1,238 @@
+use alloc::{borrow::ToOwned, format, string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::path::Path;
+
+use crate::{
+    source_file, FieldValue, TracingCallsiteKind, TracingEvent, TracingFields, TracingLevel,
+    TracingMetadata,
+};
+
+/// Builds a [`TracingEvent`] by hand, filling in whatever isn't set with
+/// defaults, so tests can fabricate events without a `tracing` dispatcher.
+///
+/// ```
+/// use tracing_bridge::{FieldValue, TracingEvent, TracingLevel};
+///
+/// let event = TracingEvent::builder()
+///     .level(TracingLevel::Warn)
+///     .target("my_app::db")
+///     .message("connection slow")
+///     .field("retries", 3)
+///     .build();
+///
+/// assert_eq!(event.fields.get("retries"), Some(&FieldValue::I64(3)));
+/// ```
+///
+/// Unless set, the level is `Info`, the target is empty, the module path,
+/// file, line, timestamps, sequence number and thread are `None`, and the
+/// event is in no spans. The metadata is named like `tracing` names events,
+/// `event file:line`, or just `event` without a location, and declares the
+/// message (if any) and each field.
+#[derive(Debug, Clone, Default)]
+pub struct TracingEventBuilder {
+    name: Option<String>,
+    target: String,
+    level: Option<TracingLevel>,
+    module_path: Option<String>,
+    file: Option<String>,
+    line: Option<u32>,
+    message: Option<String>,
+    fields: TracingFields,
+    timestamp: Option<u64>,
+    monotonic_nanos: Option<u64>,
+    seq: Option<u64>,
+    thread_id: Option<String>,
+    thread_name: Option<String>,
+    span_context: Vec<u64>,
+    current_span_id: Option<u64>,
+}
+
+impl TracingEventBuilder {
+    /// Sets the name of the event's metadata.
+    pub fn name(self, name: impl Into<String>) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..self
+        }
+    }
+
+    pub fn target(self, target: impl Into<String>) -> Self {
+        Self {
+            target: target.into(),
+            ..self
+        }
+    }
+
+    pub fn level(self, level: TracingLevel) -> Self {
+        Self {
+            level: Some(level),
+            ..self
+        }
+    }
+
+    pub fn module_path(self, module_path: impl Into<String>) -> Self {
+        Self {
+            module_path: Some(module_path.into()),
+            ..self
+        }
+    }
+
+    /// Sets the source file, normalized the same way as captured files: `\`
+    /// becomes `/` and invalid UTF-8 is replaced, so the event serializes
+    /// and deserializes the same on every platform.
+    ///
+    /// ```
+    /// use tracing_bridge::TracingEvent;
+    ///
+    /// let event = TracingEvent::builder().file(r"src\db\pool.rs").build();
+    /// assert_eq!(event.metadata.file.as_deref(), Some("src/db/pool.rs"));
+    ///
+    /// # #[cfg(unix)]
+    /// # {
+    /// use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+    ///
+    /// let event = TracingEvent::builder()
+    ///     .file(OsStr::from_bytes(b"src\\caf\xe9.rs"))
+    ///     .build();
+    /// assert_eq!(event.metadata.file.as_deref(), Some("src/caf\u{fffd}.rs"));
+    ///
+    /// # #[cfg(feature = "json")]
+    /// # {
+    /// let json = serde_json::to_string(&event).unwrap();
+    /// let decoded: TracingEvent = serde_json::from_str(&json).unwrap();
+    /// assert_eq!(decoded, event);
+    /// # }
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn file(self, file: impl AsRef<Path>) -> Self {
+        Self {
+            file: Some(source_file(file)),
+            ..self
+        }
+    }
+
+    /// Sets the source file, normalized the same way as captured files.
+    /// Without `std` there are no paths, so the file is given as a string.
+    #[cfg(not(feature = "std"))]
+    pub fn file(self, file: impl AsRef<str>) -> Self {
+        Self {
+            file: Some(source_file(file)),
+            ..self
+        }
+    }
+
+    pub fn line(self, line: u32) -> Self {
+        Self {
+            line: Some(line),
+            ..self
+        }
+    }
+
+    pub fn message(self, message: impl Into<String>) -> Self {
+        Self {
+            message: Some(message.into()),
+            ..self
+        }
+    }
+
+    /// Adds a field, or replaces the value of a field of the same name.
+    pub fn field(mut self, name: impl Into<String>, value: impl Into<FieldValue>) -> Self {
+        self.fields.insert(name.into(), value.into());
+        self
+    }
+
+    /// Adds a value for a field, keeping any values already added for a field
+    /// of the same name, as `info!(x = 1, x = 2)` records them.
+    pub fn push_field(mut self, name: impl Into<String>, value: impl Into<FieldValue>) -> Self {
+        self.fields.push(name.into(), value.into());
+        self
+    }
+
+    /// Sets the timestamp, in nanoseconds since the Unix epoch.
+    pub fn timestamp(self, timestamp: u64) -> Self {
+        Self {
+            timestamp: Some(timestamp),
+            ..self
+        }
+    }
+
+    /// Sets the monotonic timestamp, in nanoseconds since the capturing
+    /// layer's baseline. See [`TracingEvent::monotonic_nanos`].
+    pub fn monotonic_nanos(self, monotonic_nanos: u64) -> Self {
+        Self {
+            monotonic_nanos: Some(monotonic_nanos),
+            ..self
+        }
+    }
+
+    /// Sets the sequence number.
+    pub fn seq(self, seq: u64) -> Self {
+        Self {
+            seq: Some(seq),
+            ..self
+        }
+    }
+
+    /// Sets the id and name of the thread the event was emitted on.
+    pub fn thread(self, id: impl Into<String>, name: Option<String>) -> Self {
+        Self {
+            thread_id: Some(id.into()),
+            thread_name: name,
+            ..self
+        }
+    }
+
+    /// Sets the ids of the spans the event was emitted in, from the root down.
+    pub fn span_context(self, span_context: Vec<u64>) -> Self {
+        Self {
+            span_context,
+            ..self
+        }
+    }
+
+    /// Sets the id of the span that was current when the event was emitted.
+    pub fn current_span_id(self, current_span_id: u64) -> Self {
+        Self {
+            current_span_id: Some(current_span_id),
+            ..self
+        }
+    }
+
+    pub fn build(self) -> TracingEvent {
+        let name = match (self.name, &self.file, self.line) {
+            (Some(name), _, _) => name,
+            (None, Some(file), Some(line)) => format!("event {}:{}", file, line),
+            (None, _, _) => "event".to_owned(),
+        };
+        let declared_fields = self
+            .message
+            .as_ref()
+            .map(|_| "message".to_owned())
+            .into_iter()
+            .chain(self.fields.iter().map(|(name, _)| name.to_owned()))
+            .collect();
+
+        TracingEvent {
+            metadata: TracingMetadata {
+                name,
+                target: self.target,
+                level: self.level.unwrap_or(TracingLevel::Info),
+                module_path: self.module_path,
+                file: self.file,
+                line: self.line,
+                kind: TracingCallsiteKind::Event,
+                declared_fields,
+            },
+            message: self.message,
+            fields: self.fields,
+            timestamp: self.timestamp,
+            monotonic_nanos: self.monotonic_nanos,
+            seq: self.seq,
+            thread_id: self.thread_id,
+            thread_name: self.thread_name,
+            span_context: self.span_context,
+            current_span_id: self.current_span_id,
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::{
+    convert::TryFrom,
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// A source of wall-clock time for timestamping captured events.
+///
+/// [`BridgeLayer`](crate::BridgeLayer) uses [`SystemClock`] unless given
+/// another clock with
+/// [`BridgeLayer::with_clock`](crate::BridgeLayer::with_clock), so that tests
+/// and replay tools can control the timestamps events get.
+pub trait Clock: Send + Sync + 'static {
+    /// Returns the current time.
+    fn now(&self) -> SystemTime;
+}
+
+/// The system's wall clock, as returned by [`SystemTime::now`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// A clock that is stopped at a single point in time.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub SystemTime);
+
+impl Clock for FixedClock {
+    fn now(&self) -> SystemTime {
+        self.0
+    }
+}
+
+/// A clock that only moves when told to. Clones share the same time, so a
+/// test can keep one clone and advance the clock a layer is using.
+///
+/// The time is kept in nanoseconds since the Unix epoch, so it can't be set
+/// before 1970 or after 2554.
+#[derive(Clone)]
+pub struct ManualClock {
+    nanos: Arc<AtomicU64>,
+}
+
+impl ManualClock {
+    /// Returns a clock stopped at `time`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `time` can't be represented, see [`ManualClock`].
+    pub fn new(time: SystemTime) -> Self {
+        Self {
+            nanos: Arc::new(AtomicU64::new(to_nanos(time))),
+        }
+    }
+
+    /// Moves the clock to `time`, which may be earlier than its current time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `time` can't be represented, see [`ManualClock`].
+    pub fn set(&self, time: SystemTime) {
+        self.nanos.store(to_nanos(time), Ordering::SeqCst);
+    }
+
+    /// Moves the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        let nanos = u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX);
+        let _ = self
+            .nanos
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |current| {
+                Some(current.saturating_add(nanos))
+            });
+    }
+}
+
+impl Default for ManualClock {
+    /// Returns a clock stopped at the Unix epoch.
+    fn default() -> Self {
+        Self::new(UNIX_EPOCH)
+    }
+}
+
+impl fmt::Debug for ManualClock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ManualClock").field(&self.now()).finish()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_nanos(self.nanos.load(Ordering::SeqCst))
+    }
+}
+
+/// The moment [`TracingEvent::monotonic_nanos`](crate::TracingEvent::monotonic_nanos)
+/// is measured from, read from both the monotonic and the system clock, so
+/// that monotonic timestamps can be converted to wall-clock times.
+///
+/// Each [`BridgeLayer`](crate::BridgeLayer) and
+/// [`BridgeSubscriber`](crate::BridgeSubscriber) reads its baseline once,
+/// when it is created. The baseline always uses the system clock, even for a
+/// layer given another clock with
+/// [`BridgeLayer::with_clock`](crate::BridgeLayer::with_clock).
+#[derive(Debug, Clone, Copy)]
+pub struct MonotonicBaseline {
+    instant: Instant,
+    system_time: SystemTime,
+}
+
+impl MonotonicBaseline {
+    pub(crate) fn now() -> Self {
+        Self {
+            instant: Instant::now(),
+            system_time: SystemTime::now(),
+        }
+    }
+
+    /// The baseline, as read from the monotonic clock.
+    pub fn instant(&self) -> Instant {
+        self.instant
+    }
+
+    /// The baseline, as read from the system clock at the same moment.
+    pub fn system_time(&self) -> SystemTime {
+        self.system_time
+    }
+
+    /// Converts a monotonic timestamp into the wall-clock time it
+    /// corresponds to, assuming the system clock hasn't been adjusted since
+    /// the baseline was read.
+    pub fn to_system_time(&self, monotonic_nanos: u64) -> SystemTime {
+        self.system_time + Duration::from_nanos(monotonic_nanos)
+    }
+
+    /// Converts a monotonic timestamp back into an [`Instant`].
+    pub fn to_instant(&self, monotonic_nanos: u64) -> Instant {
+        self.instant + Duration::from_nanos(monotonic_nanos)
+    }
+
+    /// Returns the nanoseconds from the baseline to now.
+    pub(crate) fn elapsed_nanos(&self) -> u64 {
+        u64::try_from(self.instant.elapsed().as_nanos()).unwrap_or(u64::MAX)
+    }
+}
+
+fn to_nanos(time: SystemTime) -> u64 {
+    crate::unix_nanos(time).expect("time should be between 1970 and 2554")
+}
@@ -0,0 +1,88 @@
+use serde_json::{json, Map, Value};
+
+use crate::{time::Rfc3339, FieldValue, TracingEvent};
+
+/// The version of the Elastic Common Schema that [`TracingEvent::to_ecs_value`]
+/// follows.
+pub const ECS_VERSION: &str = "8.11.0";
+
+impl TracingEvent {
+    /// Converts the event into a JSON document following the
+    /// [Elastic Common Schema](https://www.elastic.co/guide/en/ecs/current/index.html),
+    /// so it can be indexed into Elasticsearch without custom mappings:
+    ///
+    /// - `@timestamp`: the timestamp in RFC 3339 format, to the millisecond
+    /// - `message`: the event's message
+    /// - `log.level`: the level in lowercase, e.g. `"warn"`
+    /// - `log.logger`: the target
+    /// - `log.origin.file.name` and `log.origin.file.line`: the source location
+    /// - `process.thread.name`: the name of the emitting thread
+    /// - `labels`: the fields, as strings
+    /// - `ecs.version`: [`ECS_VERSION`]
+    ///
+    /// ECS labels are flat keyword values, so every field value is
+    /// written as a string (see [`FieldValue`]'s `Display` impl, except that
+    /// strings are not quoted), and dots in field names are replaced with
+    /// underscores. Only the first value of a field recorded more than once
+    /// is kept, as is the first of two fields whose names only differ in
+    /// dots and underscores. Properties without a value are left out.
+    pub fn to_ecs_value(&self) -> Value {
+        let mut document = Map::new();
+        if let Some(timestamp) = self.timestamp {
+            let timestamp = Rfc3339 {
+                nanos: timestamp,
+                digits: 3,
+            }
+            .to_string();
+            document.insert("@timestamp".to_owned(), json!(timestamp));
+        }
+        if let Some(message) = &self.message {
+            document.insert("message".to_owned(), json!(message));
+        }
+
+        let mut log = Map::new();
+        log.insert(
+            "level".to_owned(),
+            json!(self.metadata.level.as_str().to_ascii_lowercase()),
+        );
+        log.insert("logger".to_owned(), json!(self.metadata.target));
+        let mut file = Map::new();
+        if let Some(name) = &self.metadata.file {
+            file.insert("name".to_owned(), json!(name));
+        }
+        if let Some(line) = self.metadata.line {
+            file.insert("line".to_owned(), json!(line));
+        }
+        if !file.is_empty() {
+            log.insert("origin".to_owned(), json!({ "file": file }));
+        }
+        document.insert("log".to_owned(), Value::Object(log));
+
+        if let Some(thread_name) = &self.thread_name {
+            document.insert(
+                "process".to_owned(),
+                json!({ "thread": { "name": thread_name } }),
+            );
+        }
+
+        if !self.fields.is_empty() {
+            let mut labels = Map::new();
+            for (name, value) in self.fields.iter() {
+                labels
+                    .entry(name.replace('.', "_"))
+                    .or_insert_with(|| json!(label_value(value)));
+            }
+            document.insert("labels".to_owned(), Value::Object(labels));
+        }
+
+        document.insert("ecs".to_owned(), json!({ "version": ECS_VERSION }));
+        Value::Object(document)
+    }
+}
+
+fn label_value(value: &FieldValue) -> String {
+    match value {
+        FieldValue::Str(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
@@ -0,0 +1,39 @@
+#[cfg(any(feature = "bincode", feature = "msgpack"))]
+use crate::TracingEvent;
+
+#[cfg(feature = "bincode")]
+impl TracingEvent {
+    /// Encodes the event with `bincode`'s default configuration.
+    ///
+    /// The encoding is independent of the platform it is produced on:
+    /// integers are little-endian and fixed-width, floats are written as their
+    /// IEEE 754 bits, and file paths are written as `/`-separated strings.
+    pub fn to_bincode(&self) -> Result<Vec<u8>, bincode::Error> {
+        bincode::serialize(self)
+    }
+
+    /// Decodes an event previously encoded with [`TracingEvent::to_bincode`].
+    pub fn from_bincode(bytes: &[u8]) -> Result<Self, bincode::Error> {
+        bincode::deserialize(bytes)
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl TracingEvent {
+    /// Encodes the event as MessagePack.
+    ///
+    /// Structs are written as maps keyed by field name, so decoders don't
+    /// depend on field order and tolerate fields being added. Each
+    /// [`FieldValue`](crate::FieldValue) is written as a single-entry map from
+    /// its variant name to its value, e.g. `{"I64": 5}` or
+    /// `{"Error": {"message": ..., "causes": [...]}}`, and levels and kinds
+    /// are written as their variant names.
+    pub fn to_msgpack(&self) -> Result<Vec<u8>, rmp_serde::encode::Error> {
+        rmp_serde::to_vec_named(self)
+    }
+
+    /// Decodes an event previously encoded with [`TracingEvent::to_msgpack`].
+    pub fn from_msgpack(bytes: &[u8]) -> Result<Self, rmp_serde::decode::Error> {
+        rmp_serde::from_slice(bytes)
+    }
+}
@@ -0,0 +1,729 @@
+use std::{cell::Cell, fmt, thread::Thread};
+
+use serde::{
+    ser::{SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant},
+    Serialize, Serializer,
+};
+use tracing_core::{field::Visit, Event, Field, Metadata};
+
+use crate::{
+    escape_control, float, DebugFormat, FieldOptions, FieldValue, Location, TracingCallsiteKind,
+    TracingEvent, TracingLevel, TracingMetadataFields,
+};
+
+static DEFAULT_OPTIONS: FieldOptions = FieldOptions {
+    max_field_len: None,
+    redactor: None,
+    debug_format: DebugFormat::Compact,
+    escape_control: false,
+};
+
+/// A captured event that borrows the `tracing` event it was captured from,
+/// instead of copying its metadata and fields into a [`TracingEvent`].
+///
+/// It serializes exactly like the [`TracingEvent`] that
+/// [`to_owned`](Self::to_owned) would return, so it can be deserialized as
+/// one, but it formats field values straight into the serializer rather
+/// than into intermediate `String`s. Sinks that serialize events
+/// synchronously can accept it through [`EventSink::emit_ref`] and save
+/// converting every event.
+///
+/// [`EventSink::emit_ref`]: crate::EventSink::emit_ref
+pub struct TracingEventRef<'a> {
+    event: &'a Event<'a>,
+    options: &'a FieldOptions,
+    pub(crate) timestamp: Option<u64>,
+    pub(crate) monotonic_nanos: Option<u64>,
+    pub(crate) seq: Option<u64>,
+    pub(crate) thread: Option<Thread>,
+    pub(crate) span_context: Vec<u64>,
+    pub(crate) current_span_id: Option<u64>,
+    pub(crate) location: Option<Location>,
+    pub(crate) target: Option<String>,
+}
+
+impl<'a> TracingEventRef<'a> {
+    /// Borrows `event`, with no timestamp, sequence number, thread or span
+    /// context, like `TracingEvent::from(event)`.
+    pub fn new(event: &'a Event<'a>) -> Self {
+        Self::with_options(event, &DEFAULT_OPTIONS)
+    }
+
+    pub(crate) fn with_options(event: &'a Event<'a>, options: &'a FieldOptions) -> Self {
+        Self {
+            event,
+            options,
+            timestamp: None,
+            monotonic_nanos: None,
+            seq: None,
+            thread: None,
+            span_context: Vec::new(),
+            current_span_id: None,
+            location: None,
+            target: None,
+        }
+    }
+
+    /// Returns the `tracing` event this borrows.
+    pub fn event(&self) -> &'a Event<'a> {
+        self.event
+    }
+
+    pub fn metadata(&self) -> &'static Metadata<'static> {
+        self.event.metadata()
+    }
+
+    /// The event's target, as the layer rewrote it if it was given a
+    /// [target rewrite](crate::BridgeLayer::with_target_rewrite), which
+    /// [`metadata`](Self::metadata) doesn't reflect.
+    pub fn target(&self) -> &str {
+        self.target
+            .as_deref()
+            .unwrap_or_else(|| self.metadata().target())
+    }
+
+    /// When the event was captured, in nanoseconds since the Unix epoch.
+    pub fn timestamp(&self) -> Option<u64> {
+        self.timestamp
+    }
+
+    /// When the event was captured, in nanoseconds since the capturing
+    /// layer's baseline. See [`TracingEvent::monotonic_nanos`].
+    pub fn monotonic_nanos(&self) -> Option<u64> {
+        self.monotonic_nanos
+    }
+
+    /// The event's sequence number. See [`TracingEvent::seq`].
+    pub fn seq(&self) -> Option<u64> {
+        self.seq
+    }
+
+    /// The ids of the spans the event was emitted in, from the root down.
+    pub fn span_context(&self) -> &[u64] {
+        &self.span_context
+    }
+
+    /// The id of the span that was current when the event was emitted. See
+    /// [`TracingEvent::current_span_id`].
+    pub fn current_span_id(&self) -> Option<u64> {
+        self.current_span_id
+    }
+
+    /// Converts the event into an owned [`TracingEvent`].
+    pub fn to_owned(&self) -> TracingEvent {
+        let mut event = TracingEvent::capture(self.event, self.options);
+        if let Some(location) = &self.location {
+            event.metadata.relocate(location);
+        }
+        if let Some(target) = &self.target {
+            event.metadata.target = target.clone();
+        }
+        event.timestamp = self.timestamp;
+        event.monotonic_nanos = self.monotonic_nanos;
+        event.seq = self.seq;
+        if let Some(thread) = &self.thread {
+            event.thread_id = Some(format!("{:?}", thread.id()));
+            event.thread_name = thread.name().map(str::to_owned);
+        }
+        event.span_context = self.span_context.clone();
+        event.current_span_id = self.current_span_id;
+        event
+    }
+}
+
+impl fmt::Debug for TracingEventRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TracingEventRef")
+            .field("event", self.event)
+            .field("timestamp", &self.timestamp)
+            .field("monotonic_nanos", &self.monotonic_nanos)
+            .field("seq", &self.seq)
+            .field("thread", &self.thread)
+            .field("span_context", &self.span_context)
+            .field("current_span_id", &self.current_span_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Serialize for TracingEventRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let fields = FieldsRef {
+            event: self.event,
+            options: self.options,
+        };
+        let thread_id = self.thread.as_ref().map(|thread| DebugStr(thread.id()));
+
+        let mut state = serializer.serialize_struct("TracingEvent", 10)?;
+        let metadata = MetadataRef {
+            metadata: self.metadata(),
+            location: self.location.as_ref(),
+            target: self.target(),
+        };
+        state.serialize_field("metadata", &metadata)?;
+        state.serialize_field("message", &MessageRef(fields))?;
+        state.serialize_field("fields", &fields)?;
+        state.serialize_field("timestamp", &self.timestamp)?;
+        state.serialize_field("monotonic_nanos", &self.monotonic_nanos)?;
+        state.serialize_field("seq", &self.seq)?;
+        state.serialize_field("thread_id", &thread_id)?;
+        state.serialize_field("thread_name", &self.thread.as_ref().and_then(Thread::name))?;
+        state.serialize_field("span_context", &self.span_context)?;
+        state.serialize_field("current_span_id", &self.current_span_id)?;
+        state.end()
+    }
+}
+
+/// Serializes `tracing` metadata like the [`TracingMetadata`] converted from
+/// it, with its location replaced by `location` if there is one, and its
+/// target by `target`.
+struct MetadataRef<'a> {
+    metadata: &'static Metadata<'static>,
+    location: Option<&'a Location>,
+    target: &'a str,
+}
+
+impl Serialize for MetadataRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let metadata = self.metadata;
+        let (module_path, file, line) = match self.location {
+            Some(location) => (
+                location.module_path.as_deref(),
+                location.file.as_deref(),
+                location.line,
+            ),
+            None => (metadata.module_path(), metadata.file(), metadata.line()),
+        };
+        let mut state = serializer.serialize_struct("TracingMetadata", 8)?;
+        state.serialize_field("name", metadata.name())?;
+        state.serialize_field("target", self.target)?;
+        state.serialize_field("level", &TracingLevel::from(metadata.level()))?;
+        state.serialize_field("module_path", &module_path)?;
+        state.serialize_field("file", &file.map(SourceFile))?;
+        state.serialize_field("line", &line)?;
+        state.serialize_field("kind", &TracingCallsiteKind::of(metadata))?;
+        state.serialize_field("declared_fields", &DeclaredFields(metadata))?;
+        state.end()
+    }
+}
+
+/// Serializes a source file path normalized like
+/// [`TracingMetadata::file`](crate::TracingMetadata::file).
+struct SourceFile<'a>(&'a str);
+
+impl Serialize for SourceFile<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.0.contains('\\') {
+            serializer.collect_str(&crate::source_file(self.0))
+        } else {
+            serializer.serialize_str(self.0)
+        }
+    }
+}
+
+struct DeclaredFields(&'static Metadata<'static>);
+
+impl Serialize for DeclaredFields {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let fields = self.0.fields();
+        let mut seq = serializer.serialize_seq(Some(fields.len()))?;
+        for field in fields {
+            seq.serialize_element(field.name())?;
+        }
+        seq.end()
+    }
+}
+
+/// The fields of an event, serialized like [`TracingFields`](crate::TracingFields)
+/// by visiting the event, first to count them and then to write them.
+#[derive(Clone, Copy)]
+struct FieldsRef<'a> {
+    event: &'a Event<'a>,
+    options: &'a FieldOptions,
+}
+
+impl Serialize for FieldsRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut count = FieldVisitor::new(self.options, |_, _| Ok::<_, S::Error>(()));
+        self.event.record(&mut count);
+
+        let mut map = serializer.serialize_map(Some(count.fields))?;
+        let mut write = FieldVisitor::new(self.options, |name, value| {
+            map.serialize_entry(name, &value)
+        });
+        self.event.record(&mut write);
+        write.result?;
+        map.end()
+    }
+}
+
+/// The message of an event, serialized like [`TracingEvent::message`] by
+/// visiting the event until the message is found.
+struct MessageRef<'a>(FieldsRef<'a>);
+
+impl Serialize for MessageRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut visitor = MessageVisitor {
+            serializer: Some(serializer),
+            options: self.0.options,
+            result: None,
+        };
+        self.0.event.record(&mut visitor);
+        match (visitor.result, visitor.serializer) {
+            (Some(result), _) => result,
+            (None, Some(serializer)) => serializer.serialize_none(),
+            (None, None) => unreachable!("the serializer is only taken to produce a result"),
+        }
+    }
+}
+
+/// Passes each field that isn't the message to `on_field`, stopping at the
+/// first error.
+struct FieldVisitor<'a, F, E> {
+    options: &'a FieldOptions,
+    on_field: F,
+    fields: usize,
+    result: Result<(), E>,
+}
+
+impl<'a, F, E> FieldVisitor<'a, F, E>
+where
+    F: FnMut(&str, ValueRef<'_>) -> Result<(), E>,
+{
+    fn new(options: &'a FieldOptions, on_field: F) -> Self {
+        Self {
+            options,
+            on_field,
+            fields: 0,
+            result: Ok(()),
+        }
+    }
+
+    fn field(&mut self, field: &Field, value: ValueRef<'_>) {
+        if self.result.is_err() {
+            return;
+        }
+        self.fields += 1;
+        if self.options.redactor.is_some() {
+            // Redactors rewrite owned values, so build the value `capture`
+            // would store, redacted, and write that instead.
+            let mut owned = TracingMetadataFields::new(self.options, true);
+            value.record(field, &mut owned);
+            if let Some((_, value)) = owned.fields.iter().next() {
+                self.result = (self.on_field)(field.name(), ValueRef::Owned(value.clone()));
+            }
+            return;
+        }
+        self.result = (self.on_field)(field.name(), value.bounded(self.options));
+    }
+}
+
+impl<F, E> Visit for FieldVisitor<'_, F, E>
+where
+    F: FnMut(&str, ValueRef<'_>) -> Result<(), E>,
+{
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.field(field, ValueRef::I64(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.field(field, ValueRef::U64(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.field(field, ValueRef::F64(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.field(field, ValueRef::Bool(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() != "message" {
+            self.field(field, ValueRef::Str(value, Bounds::NONE));
+        }
+    }
+
+    fn record_error(&mut self, field: &Field, value: &(dyn std::error::Error + 'static)) {
+        self.field(field, ValueRef::Error(value, Bounds::NONE));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() != "message" {
+            self.field(
+                field,
+                ValueRef::Debug(value, DebugFormat::Compact, Bounds::NONE),
+            );
+        }
+    }
+
+    #[cfg(all(tracing_unstable, feature = "valuable"))]
+    fn record_value(&mut self, field: &Field, value: valuable::Value<'_>) {
+        // Converting nested values allocates anyway, so reuse the owned
+        // conversion, which also decides whether this is the message.
+        let mut owned = TracingMetadataFields::new(self.options, true);
+        owned.record_value(field, value);
+        if let Some((_, value)) = owned.fields.iter().next() {
+            self.field(field, ValueRef::Owned(value.clone()));
+        }
+    }
+}
+
+/// Serializes the first `message` field recorded as a string or `Debug`
+/// value, which is the one `tracing`'s macros record.
+struct MessageVisitor<'a, S: Serializer> {
+    serializer: Option<S>,
+    options: &'a FieldOptions,
+    result: Option<Result<S::Ok, S::Error>>,
+}
+
+impl<S: Serializer> MessageVisitor<'_, S> {
+    fn message(&mut self, field: &Field, value: ValueRef<'_>) {
+        if field.name() != "message" {
+            return;
+        }
+        if let Some(serializer) = self.serializer.take() {
+            let value = value.bounded(self.options);
+            self.result = Some(serializer.serialize_some(&Text(&value)));
+        }
+    }
+}
+
+impl<S: Serializer> Visit for MessageVisitor<'_, S> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.message(field, ValueRef::Str(value, Bounds::NONE));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.message(
+            field,
+            ValueRef::Debug(value, DebugFormat::Compact, Bounds::NONE),
+        );
+    }
+
+    #[cfg(all(tracing_unstable, feature = "valuable"))]
+    fn record_value(&mut self, field: &Field, value: valuable::Value<'_>) {
+        let mut owned = TracingMetadataFields::new(self.options, true);
+        owned.record_value(field, value);
+        if let Some(message) = &owned.message {
+            self.message(field, ValueRef::Str(message, Bounds::NONE));
+        }
+    }
+}
+
+/// A borrowed field value, serialized like the [`FieldValue`] it would be
+/// captured as. String, `Debug` and error values carry the bounds their
+/// text is written within.
+enum ValueRef<'v> {
+    I64(i64),
+    U64(u64),
+    F64(f64),
+    Bool(bool),
+    Str(&'v str, Bounds),
+    Debug(&'v dyn fmt::Debug, DebugFormat, Bounds),
+    Error(&'v (dyn std::error::Error + 'static), Bounds),
+    Owned(FieldValue),
+}
+
+/// The length text is truncated to, and whether its control characters are
+/// escaped, as [`FieldOptions`] says.
+#[derive(Clone, Copy)]
+struct Bounds {
+    max_len: Option<usize>,
+    escape_control: bool,
+}
+
+impl Bounds {
+    /// Writes text as it is.
+    const NONE: Self = Self {
+        max_len: None,
+        escape_control: false,
+    };
+}
+
+impl ValueRef<'_> {
+    /// Applies the truncation, escaping and formatting of `options`.
+    fn bounded(self, options: &FieldOptions) -> Self {
+        let bounds = Bounds {
+            max_len: options.max_field_len,
+            escape_control: options.escape_control,
+        };
+        match self {
+            Self::Str(value, _) => Self::Str(value, bounds),
+            Self::Debug(value, _, _) => Self::Debug(value, options.debug_format, bounds),
+            Self::Error(value, _) => Self::Error(value, bounds),
+            value => value,
+        }
+    }
+
+    /// Returns whether a string or `Debug` value is longer than its bounds
+    /// allow, and so is captured as a [`FieldValue::Truncated`]. `Debug`
+    /// values are formatted to find out, but only until they pass the limit.
+    fn overflows(&self) -> bool {
+        let bounds = match self {
+            Self::Str(_, bounds) | Self::Debug(_, _, bounds) => bounds,
+            _ => return false,
+        };
+        let limit = match bounds.max_len {
+            Some(max_len) => max_len,
+            None => return false,
+        };
+        let mut measure = Measure {
+            len: 0,
+            limit,
+            escape_control: bounds.escape_control,
+        };
+        let _ = match self {
+            Self::Str(value, _) => fmt::Write::write_str(&mut measure, value),
+            Self::Debug(value, DebugFormat::Compact, _) => {
+                fmt::write(&mut measure, format_args!("{:?}", value))
+            }
+            Self::Debug(value, DebugFormat::Pretty, _) => {
+                fmt::write(&mut measure, format_args!("{:#?}", value))
+            }
+            _ => Ok(()),
+        };
+        measure.len > limit
+    }
+
+    /// Records the value into `visitor` as the `tracing` value it came from.
+    fn record(&self, field: &Field, visitor: &mut dyn Visit) {
+        match self {
+            Self::I64(value) => visitor.record_i64(field, *value),
+            Self::U64(value) => visitor.record_u64(field, *value),
+            Self::F64(value) => visitor.record_f64(field, *value),
+            Self::Bool(value) => visitor.record_bool(field, *value),
+            Self::Str(value, _) => visitor.record_str(field, value),
+            Self::Debug(value, _, _) => visitor.record_debug(field, *value),
+            Self::Error(value, _) => visitor.record_error(field, *value),
+            Self::Owned(_) => unreachable!("owned values are never recorded again"),
+        }
+    }
+}
+
+impl Serialize for ValueRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::I64(value) => serializer.serialize_newtype_variant("FieldValue", 0, "I64", value),
+            Self::U64(value) => serializer.serialize_newtype_variant("FieldValue", 1, "U64", value),
+            Self::F64(value) => {
+                serializer.serialize_newtype_variant("FieldValue", 2, "F64", &Float(*value))
+            }
+            Self::Bool(value) => {
+                serializer.serialize_newtype_variant("FieldValue", 3, "Bool", value)
+            }
+            Self::Str(..) | Self::Debug(..) if self.overflows() => {
+                let kept = Kept {
+                    value: self,
+                    original_len: Cell::new(0),
+                };
+                let mut state =
+                    serializer.serialize_struct_variant("FieldValue", 7, "Truncated", 2)?;
+                state.serialize_field("value", &kept)?;
+                state.serialize_field("original_len", &kept.original_len.get())?;
+                state.end()
+            }
+            Self::Str(..) => {
+                serializer.serialize_newtype_variant("FieldValue", 4, "Str", &Text(self))
+            }
+            Self::Debug(..) => {
+                serializer.serialize_newtype_variant("FieldValue", 5, "Debug", &Text(self))
+            }
+            Self::Error(error, bounds) => {
+                let mut state = serializer.serialize_struct_variant("FieldValue", 6, "Error", 2)?;
+                state.serialize_field("message", &Text(&ValueRef::Error(*error, *bounds)))?;
+                state.serialize_field("causes", &Causes(*error, *bounds))?;
+                state.end()
+            }
+            Self::Owned(value) => value.serialize(serializer),
+        }
+    }
+}
+
+/// Serializes a value's `Debug` output as a string.
+struct DebugStr<T>(T);
+
+impl<T: fmt::Debug> Serialize for DebugStr<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{:?}", self.0))
+    }
+}
+
+struct Float(f64);
+
+impl Serialize for Float {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        float::serialize(&self.0, serializer)
+    }
+}
+
+/// The messages of an error's causes, each written within the same bounds.
+struct Causes<'v>(&'v (dyn std::error::Error + 'static), Bounds);
+
+impl Serialize for Causes<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let causes = || std::iter::successors(self.0.source(), |cause| cause.source());
+        let mut seq = serializer.serialize_seq(Some(causes().count()))?;
+        for cause in causes() {
+            seq.serialize_element(&Text(&ValueRef::Error(cause, self.1)))?;
+        }
+        seq.end()
+    }
+}
+
+/// Serializes the text of a string, `Debug` value or error (just its own
+/// message, not its causes) as a string, truncated the way
+/// [`FieldOptions`] truncates captured values but without buffering it.
+struct Text<'r, 'v>(&'r ValueRef<'v>);
+
+impl Serialize for Text<'_, '_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl fmt::Display for Text<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (_, dropped) = write_text(self.0, f)?;
+        if dropped > 0 {
+            write!(f, "… (truncated {} bytes)", dropped)?;
+        }
+        Ok(())
+    }
+}
+
+/// Serializes the part of a string or `Debug` value that fits within its
+/// bounds, as the `value` of a [`FieldValue::Truncated`], and records the
+/// length of the whole value while doing so.
+struct Kept<'r, 'v> {
+    value: &'r ValueRef<'v>,
+    original_len: Cell<usize>,
+}
+
+impl Serialize for Kept<'_, '_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl fmt::Display for Kept<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (kept, dropped) = write_text(self.value, f)?;
+        self.original_len.set(kept + dropped);
+        Ok(())
+    }
+}
+
+/// Writes as much of the text of `value` as fits within its bounds to `f`,
+/// returning how many bytes were written and how many were dropped.
+fn write_text(
+    value: &ValueRef<'_>,
+    f: &mut fmt::Formatter<'_>,
+) -> Result<(usize, usize), fmt::Error> {
+    let bounds = match value {
+        ValueRef::Str(_, bounds) | ValueRef::Debug(_, _, bounds) | ValueRef::Error(_, bounds) => {
+            *bounds
+        }
+        _ => unreachable!("only strings, `Debug` values and errors are text"),
+    };
+    let mut writer = Truncate {
+        f,
+        room: bounds.max_len.unwrap_or(usize::MAX),
+        written: 0,
+        dropped: 0,
+        failed: false,
+        escape_control: bounds.escape_control,
+    };
+
+    // Like `FieldOptions`, ignore errors from the value's own formatting
+    // and keep whatever it wrote.
+    let _ = match value {
+        ValueRef::Str(value, _) => fmt::Write::write_str(&mut writer, value),
+        ValueRef::Debug(value, DebugFormat::Compact, _) => {
+            fmt::write(&mut writer, format_args!("{:?}", value))
+        }
+        ValueRef::Debug(value, DebugFormat::Pretty, _) => {
+            fmt::write(&mut writer, format_args!("{:#?}", value))
+        }
+        ValueRef::Error(value, _) => fmt::write(&mut writer, format_args!("{}", value)),
+        _ => Ok(()),
+    };
+    if writer.failed {
+        return Err(fmt::Error);
+    }
+    Ok((writer.written, writer.dropped))
+}
+
+/// Writes to a formatter until `room` bytes have been written, then counts
+/// what it drops, like the `BoundedString` that owned values are captured
+/// into.
+struct Truncate<'a, 'b> {
+    f: &'a mut fmt::Formatter<'b>,
+    room: usize,
+    written: usize,
+    dropped: usize,
+    /// Whether the formatter itself failed, as opposed to the value.
+    failed: bool,
+    escape_control: bool,
+}
+
+impl Truncate<'_, '_> {
+    fn push(&mut self, s: &str) -> fmt::Result {
+        // Once anything has been dropped nothing more is kept, so the kept
+        // part is always a prefix of the full value.
+        let room = if self.dropped > 0 { 0 } else { self.room };
+
+        // Cut at a char boundary so multibyte characters are never split.
+        let mut end = room.min(s.len());
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        if self.f.write_str(&s[..end]).is_err() {
+            self.failed = true;
+            return Err(fmt::Error);
+        }
+        self.room -= end;
+        self.written += end;
+        self.dropped += s.len() - end;
+        Ok(())
+    }
+}
+
+impl fmt::Write for Truncate<'_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.escape_control {
+            escape_control(s, |s| self.push(s))
+        } else {
+            self.push(s)
+        }
+    }
+}
+
+/// Counts the bytes a value would be captured as, with control characters
+/// escaped if asked, giving up as soon as there are more than `limit`.
+struct Measure {
+    len: usize,
+    limit: usize,
+    escape_control: bool,
+}
+
+impl Measure {
+    fn add(&mut self, s: &str) -> fmt::Result {
+        self.len += s.len();
+        if self.len > self.limit {
+            Err(fmt::Error)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl fmt::Write for Measure {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.escape_control {
+            escape_control(s, |s| self.add(s))
+        } else {
+            self.add(s)
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use std::sync::Arc;
+
+use arc_swap::{ArcSwap, Guard};
+use tracing_core::{callsite, LevelFilter, Metadata};
+
+use crate::{TracingFields, TracingLevel, TracingMetadata};
+
+/// Decides which callsites a [`BridgeLayer`](crate::BridgeLayer) captures,
+/// using only their metadata so rejected events are never converted.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct FilterConfig {
+    /// The least severe level that is captured, or `None` to capture all.
+    pub min_level: Option<TracingLevel>,
+
+    /// If non-empty, only targets matching one of these prefixes are captured.
+    pub target_allow: Vec<String>,
+
+    /// Targets matching any of these prefixes are never captured, even if
+    /// they are also allowed.
+    pub target_deny: Vec<String>,
+
+    /// Whether nothing at all is captured, whatever the other settings say,
+    /// as for [`LevelFilter::OFF`].
+    pub disabled: bool,
+}
+
+impl FilterConfig {
+    pub(crate) fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        self.allows(metadata.level().into(), metadata.target())
+    }
+
+    /// Sets the level filter: [`LevelFilter::OFF`] disables the config, and
+    /// any other level enables it with that level as the minimum.
+    pub fn set_level_filter(&mut self, level_filter: LevelFilter) {
+        match level_filter.into_level() {
+            Some(level) => {
+                self.min_level = Some((&level).into());
+                self.disabled = false;
+            }
+            None => self.disabled = true,
+        }
+    }
+
+    /// Returns the most verbose level the config may capture, as a hint for
+    /// `tracing`.
+    pub(crate) fn level_hint(&self) -> Option<LevelFilter> {
+        if self.disabled {
+            return Some(LevelFilter::OFF);
+        }
+        let min_level = self.min_level?;
+        Some(LevelFilter::from_level((&min_level).into()))
+    }
+
+    /// Returns whether the config captures events with `metadata`, for
+    /// filtering events that have already been captured, such as in a
+    /// [`FanOutSink`](crate::FanOutSink).
+    pub fn matches(&self, metadata: &TracingMetadata) -> bool {
+        self.allows(metadata.level, &metadata.target)
+    }
+
+    /// Returns whether the config captures events at `level` with `target`,
+    /// for events whose target was rewritten after their metadata was
+    /// created.
+    pub(crate) fn allows(&self, level: TracingLevel, target: &str) -> bool {
+        !self.disabled
+            && self.min_level.is_none_or(|min_level| level >= min_level)
+            && self.target_enabled(target)
+    }
+
+    fn target_enabled(&self, target: &str) -> bool {
+        let matches = |prefix: &String| target_matches(target, prefix);
+        (self.target_allow.is_empty() || self.target_allow.iter().any(matches))
+            && !self.target_deny.iter().any(matches)
+    }
+}
+
+/// Converts `tracing`'s level filter into a config that captures every
+/// target at that level or more severe, or nothing for [`LevelFilter::OFF`].
+impl From<LevelFilter> for FilterConfig {
+    fn from(level_filter: LevelFilter) -> Self {
+        let mut config = Self::default();
+        config.set_level_filter(level_filter);
+        config
+    }
+}
+
+/// A handle to the [`FilterConfig`] of a [`BridgeLayer`](crate::BridgeLayer),
+/// returned by [`BridgeLayer::filter_handle`](crate::BridgeLayer::filter_handle),
+/// for changing what the layer captures while it is running.
+///
+/// Reading the config in the layer never locks: a new config replaces the
+/// old one atomically, and events already being captured finish with the
+/// config they started with. Replacing the config also clears `tracing`'s
+/// cache of which callsites are enabled, so that layers using
+/// [`with_global_filtering`](crate::BridgeLayer::with_global_filtering) pick
+/// it up.
+#[derive(Debug, Clone, Default)]
+pub struct FilterHandle {
+    config: Arc<ArcSwap<FilterConfig>>,
+}
+
+impl FilterHandle {
+    /// Returns the config currently in use.
+    pub fn get(&self) -> Arc<FilterConfig> {
+        self.config.load_full()
+    }
+
+    /// Replaces the config, affecting every event and span captured from
+    /// now on. Spans that are already open keep being reported according to
+    /// whether they were captured when they were created.
+    pub fn set(&self, config: FilterConfig) {
+        self.config.store(Arc::new(config));
+        callsite::rebuild_interest_cache();
+    }
+
+    /// Replaces the config with a modified copy of the current one.
+    pub fn update<F>(&self, f: F)
+    where
+        F: Fn(&mut FilterConfig),
+    {
+        self.config.rcu(|config| {
+            let mut config = FilterConfig::clone(config);
+            f(&mut config);
+            config
+        });
+        callsite::rebuild_interest_cache();
+    }
+
+    pub(crate) fn load(&self) -> Guard<Arc<FilterConfig>> {
+        self.config.load()
+    }
+}
+
+/// Decides which events a [`BridgeLayer`](crate::BridgeLayer) captures based
+/// on their fields, after the level and target filters have passed them.
+#[derive(Clone, Default)]
+pub(crate) struct FieldFilter {
+    /// Fields an event must have a value for.
+    pub required: Vec<String>,
+
+    /// Called with the converted fields of each event that has all the
+    /// required fields.
+    pub predicate: Option<FieldPredicate>,
+}
+
+type FieldPredicate = Arc<dyn Fn(&TracingFields) -> bool + Send + Sync>;
+
+impl FieldFilter {
+    /// Returns whether the filter accepts every event.
+    pub fn is_empty(&self) -> bool {
+        self.required.is_empty() && self.predicate.is_none()
+    }
+
+    /// Returns whether the callsite declares every required field, which is
+    /// checked before the event is converted.
+    pub fn declared(&self, metadata: &Metadata<'_>) -> bool {
+        self.required
+            .iter()
+            .all(|name| metadata.fields().field(name).is_some())
+    }
+
+    /// Returns whether the converted fields have a value for every required
+    /// field and pass the predicate.
+    pub fn matches(&self, fields: &TracingFields) -> bool {
+        self.required.iter().all(|name| fields.get(name).is_some())
+            && self
+                .predicate
+                .as_ref()
+                .is_none_or(|predicate| predicate(fields))
+    }
+}
+
+/// Returns whether `target` is `prefix` or a module path nested inside it, so
+/// that `my_app` matches `my_app::db` but not `my_app_other`.
+fn target_matches(target: &str, prefix: &str) -> bool {
+    match target.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with("::") || prefix.ends_with("::"),
+        None => false,
+    }
+}
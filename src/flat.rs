@@ -0,0 +1,177 @@
+use alloc::vec::Vec;
+
+use serde::{
+    ser::{SerializeMap, SerializeStruct},
+    Serialize, Serializer,
+};
+
+use crate::{float, FieldValue, TracingEvent, TracingFields};
+
+/// The keys a [`TracingEventFlat`] writes for the event's metadata and other
+/// properties, in the order it writes them.
+const RESERVED: [&str; 16] = [
+    "level",
+    "target",
+    "name",
+    "module_path",
+    "file",
+    "line",
+    "kind",
+    "declared_fields",
+    "message",
+    "timestamp",
+    "monotonic_nanos",
+    "seq",
+    "thread_id",
+    "thread_name",
+    "span_context",
+    "current_span_id",
+];
+
+/// Serializes a [`TracingEvent`] as a single flat map, with the fields
+/// alongside the event's metadata rather than nested under `fields`.
+/// Returned by [`TracingEvent::flat`].
+///
+/// The map holds `level`, `target`, `name`, `module_path`, `file`, `line`,
+/// `kind`, `declared_fields`, `message`, `timestamp`, `monotonic_nanos`,
+/// `seq`, `thread_id`, `thread_name`, `span_context` and `current_span_id`,
+/// followed by the fields in the order they were first recorded. Field values
+/// are written plainly, as numbers, booleans or strings, like
+/// `FieldValue::to_json_value` does, except that non-finite floats are
+/// written as the strings `"NaN"`, `"inf"` and `"-inf"`. A name recorded more
+/// than once is written once, with an array of its values.
+///
+/// A field whose name is one of the keys above is written with `fields.`
+/// prepended, so a field named `level` becomes `fields.level` and never
+/// replaces the event's level. A field whose name already starts with
+/// `fields.` gets the prefix too, so `fields.level` becomes
+/// `fields.fields.level` and no two fields are written under the same key.
+///
+/// ```
+/// # #[cfg(feature = "json")]
+/// # {
+/// use tracing_bridge::{FieldValue, TracingEvent, TracingLevel};
+///
+/// let event = TracingEvent::builder()
+///     .level(TracingLevel::Warn)
+///     .target("my_app::db")
+///     .field("retries", FieldValue::U64(3))
+///     .field("level", FieldValue::Str("high".to_owned()))
+///     .build();
+/// let flat = serde_json::to_value(event.flat()).unwrap();
+/// assert_eq!(flat["level"], "Warn");
+/// assert_eq!(flat["target"], "my_app::db");
+/// assert_eq!(flat["retries"], 3);
+/// assert_eq!(flat["fields.level"], "high");
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct TracingEventFlat<'a>(pub &'a TracingEvent);
+
+impl TracingEvent {
+    /// Returns a view of the event that serializes its fields at the top
+    /// level. See [`TracingEventFlat`].
+    pub fn flat(&self) -> TracingEventFlat<'_> {
+        TracingEventFlat(self)
+    }
+}
+
+impl Serialize for TracingEventFlat<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let event = self.0;
+        let metadata = &event.metadata;
+        let names = distinct_names(&event.fields);
+
+        let mut map = serializer.serialize_map(Some(RESERVED.len() + names.len()))?;
+        map.serialize_entry("level", &metadata.level)?;
+        map.serialize_entry("target", &metadata.target)?;
+        map.serialize_entry("name", &metadata.name)?;
+        map.serialize_entry("module_path", &metadata.module_path)?;
+        map.serialize_entry("file", &metadata.file)?;
+        map.serialize_entry("line", &metadata.line)?;
+        map.serialize_entry("kind", &metadata.kind)?;
+        map.serialize_entry("declared_fields", &metadata.declared_fields)?;
+        map.serialize_entry("message", &event.message)?;
+        map.serialize_entry("timestamp", &event.timestamp)?;
+        map.serialize_entry("monotonic_nanos", &event.monotonic_nanos)?;
+        map.serialize_entry("seq", &event.seq)?;
+        map.serialize_entry("thread_id", &event.thread_id)?;
+        map.serialize_entry("thread_name", &event.thread_name)?;
+        map.serialize_entry("span_context", &event.span_context)?;
+        map.serialize_entry("current_span_id", &event.current_span_id)?;
+
+        for name in names {
+            let values = Values {
+                fields: &event.fields,
+                name,
+            };
+            if RESERVED.contains(&name) || name.starts_with("fields.") {
+                map.serialize_entry(&format_args!("fields.{}", name), &values)?;
+            } else {
+                map.serialize_entry(name, &values)?;
+            }
+        }
+        map.end()
+    }
+}
+
+/// Returns the names of `fields`, each once, in the order they were first
+/// recorded.
+fn distinct_names(fields: &TracingFields) -> Vec<&str> {
+    let mut names: Vec<&str> = Vec::new();
+    for (name, _) in fields.iter() {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// The values recorded for `name`: the value itself if there is only one,
+/// or an array of them.
+struct Values<'a> {
+    fields: &'a TracingFields,
+    name: &'a str,
+}
+
+impl Serialize for Values<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let values: Vec<&FieldValue> = self.fields.get_all(self.name).collect();
+        match values.as_slice() {
+            [value] => Plain(value).serialize(serializer),
+            values => serializer.collect_seq(values.iter().copied().map(Plain)),
+        }
+    }
+}
+
+/// Serializes a field value without its variant tag.
+struct Plain<'a>(&'a FieldValue);
+
+impl Serialize for Plain<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            FieldValue::I64(value) => serializer.serialize_i64(*value),
+            FieldValue::U64(value) => serializer.serialize_u64(*value),
+            FieldValue::F64(value) => float::serialize(value, serializer),
+            FieldValue::Bool(value) => serializer.serialize_bool(*value),
+            FieldValue::Str(value) | FieldValue::Debug(value) => serializer.serialize_str(value),
+            FieldValue::Error { message, causes } => {
+                let mut state = serializer.serialize_struct("Error", 2)?;
+                state.serialize_field("message", message)?;
+                state.serialize_field("causes", causes)?;
+                state.end()
+            }
+            FieldValue::Truncated {
+                value,
+                original_len,
+            } => {
+                let mut state = serializer.serialize_struct("Truncated", 2)?;
+                state.serialize_field("value", value)?;
+                state.serialize_field("original_len", original_len)?;
+                state.end()
+            }
+            #[cfg(feature = "valuable")]
+            FieldValue::Nested(value) => crate::nested::serialize(value, serializer),
+        }
+    }
+}
@@ -0,0 +1,433 @@
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+use serde::{
+    de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess},
+    ser::SerializeStruct,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{
+    priority::{Level, LevelSeed},
+    timestamp::{Timestamp, TimestampSeed},
+    LevelFormat, TimestampFormat, TracingCallsiteKind, TracingEvent, TracingFields,
+    TracingMetadata,
+};
+
+/// The formats an event's timestamp and level are written and read back in,
+/// where they shouldn't be written the way [`TracingEvent`] serializes them.
+///
+/// An event is written in a format by serializing a [`FormattedEvent`], and
+/// read back by using the format as a [`DeserializeSeed`]. Sinks and
+/// readers take the format with methods such as
+/// [`WriterSink::with_timestamp_format`](crate::WriterSink::with_timestamp_format)
+/// and [`WriterSink::with_level_format`](crate::WriterSink::with_level_format),
+/// and must be given the same format to read back what they wrote.
+///
+/// ```
+/// # #[cfg(feature = "json")]
+/// # {
+/// use serde::de::DeserializeSeed;
+/// use tracing_bridge::{EventFormat, FormattedEvent, TimestampFormat, TracingEvent};
+///
+/// let format = EventFormat::default().with_timestamp_format(TimestampFormat::EpochMillis);
+/// let event = TracingEvent::builder().timestamp(1_714_566_600_123_000_000).build();
+///
+/// let json = serde_json::to_string(&FormattedEvent::new(&event, format)).unwrap();
+/// assert!(json.contains(r#""timestamp":1714566600123"#));
+///
+/// let read = format.deserialize(&mut serde_json::Deserializer::from_str(&json)).unwrap();
+/// assert_eq!(read, event);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct EventFormat {
+    timestamp: TimestampFormat,
+    level: LevelFormat,
+}
+
+impl EventFormat {
+    /// Writes and reads timestamps in `format`.
+    pub fn with_timestamp_format(self, format: TimestampFormat) -> Self {
+        Self {
+            timestamp: format,
+            ..self
+        }
+    }
+
+    /// Writes and reads levels in `format`.
+    pub fn with_level_format(self, format: LevelFormat) -> Self {
+        Self {
+            level: format,
+            ..self
+        }
+    }
+
+    pub fn timestamp_format(&self) -> TimestampFormat {
+        self.timestamp
+    }
+
+    pub fn level_format(&self) -> LevelFormat {
+        self.level
+    }
+}
+
+/// A [`TracingEvent`] that serializes like the event itself, except with its
+/// timestamp and level written in an [`EventFormat`].
+#[derive(Debug, Clone, Copy)]
+pub struct FormattedEvent<'a> {
+    event: &'a TracingEvent,
+    format: EventFormat,
+}
+
+impl<'a> FormattedEvent<'a> {
+    pub fn new(event: &'a TracingEvent, format: EventFormat) -> Self {
+        Self { event, format }
+    }
+
+    pub fn event(&self) -> &'a TracingEvent {
+        self.event
+    }
+
+    pub fn format(&self) -> EventFormat {
+        self.format
+    }
+}
+
+impl Serialize for FormattedEvent<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let event = self.event;
+        let timestamp = Timestamp {
+            nanos: event.timestamp,
+            format: self.format.timestamp,
+        };
+
+        let mut state = serializer.serialize_struct("TracingEvent", FIELDS.len())?;
+        let metadata = FormattedMetadata {
+            metadata: &event.metadata,
+            level: self.format.level,
+        };
+        state.serialize_field("metadata", &metadata)?;
+        state.serialize_field("message", &event.message)?;
+        state.serialize_field("fields", &event.fields)?;
+        state.serialize_field("timestamp", &timestamp)?;
+        state.serialize_field("monotonic_nanos", &event.monotonic_nanos)?;
+        state.serialize_field("seq", &event.seq)?;
+        state.serialize_field("thread_id", &event.thread_id)?;
+        state.serialize_field("thread_name", &event.thread_name)?;
+        state.serialize_field("span_context", &event.span_context)?;
+        state.serialize_field("current_span_id", &event.current_span_id)?;
+        state.end()
+    }
+}
+
+/// The properties of a serialized [`TracingEvent`], in the order they are
+/// written.
+const FIELDS: [&str; 10] = [
+    "metadata",
+    "message",
+    "fields",
+    "timestamp",
+    "monotonic_nanos",
+    "seq",
+    "thread_id",
+    "thread_name",
+    "span_context",
+    "current_span_id",
+];
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "snake_case")]
+enum Field {
+    Metadata,
+    Message,
+    Fields,
+    Timestamp,
+    MonotonicNanos,
+    Seq,
+    ThreadId,
+    ThreadName,
+    SpanContext,
+    CurrentSpanId,
+    #[serde(other)]
+    Unknown,
+}
+
+/// Reads an event written in the format, the same way as
+/// `TracingEvent`'s `Deserialize` implementation reads one written by its
+/// `Serialize` implementation.
+impl<'de> DeserializeSeed<'de> for EventFormat {
+    type Value = TracingEvent;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<TracingEvent, D::Error> {
+        deserializer.deserialize_struct("TracingEvent", &FIELDS, EventVisitor(self))
+    }
+}
+
+struct EventVisitor(EventFormat);
+
+impl<'de> de::Visitor<'de> for EventVisitor {
+    type Value = TracingEvent;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("struct TracingEvent")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<TracingEvent, A::Error> {
+        let missing = |index| de::Error::invalid_length(index, &self);
+        Ok(TracingEvent {
+            metadata: seq
+                .next_element_seed(MetadataSeed(self.0.level))?
+                .ok_or_else(|| missing(0))?,
+            message: seq.next_element()?.unwrap_or_default(),
+            fields: seq.next_element()?.ok_or_else(|| missing(2))?,
+            timestamp: seq
+                .next_element_seed(TimestampSeed(self.0.timestamp))?
+                .unwrap_or_default(),
+            monotonic_nanos: seq.next_element()?.unwrap_or_default(),
+            seq: seq.next_element()?.unwrap_or_default(),
+            thread_id: seq.next_element()?.unwrap_or_default(),
+            thread_name: seq.next_element()?.unwrap_or_default(),
+            span_context: seq.next_element()?.unwrap_or_default(),
+            current_span_id: seq.next_element()?.unwrap_or_default(),
+        })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<TracingEvent, A::Error> {
+        let mut metadata: Option<TracingMetadata> = None;
+        let mut message: Option<Option<String>> = None;
+        let mut fields: Option<TracingFields> = None;
+        let mut timestamp: Option<Option<u64>> = None;
+        let mut monotonic_nanos: Option<Option<u64>> = None;
+        let mut seq: Option<Option<u64>> = None;
+        let mut thread_id: Option<Option<String>> = None;
+        let mut thread_name: Option<Option<String>> = None;
+        let mut span_context: Option<Vec<u64>> = None;
+        let mut current_span_id: Option<Option<u64>> = None;
+
+        while let Some(key) = map.next_key()? {
+            match key {
+                Field::Metadata => {
+                    once(&metadata, "metadata")?;
+                    metadata = Some(map.next_value_seed(MetadataSeed(self.0.level))?);
+                }
+                Field::Message => {
+                    once(&message, "message")?;
+                    message = Some(map.next_value()?);
+                }
+                Field::Fields => {
+                    once(&fields, "fields")?;
+                    fields = Some(map.next_value()?);
+                }
+                Field::Timestamp => {
+                    once(&timestamp, "timestamp")?;
+                    timestamp = Some(map.next_value_seed(TimestampSeed(self.0.timestamp))?);
+                }
+                Field::MonotonicNanos => {
+                    once(&monotonic_nanos, "monotonic_nanos")?;
+                    monotonic_nanos = Some(map.next_value()?);
+                }
+                Field::Seq => {
+                    once(&seq, "seq")?;
+                    seq = Some(map.next_value()?);
+                }
+                Field::ThreadId => {
+                    once(&thread_id, "thread_id")?;
+                    thread_id = Some(map.next_value()?);
+                }
+                Field::ThreadName => {
+                    once(&thread_name, "thread_name")?;
+                    thread_name = Some(map.next_value()?);
+                }
+                Field::SpanContext => {
+                    once(&span_context, "span_context")?;
+                    span_context = Some(map.next_value()?);
+                }
+                Field::CurrentSpanId => {
+                    once(&current_span_id, "current_span_id")?;
+                    current_span_id = Some(map.next_value()?);
+                }
+                Field::Unknown => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        Ok(TracingEvent {
+            metadata: metadata.ok_or_else(|| de::Error::missing_field("metadata"))?,
+            message: message.unwrap_or_default(),
+            fields: fields.ok_or_else(|| de::Error::missing_field("fields"))?,
+            timestamp: timestamp.unwrap_or_default(),
+            monotonic_nanos: monotonic_nanos.unwrap_or_default(),
+            seq: seq.unwrap_or_default(),
+            thread_id: thread_id.unwrap_or_default(),
+            thread_name: thread_name.unwrap_or_default(),
+            span_context: span_context.unwrap_or_default(),
+            current_span_id: current_span_id.unwrap_or_default(),
+        })
+    }
+}
+
+/// [`TracingMetadata`] that serializes like the metadata itself, except with
+/// its level written in `level`.
+struct FormattedMetadata<'a> {
+    metadata: &'a TracingMetadata,
+    level: LevelFormat,
+}
+
+impl Serialize for FormattedMetadata<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let metadata = self.metadata;
+        let level = Level {
+            level: metadata.level,
+            format: self.level,
+        };
+
+        let mut state = serializer.serialize_struct("TracingMetadata", METADATA_FIELDS.len())?;
+        state.serialize_field("name", &metadata.name)?;
+        state.serialize_field("target", &metadata.target)?;
+        state.serialize_field("level", &level)?;
+        state.serialize_field("module_path", &metadata.module_path)?;
+        state.serialize_field("file", &metadata.file)?;
+        state.serialize_field("line", &metadata.line)?;
+        state.serialize_field("kind", &metadata.kind)?;
+        state.serialize_field("declared_fields", &metadata.declared_fields)?;
+        state.end()
+    }
+}
+
+/// The properties of serialized [`TracingMetadata`], in the order they are
+/// written.
+const METADATA_FIELDS: [&str; 8] = [
+    "name",
+    "target",
+    "level",
+    "module_path",
+    "file",
+    "line",
+    "kind",
+    "declared_fields",
+];
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "snake_case")]
+enum MetadataField {
+    Name,
+    Target,
+    Level,
+    ModulePath,
+    File,
+    Line,
+    Kind,
+    DeclaredFields,
+    #[serde(other)]
+    Unknown,
+}
+
+/// Reads [`TracingMetadata`] with its level written in a format.
+struct MetadataSeed(LevelFormat);
+
+impl<'de> DeserializeSeed<'de> for MetadataSeed {
+    type Value = TracingMetadata;
+
+    fn deserialize<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<TracingMetadata, D::Error> {
+        deserializer.deserialize_struct("TracingMetadata", &METADATA_FIELDS, self)
+    }
+}
+
+impl<'de> de::Visitor<'de> for MetadataSeed {
+    type Value = TracingMetadata;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("struct TracingMetadata")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<TracingMetadata, A::Error> {
+        let missing = |index| de::Error::invalid_length(index, &self);
+        Ok(TracingMetadata {
+            name: seq.next_element()?.ok_or_else(|| missing(0))?,
+            target: seq.next_element()?.ok_or_else(|| missing(1))?,
+            level: seq
+                .next_element_seed(LevelSeed(self.0))?
+                .ok_or_else(|| missing(2))?,
+            module_path: seq.next_element()?.unwrap_or_default(),
+            file: seq.next_element()?.unwrap_or_default(),
+            line: seq.next_element()?.unwrap_or_default(),
+            kind: seq.next_element()?.ok_or_else(|| missing(6))?,
+            declared_fields: seq.next_element()?.unwrap_or_default(),
+        })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<TracingMetadata, A::Error> {
+        let mut name: Option<String> = None;
+        let mut target: Option<String> = None;
+        let mut level = None;
+        let mut module_path: Option<Option<String>> = None;
+        let mut file: Option<Option<String>> = None;
+        let mut line: Option<Option<u32>> = None;
+        let mut kind: Option<TracingCallsiteKind> = None;
+        let mut declared_fields: Option<Vec<String>> = None;
+
+        while let Some(key) = map.next_key()? {
+            match key {
+                MetadataField::Name => {
+                    once(&name, "name")?;
+                    name = Some(map.next_value()?);
+                }
+                MetadataField::Target => {
+                    once(&target, "target")?;
+                    target = Some(map.next_value()?);
+                }
+                MetadataField::Level => {
+                    once(&level, "level")?;
+                    level = Some(map.next_value_seed(LevelSeed(self.0))?);
+                }
+                MetadataField::ModulePath => {
+                    once(&module_path, "module_path")?;
+                    module_path = Some(map.next_value()?);
+                }
+                MetadataField::File => {
+                    once(&file, "file")?;
+                    file = Some(map.next_value()?);
+                }
+                MetadataField::Line => {
+                    once(&line, "line")?;
+                    line = Some(map.next_value()?);
+                }
+                MetadataField::Kind => {
+                    once(&kind, "kind")?;
+                    kind = Some(map.next_value()?);
+                }
+                MetadataField::DeclaredFields => {
+                    once(&declared_fields, "declared_fields")?;
+                    declared_fields = Some(map.next_value()?);
+                }
+                MetadataField::Unknown => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        Ok(TracingMetadata {
+            name: name.ok_or_else(|| de::Error::missing_field("name"))?,
+            target: target.ok_or_else(|| de::Error::missing_field("target"))?,
+            level: level.ok_or_else(|| de::Error::missing_field("level"))?,
+            module_path: module_path.unwrap_or_default(),
+            file: file.unwrap_or_default(),
+            line: line.unwrap_or_default(),
+            kind: kind.ok_or_else(|| de::Error::missing_field("kind"))?,
+            declared_fields: declared_fields.unwrap_or_default(),
+        })
+    }
+}
+
+/// Fails if `value` was already read.
+fn once<T, E: de::Error>(value: &Option<T>, name: &'static str) -> Result<(), E> {
+    match value {
+        Some(_) => Err(E::duplicate_field(name)),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,269 @@
+use std::{
+    convert::TryFrom,
+    fmt,
+    io::{self, Read, Write},
+};
+
+use bincode::Options;
+use serde::{
+    de::{self, DeserializeSeed, EnumAccess, VariantAccess},
+    Deserialize, Deserializer, Serialize,
+};
+
+use crate::{
+    EventFormat, FormattedEvent, LevelFormat, StreamHeader, TimestampFormat, TracingEvent,
+};
+
+/// The longest frame a [`FramedReader`] accepts by default.
+const DEFAULT_MAX_FRAME_LEN: u64 = 16 * 1024 * 1024;
+
+/// The contents of a frame. Encoded with `bincode`, so the payload of each
+/// frame starts with the variant index as a little-endian `u32`.
+#[derive(Serialize)]
+enum FrameRef<'a> {
+    Header(&'a StreamHeader),
+    Event(FormattedEvent<'a>),
+}
+
+/// A frame read by a [`FramedReader`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Frame {
+    /// A header describing the events that follow it.
+    Header(StreamHeader),
+    Event(Box<TracingEvent>),
+}
+
+impl Frame {
+    /// Returns the event the frame holds, or `None` for a header.
+    pub fn into_event(self) -> Option<TracingEvent> {
+        match self {
+            Frame::Header(_) => None,
+            Frame::Event(event) => Some(*event),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(variant_identifier)]
+enum FrameKind {
+    Header,
+    Event,
+}
+
+/// Reads a frame's contents, with events in the format.
+struct FrameSeed(EventFormat);
+
+impl<'de> DeserializeSeed<'de> for FrameSeed {
+    type Value = Frame;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Frame, D::Error> {
+        deserializer.deserialize_enum("FrameRef", &["Header", "Event"], self)
+    }
+}
+
+impl<'de> de::Visitor<'de> for FrameSeed {
+    type Value = Frame;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a stream header or an event")
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<Frame, A::Error> {
+        match data.variant()? {
+            (FrameKind::Header, variant) => variant.newtype_variant().map(Frame::Header),
+            (FrameKind::Event, variant) => variant
+                .newtype_variant_seed(self.0)
+                .map(|event| Frame::Event(Box::new(event))),
+        }
+    }
+}
+
+/// Writes events to `W` as frames: a big-endian `u32` length followed by a
+/// `bincode`-encoded payload holding either an event or a [`StreamHeader`].
+pub struct FramedWriter<W> {
+    writer: W,
+    format: EventFormat,
+}
+
+impl<W: Write> FramedWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            format: EventFormat::default(),
+        }
+    }
+
+    /// Writes event timestamps in `format` rather than as nanoseconds since
+    /// the Unix epoch. A [`FramedReader`] needs the same format to read them
+    /// back.
+    pub fn with_timestamp_format(self, format: TimestampFormat) -> Self {
+        Self {
+            format: self.format.with_timestamp_format(format),
+            ..self
+        }
+    }
+
+    /// Writes levels in `format` rather than as their names. A
+    /// [`FramedReader`] needs the same format to read them back.
+    pub fn with_level_format(self, format: LevelFormat) -> Self {
+        Self {
+            format: self.format.with_level_format(format),
+            ..self
+        }
+    }
+
+    /// Creates a writer that starts the stream with `header`.
+    pub fn with_header(writer: W, header: &StreamHeader) -> io::Result<Self> {
+        let mut framed = Self::new(writer);
+        framed.write_frame(&FrameRef::Header(header))?;
+        Ok(framed)
+    }
+
+    /// Writes a single event as one frame.
+    pub fn write(&mut self, event: &TracingEvent) -> io::Result<()> {
+        self.write_frame(&FrameRef::Event(FormattedEvent::new(event, self.format)))
+    }
+
+    fn write_frame(&mut self, frame: &FrameRef<'_>) -> io::Result<()> {
+        let payload = bincode::serialize(frame)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+        let len = u32::try_from(payload.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "event too large to frame"))?;
+
+        self.writer.write_all(&len.to_be_bytes())?;
+        self.writer.write_all(&payload)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Reads frames written by a [`FramedWriter`], yielding one [`Frame`] per
+/// frame.
+///
+/// A stream started with [`FramedWriter::with_header`] yields its
+/// [`StreamHeader`] as the first item, followed by its events. A header in
+/// the middle of the stream, e.g. where two streams were concatenated, is
+/// yielded where it appears. [`events`](Self::events) skips the headers.
+///
+/// Iteration ends at the end of the stream. A frame cut short by the end of
+/// the stream (for example because the writer was killed mid-write) also
+/// ends iteration rather than producing an error.
+///
+/// Frames longer than the maximum frame length, 16 MiB unless changed with
+/// [`with_max_frame_len`](Self::with_max_frame_len), are skipped without
+/// being buffered and yield an [`io::ErrorKind::InvalidData`] error, so a
+/// corrupt or hostile length prefix can't make the reader allocate up to
+/// 4 GiB. Iteration can continue after the error.
+pub struct FramedReader<R> {
+    reader: R,
+    max_frame_len: u64,
+    format: EventFormat,
+}
+
+impl<R: Read> FramedReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            max_frame_len: DEFAULT_MAX_FRAME_LEN,
+            format: EventFormat::default(),
+        }
+    }
+
+    /// Sets the length in bytes beyond which frames are rejected.
+    pub fn with_max_frame_len(self, max_frame_len: u32) -> Self {
+        Self {
+            max_frame_len: u64::from(max_frame_len),
+            ..self
+        }
+    }
+
+    /// Reads timestamps written in `format`, by a [`FramedWriter`] given the
+    /// same format, rather than as nanoseconds since the Unix epoch.
+    pub fn with_timestamp_format(self, format: TimestampFormat) -> Self {
+        Self {
+            format: self.format.with_timestamp_format(format),
+            ..self
+        }
+    }
+
+    /// Reads levels written in `format`, by a [`FramedWriter`] given the same
+    /// format, rather than as names.
+    pub fn with_level_format(self, format: LevelFormat) -> Self {
+        Self {
+            format: self.format.with_level_format(format),
+            ..self
+        }
+    }
+
+    /// Returns an iterator over the events in the stream, skipping headers.
+    pub fn events(self) -> impl Iterator<Item = io::Result<TracingEvent>> {
+        self.filter_map(|frame| match frame {
+            Ok(frame) => frame.into_event().map(Ok),
+            Err(error) => Some(Err(error)),
+        })
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads the next frame, returning `None` if the stream ends before a
+    /// complete frame has been read.
+    fn read_frame(&mut self) -> io::Result<Option<Frame>> {
+        let mut len = [0; 4];
+        if !read_exact_or_eof(&mut self.reader, &mut len)? {
+            return Ok(None);
+        }
+
+        let len = u64::from(u32::from_be_bytes(len));
+        let mut frame = (&mut self.reader).take(len);
+        if len > self.max_frame_len {
+            io::copy(&mut frame, &mut io::sink())?;
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "frame of {} bytes is longer than the maximum of {}",
+                    len, self.max_frame_len
+                ),
+            ));
+        }
+
+        // Grow the buffer as the payload arrives rather than trusting the
+        // length up front.
+        let mut payload = Vec::new();
+        if frame.read_to_end(&mut payload)? < len as usize {
+            return Ok(None);
+        }
+
+        // The options `bincode::deserialize` uses.
+        bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .allow_trailing_bytes()
+            .deserialize_seed(FrameSeed(self.format), &payload)
+            .map(Some)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+}
+
+impl<R: Read> Iterator for FramedReader<R> {
+    type Item = io::Result<Frame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_frame().transpose()
+    }
+}
+
+/// Fills `buf` from `reader`, returning `false` if the stream ends first.
+fn read_exact_or_eof(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<bool> {
+    match reader.read_exact(buf) {
+        Ok(()) => Ok(true),
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(error) => Err(error),
+    }
+}
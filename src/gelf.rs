@@ -0,0 +1,84 @@
+use serde_json::{json, Map, Value};
+
+use crate::{FieldValue, TracingEvent};
+
+impl TracingEvent {
+    /// Converts the event into a [GELF](https://go2docs.graylog.org/current/getting_in_log_data/gelf.html)
+    /// 1.1 message, so it can be sent to Graylog as it is:
+    ///
+    /// - `host`: `host`
+    /// - `short_message`: the first line of the message, or the whole event
+    ///   formatted as a line (see [`TracingEvent`]'s `Display` impl) if it
+    ///   has no message, since GELF requires one
+    /// - `full_message`: the whole message, if it has more than one line
+    /// - `timestamp`: the timestamp in seconds since the Unix epoch
+    /// - `level`: the [syslog severity](crate::TracingLevel::syslog_severity)
+    /// - `_<name>`: each field, under its name prefixed with `_`
+    ///
+    /// GELF only allows strings and numbers in custom fields, so fields that
+    /// aren't finite numbers are written as strings (see [`FieldValue`]'s
+    /// `Display` impl, except that strings are not quoted), and characters
+    /// other than letters, digits, `_`, `.` and `-` in their names are
+    /// replaced with underscores. GELF reserves `_id`, so a field called `id`
+    /// is written as `_id_`. Only the first value of a field recorded more
+    /// than once is kept.
+    pub fn to_gelf_value(&self, host: &str) -> Value {
+        let mut message = Map::new();
+        message.insert("version".to_owned(), json!("1.1"));
+        message.insert("host".to_owned(), json!(host));
+        match &self.message {
+            Some(text) => {
+                let first_line = text.lines().next().unwrap_or_default();
+                message.insert("short_message".to_owned(), json!(first_line));
+                if first_line.len() < text.trim_end().len() {
+                    message.insert("full_message".to_owned(), json!(text));
+                }
+            }
+            None => {
+                message.insert("short_message".to_owned(), json!(self.to_string()));
+            }
+        }
+        if let Some(timestamp) = self.timestamp {
+            // Dividing the whole timestamp would lose precision to rounding,
+            // so the seconds and the fraction are converted separately.
+            let seconds =
+                (timestamp / 1_000_000_000) as f64 + (timestamp % 1_000_000_000) as f64 / 1e9;
+            message.insert("timestamp".to_owned(), json!(seconds));
+        }
+        message.insert(
+            "level".to_owned(),
+            json!(self.metadata.level.syslog_severity()),
+        );
+
+        for (name, value) in self.fields.iter() {
+            message
+                .entry(field_name(name))
+                .or_insert_with(|| field_value(value));
+        }
+        Value::Object(message)
+    }
+}
+
+fn field_name(name: &str) -> String {
+    if name == "id" {
+        return "_id_".to_owned();
+    }
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '.' | '-' => c,
+            _ => '_',
+        })
+        .collect();
+    format!("_{}", name)
+}
+
+fn field_value(value: &FieldValue) -> Value {
+    match value {
+        FieldValue::I64(value) => json!(value),
+        FieldValue::U64(value) => json!(value),
+        FieldValue::F64(value) if value.is_finite() => json!(value),
+        FieldValue::Str(value) => json!(value),
+        value => json!(value.to_string()),
+    }
+}
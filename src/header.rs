@@ -0,0 +1,44 @@
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::unix_nanos;
+
+/// Describes where a stream of events comes from. Written once at the start
+/// of a stream, so that individual events don't have to carry it.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct StreamHeader {
+    /// The name of the host the events were captured on, or `None` if it
+    /// could not be determined.
+    pub hostname: Option<String>,
+
+    /// The id of the process the events were captured in.
+    pub pid: u32,
+
+    /// When the stream was started, in nanoseconds since the Unix epoch.
+    pub start_time: Option<u64>,
+}
+
+impl StreamHeader {
+    /// Describes a stream starting now in the current process.
+    pub fn current() -> Self {
+        Self {
+            hostname: current_hostname().map(str::to_owned),
+            pid: std::process::id(),
+            start_time: unix_nanos(std::time::SystemTime::now()),
+        }
+    }
+}
+
+/// Returns the name of the current host, looked up once and then cached, or
+/// `None` if it could not be determined.
+pub(crate) fn current_hostname() -> Option<&'static str> {
+    static HOSTNAME: OnceLock<Option<String>> = OnceLock::new();
+    HOSTNAME
+        .get_or_init(|| {
+            hostname::get()
+                .ok()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .as_deref()
+}
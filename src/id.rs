@@ -0,0 +1,76 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A source of ids for the spans a [`BridgeSubscriber`] creates, or that a
+/// [`BridgeLayer`] reports.
+///
+/// [`BridgeSubscriber`] numbers spans with [`SequentialIds`] unless given
+/// another generator with [`BridgeSubscriber::with_id_generator`], and
+/// [`BridgeLayer`] reports the ids its subscriber assigned unless given one
+/// with [`BridgeLayer::with_id_generator`]. Either way, ids restart with
+/// every run of the process, so events from several runs or hosts can't be
+/// told apart by span id alone. A generator that mixes in something unique
+/// to the process, such as its pid and start time, or that picks ids at
+/// random, keeps them apart.
+///
+/// ```
+/// use std::{
+///     sync::atomic::{AtomicU64, Ordering},
+///     time::{SystemTime, UNIX_EPOCH},
+/// };
+///
+/// use tracing_bridge::{BridgeSubscriber, IdGenerator};
+///
+/// /// Puts the pid in the top 32 bits and counts in the bottom 32.
+/// struct PerProcess(AtomicU64);
+///
+/// impl IdGenerator for PerProcess {
+///     fn next_span_id(&self) -> u64 {
+///         u64::from(std::process::id()) << 32 | self.0.fetch_add(1, Ordering::Relaxed)
+///     }
+/// }
+///
+/// let subscriber = BridgeSubscriber::new(|event| println!("{:?}", event))
+///     .with_id_generator(PerProcess(AtomicU64::new(1)));
+/// ```
+///
+/// [`BridgeLayer`]: crate::BridgeLayer
+/// [`BridgeLayer::with_id_generator`]: crate::BridgeLayer::with_id_generator
+/// [`BridgeSubscriber`]: crate::BridgeSubscriber
+/// [`BridgeSubscriber::with_id_generator`]: crate::BridgeSubscriber::with_id_generator
+pub trait IdGenerator: Send + Sync + 'static {
+    /// Returns the id of a new span. It must not be zero, which `tracing`
+    /// reserves, and should not repeat an id still in use.
+    fn next_span_id(&self) -> u64;
+}
+
+/// Numbers spans one after the other, from 1 unless told otherwise.
+#[derive(Debug)]
+pub struct SequentialIds {
+    next: AtomicU64,
+}
+
+impl SequentialIds {
+    pub fn new() -> Self {
+        Self::starting_at(1)
+    }
+
+    /// Returns a generator whose first id is `first`, which must not be
+    /// zero.
+    pub fn starting_at(first: u64) -> Self {
+        Self {
+            next: AtomicU64::new(first),
+        }
+    }
+}
+
+impl Default for SequentialIds {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl IdGenerator for SequentialIds {
+    fn next_span_id(&self) -> u64 {
+        self.next.fetch_add(1, Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,81 @@
+use serde_json::{json, Map, Value};
+
+use crate::{FieldValue, TracingEvent, TracingFields};
+
+impl TracingEvent {
+    /// Converts the event into a JSON object with `metadata`, `message`,
+    /// `fields` and the event's other properties as top-level keys.
+    ///
+    /// Unlike serializing the event directly, field values are written as
+    /// plain JSON values (see [`FieldValue::to_json_value`]) rather than
+    /// tagged with their variant, which makes the result easier to reshape or
+    /// merge with other data.
+    pub fn to_json_value(&self) -> Value {
+        json!({
+            "metadata": self.metadata,
+            "message": self.message,
+            "fields": self.fields.to_json_value(),
+            "timestamp": self.timestamp,
+            "monotonic_nanos": self.monotonic_nanos,
+            "seq": self.seq,
+            "thread_id": self.thread_id,
+            "thread_name": self.thread_name,
+            "span_context": self.span_context,
+            "current_span_id": self.current_span_id,
+        })
+    }
+}
+
+impl TracingFields {
+    /// Converts the fields into a JSON object mapping each name to its
+    /// [`FieldValue::to_json_value`]. A name recorded more than once maps to
+    /// an array of its values, in recording order.
+    pub fn to_json_value(&self) -> Value {
+        let mut fields = Map::new();
+        for (name, _) in self.iter() {
+            if fields.contains_key(name) {
+                continue;
+            }
+            let mut values: Vec<Value> =
+                self.get_all(name).map(FieldValue::to_json_value).collect();
+            let value = match values.len() {
+                1 => values.remove(0),
+                _ => Value::Array(values),
+            };
+            fields.insert(name.to_owned(), value);
+        }
+        Value::Object(fields)
+    }
+}
+
+impl FieldValue {
+    /// Converts the value into the closest plain JSON value: numbers and
+    /// booleans stay typed, strings and `Debug` output become strings, errors
+    /// become an object with `message` and `causes`, truncated values an
+    /// object with `value` and `original_len`, and nested values are returned
+    /// as they are.
+    ///
+    /// Non-finite floats, which JSON can't represent, become `null`.
+    pub fn to_json_value(&self) -> Value {
+        match self {
+            Self::I64(value) => json!(value),
+            Self::U64(value) => json!(value),
+            Self::F64(value) => json!(value),
+            Self::Bool(value) => json!(value),
+            Self::Str(value) | Self::Debug(value) => json!(value),
+            Self::Error { message, causes } => json!({
+                "message": message,
+                "causes": causes,
+            }),
+            Self::Truncated {
+                value,
+                original_len,
+            } => json!({
+                "value": value,
+                "original_len": original_len,
+            }),
+            #[cfg(feature = "valuable")]
+            Self::Nested(value) => value.clone(),
+        }
+    }
+}
@@ -0,0 +1,945 @@
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::SystemTime,
+};
+
+use tracing_core::{
+    span::{Attributes, Id, Record},
+    subscriber::Interest,
+    Event, LevelFilter, Metadata, Subscriber,
+};
+use tracing_subscriber::{
+    layer::{Context, Layer},
+    registry::{Extensions, LookupSpan, Scope, SpanRef},
+};
+
+use crate::{
+    filter::{FieldFilter, FilterHandle},
+    rate_limit::{self, RateLimiter},
+    sample::Sampler,
+    span::SpanTimings,
+    unix_nanos, Clock, DebugFormat, DropStats, EventMetrics, EventSink, FieldOptions, FieldValue,
+    FlushGuard, IdGenerator, Location, MonotonicBaseline, SinkError, SpanEvent, SystemClock,
+    TracingEventRef, TracingFields, TracingLevel, TracingMetadata, TracingSpan,
+};
+
+/// A [`Layer`] that converts every event it sees into a
+/// [`TracingEvent`](crate::TracingEvent) and forwards it to an
+/// [`EventSink`]. Events are stamped with the time they were captured, the
+/// thread that emitted them and a sequence number.
+///
+/// ```
+/// use tracing_bridge::BridgeLayer;
+/// use tracing_subscriber::layer::SubscriberExt;
+///
+/// let subscriber = tracing_subscriber::registry()
+///     .with(BridgeLayer::new(|event| println!("{:?}", event)));
+/// ```
+///
+/// Span lifecycles are reported to [`EventSink::emit_span`] as
+/// [`SpanEvent`]s, which requires the layer to be used with a subscriber
+/// that stores span data, such as [`tracing_subscriber::Registry`].
+///
+/// The sink is called inline from [`Layer::on_event`], so the emitting thread
+/// is blocked for as long as the sink takes to accept the event. See
+/// [`EventSink`] for how slow sinks should handle this.
+pub struct BridgeLayer<K> {
+    sink: Arc<K>,
+    field_options: FieldOptions,
+    filter: FilterHandle,
+    field_filter: FieldFilter,
+    sampler: Sampler,
+    rate_limiter: Option<RateLimiter>,
+    drop_stats: DropStats,
+    metrics: Option<EventMetrics>,
+    error_handler: Option<ErrorHandler>,
+    clock: Arc<dyn Clock>,
+    baseline: MonotonicBaseline,
+    global_filtering: bool,
+    inherited_fields: bool,
+    global_fields: TracingFields,
+    location_override: Option<LocationOverride>,
+    target_rewrite: Option<TargetRewrite>,
+    span_extension_extractor: Option<SpanExtensionExtractor>,
+    id_generator: Option<Arc<dyn IdGenerator>>,
+    next_seq: AtomicU64,
+}
+
+impl<K: EventSink> BridgeLayer<K> {
+    pub fn new(sink: K) -> Self {
+        Self {
+            sink: Arc::new(sink),
+            field_options: FieldOptions {
+                max_field_len: Some(FieldOptions::DEFAULT_MAX_FIELD_LEN),
+                ..FieldOptions::default()
+            },
+            filter: FilterHandle::default(),
+            field_filter: FieldFilter::default(),
+            sampler: Sampler::default(),
+            rate_limiter: None,
+            drop_stats: DropStats::new(),
+            metrics: None,
+            error_handler: None,
+            clock: Arc::new(SystemClock),
+            baseline: MonotonicBaseline::now(),
+            global_filtering: false,
+            inherited_fields: false,
+            global_fields: TracingFields::new(),
+            location_override: None,
+            target_rewrite: None,
+            span_extension_extractor: None,
+            id_generator: None,
+            next_seq: AtomicU64::new(0),
+        }
+    }
+
+    /// Keeps only a random fraction `rate` of events, e.g. `0.01` keeps about
+    /// 1% of them. Rates of `1.0` or more keep every event, and rates of `0.0`
+    /// or less keep none.
+    ///
+    /// Sampling happens after level and target filtering but before the event
+    /// is converted, and uses a thread-local generator so it never contends
+    /// across threads. Spans are not sampled.
+    pub fn with_sampling(mut self, rate: f64) -> Self {
+        self.sampler.set_all_rates(rate);
+        self
+    }
+
+    /// Like [`with_sampling`](Self::with_sampling), but only for events at
+    /// `level`, so that e.g. all errors can be kept while traces are sampled.
+    pub fn with_level_sampling(mut self, level: TracingLevel, rate: f64) -> Self {
+        self.sampler.set_rate(level, rate);
+        self
+    }
+
+    /// Captures at most `max_per_sec` events per second from each callsite,
+    /// so one misbehaving callsite can't bury everything else.
+    ///
+    /// Each callsite has a bucket of `max_per_sec` tokens, refilled
+    /// continuously, so short bursts up to the limit are let through.
+    /// Suppressed events are counted in
+    /// [`DropCounts::rate_limited`](crate::DropCounts::rate_limited), and at
+    /// most once a second a `WARN` event from the `tracing_bridge::rate_limit`
+    /// target reports how many events each limited callsite suppressed since
+    /// its last report (in a `suppressed` field). There is no timer: the
+    /// reports are emitted just before the first event from any callsite
+    /// that reaches the limit check a second or more after the last reports,
+    /// so a storm that stops is still reported once anything else is logged.
+    ///
+    /// Buckets are kept in a sharded map, so callsites mostly don't contend
+    /// for the same lock.
+    ///
+    /// The limit is checked after level, target and sampling filters but
+    /// before the event is converted, so suppressed events are cheap, and
+    /// events later rejected by [`with_field_filter`](Self::with_field_filter)
+    /// still count towards it. Spans are not rate limited.
+    pub fn with_rate_limit(mut self, max_per_sec: u32) -> Self {
+        self.rate_limiter = Some(RateLimiter::new(max_per_sec));
+        self
+    }
+
+    /// Counts events rejected by the layer's filters and lost by its sink in
+    /// `drop_stats`, instead of in counters of the layer's own.
+    pub fn with_drop_stats(mut self, drop_stats: DropStats) -> Self {
+        self.drop_stats = drop_stats;
+        self
+    }
+
+    /// Timestamps events with `clock` instead of the system clock, e.g. a
+    /// [`ManualClock`](crate::ManualClock) to make timestamps deterministic in
+    /// tests.
+    pub fn with_clock<C: Clock>(mut self, clock: C) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Returns the baseline the layer measures
+    /// [`TracingEvent::monotonic_nanos`](crate::TracingEvent::monotonic_nanos)
+    /// from, read when the layer was created.
+    pub fn monotonic_baseline(&self) -> MonotonicBaseline {
+        self.baseline
+    }
+
+    /// Returns a handle to the counters of events the layer didn't deliver.
+    pub fn drop_stats(&self) -> DropStats {
+        self.drop_stats.clone()
+    }
+
+    /// Counts the events the layer delivers to its sink by level and target,
+    /// e.g. to report errors per component. Off by default, so that layers
+    /// that don't need the counts don't pay for them.
+    ///
+    /// Events rejected by a filter, sampled out or rate limited are not
+    /// counted, nor are the summaries of rate limited events.
+    pub fn with_metrics(mut self) -> Self {
+        self.metrics = Some(EventMetrics::new());
+        self
+    }
+
+    /// Returns a handle to the layer's event counts, or `None` unless
+    /// [`with_metrics`](Self::with_metrics) was called. Take the handle
+    /// before installing the layer to read the counts afterwards.
+    pub fn metrics(&self) -> Option<EventMetrics> {
+        self.metrics.clone()
+    }
+
+    /// Returns the number of events delivered for each level and target so
+    /// far. Empty unless [`with_metrics`](Self::with_metrics) was called.
+    pub fn metrics_snapshot(&self) -> HashMap<(TracingLevel, String), u64> {
+        self.metrics
+            .as_ref()
+            .map(EventMetrics::snapshot)
+            .unwrap_or_default()
+    }
+
+    /// Calls `handler` with every error the sink returns, instead of counting
+    /// the events it lost in the layer's [`DropStats`].
+    ///
+    /// The handler is called inline on the thread that emitted the event, so
+    /// it should be quick. It must not itself emit `tracing` events, which
+    /// would be captured by the layer again.
+    ///
+    /// ```
+    /// use tracing_bridge::SinkErrorKind;
+    ///
+    /// let (layer, _events) = tracing_bridge::channel();
+    /// let drop_stats = layer.drop_stats();
+    /// let layer = layer.with_error_handler(move |error| {
+    ///     if error.kind() != SinkErrorKind::Closed {
+    ///         eprintln!("failed to deliver events: {}", error);
+    ///     }
+    ///     drop_stats.record_sink_error(&error);
+    /// });
+    /// ```
+    pub fn with_error_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(SinkError) + Send + Sync + 'static,
+    {
+        self.error_handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// Calls `redactor` with the name and value of every field as it is
+    /// captured, so sensitive values can be rewritten before they reach the
+    /// sink. [`redact_keys`](crate::redact_keys) covers the common case of
+    /// hiding fields by name.
+    ///
+    /// This applies to event and span fields, but not to event messages.
+    pub fn with_redactor<F>(mut self, redactor: F) -> Self
+    where
+        F: Fn(&str, &mut FieldValue) + Send + Sync + 'static,
+    {
+        self.field_options.redactor = Some(Arc::new(redactor));
+        self
+    }
+
+    /// Also applies the layer's level and target filters in
+    /// [`Layer::register_callsite`] and [`Layer::enabled`], so that `tracing`
+    /// caches which callsites are rejected and skips them entirely, instead of
+    /// calling the layer for every event only to have it discard most of them.
+    /// The minimum level is also reported from [`Layer::max_level_hint`], so
+    /// that callsites below it are skipped before their interest is even
+    /// checked.
+    ///
+    /// This makes rejected callsites nearly free, but disables them for the
+    /// whole subscriber: other layers in the same stack stop seeing them too,
+    /// and rejected events are no longer counted in
+    /// [`drop_stats`](Self::drop_stats). Only enable it when the bridge's
+    /// filters should apply to every layer, e.g. when the bridge is the only
+    /// layer or sits under a stricter `EnvFilter`. To filter just the bridge
+    /// this cheaply, wrap it in a per-layer filter with [`Layer::with_filter`]
+    /// instead.
+    ///
+    /// Changes made through [`filter_handle`](Self::filter_handle) take effect
+    /// for cached callsites too. Sampling is not applied here, since it has to
+    /// be decided for each event.
+    pub fn with_global_filtering(self, global_filtering: bool) -> Self {
+        Self {
+            global_filtering,
+            ..self
+        }
+    }
+
+    /// Also copies the fields of every span an event is in into the event's
+    /// own fields, named `span.field`, for sinks that have no notion of
+    /// spans.
+    ///
+    /// ```
+    /// use tracing_bridge::BridgeLayer;
+    ///
+    /// // An event `info!(rows = 3)` inside `info_span!("query", table = "users")`
+    /// // is captured with the fields `rows = 3` and `query.table = "users"`.
+    /// let layer = BridgeLayer::new(|event| println!("{:?}", event))
+    ///     .with_inherited_fields(true);
+    /// ```
+    ///
+    /// Inherited fields come after the event's own, from the root span down.
+    /// When names clash, the value closest to the event wins: the event's own
+    /// field over any span's, and a span's over those of the spans it is
+    /// nested in. Only spans that passed the layer's filters are included,
+    /// with the fields they have when the event is emitted.
+    pub fn with_inherited_fields(mut self, inherited_fields: bool) -> Self {
+        self.inherited_fields = inherited_fields;
+        self
+    }
+
+    /// Adds `fields` to every captured event, such as the name and version
+    /// of the service and the environment it runs in, replacing any global
+    /// fields set before.
+    ///
+    /// ```
+    /// use std::collections::HashMap;
+    /// use tracing_bridge::{BridgeLayer, FieldValue};
+    ///
+    /// let fields = HashMap::from([
+    ///     ("service.name".to_owned(), FieldValue::from("checkout")),
+    ///     ("environment".to_owned(), FieldValue::from("production")),
+    /// ]);
+    /// let layer = BridgeLayer::new(|event| println!("{:?}", event))
+    ///     .with_global_fields(fields);
+    /// ```
+    ///
+    /// Global fields come last, after the event's own and any
+    /// [inherited](Self::with_inherited_fields) fields, sorted by name. They
+    /// have the lowest precedence: a global field is left out of an event
+    /// that already has a field with the same name, whether recorded on the
+    /// event itself or inherited from a span. They are added after
+    /// [field filters](Self::with_field_filter) run, and are not truncated
+    /// or redacted.
+    pub fn with_global_fields(mut self, fields: HashMap<String, FieldValue>) -> Self {
+        let mut fields: Vec<_> = fields.into_iter().collect();
+        fields.sort_by(|(a, _), (b, _)| a.cmp(b));
+        self.global_fields = fields.into_iter().collect();
+        self
+    }
+
+    /// Only captures events and spans at `min_level` or more severe.
+    ///
+    /// The level is checked before anything is converted, so rejected events
+    /// cost no more than a comparison. This only affects what the bridge
+    /// captures: other layers still see every event, unless
+    /// [`with_global_filtering`](Self::with_global_filtering) is enabled.
+    pub fn with_min_level(self, min_level: TracingLevel) -> Self {
+        self.filter
+            .update(|config| config.min_level = Some(min_level));
+        self
+    }
+
+    /// Like [`with_min_level`](Self::with_min_level), but takes `tracing`'s
+    /// own [`LevelFilter`], for configurations that already have one.
+    ///
+    /// [`LevelFilter::OFF`] disables the bridge entirely: it captures no
+    /// events or spans, and reports every callsite as disabled from
+    /// [`Layer::enabled`] and [`Layer::register_callsite`], with or without
+    /// [`with_global_filtering`](Self::with_global_filtering), so `tracing`
+    /// skips them. As with global filtering, this disables them for the whole
+    /// subscriber, so other layers in the same stack stop seeing them too. To
+    /// turn off just the bridge, wrap it in a per-layer filter with
+    /// [`Layer::with_filter`] instead.
+    ///
+    /// ```
+    /// use tracing_bridge::BridgeLayer;
+    /// use tracing_core::LevelFilter;
+    ///
+    /// let layer = BridgeLayer::new(|event| println!("{:?}", event))
+    ///     .with_level_filter(LevelFilter::INFO);
+    /// ```
+    pub fn with_level_filter(self, level_filter: LevelFilter) -> Self {
+        self.filter
+            .update(|config| config.set_level_filter(level_filter));
+        self
+    }
+
+    /// Only captures events and spans whose target is one of `targets` or a
+    /// module nested inside one, so `"my_app"` allows `my_app::db` but not
+    /// `my_app_other`.
+    ///
+    /// Like [`with_min_level`](Self::with_min_level), this only affects what
+    /// the bridge captures.
+    pub fn with_target_allowlist<I>(self, targets: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let targets: Vec<String> = targets
+            .into_iter()
+            .map(|target| target.as_ref().to_owned())
+            .collect();
+        self.filter
+            .update(|config| config.target_allow = targets.clone());
+        self
+    }
+
+    /// Never captures events and spans whose target is one of `targets` or a
+    /// module nested inside one. The denylist wins over
+    /// [`with_target_allowlist`](Self::with_target_allowlist) when a target
+    /// matches both.
+    pub fn with_target_denylist<I>(self, targets: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let targets: Vec<String> = targets
+            .into_iter()
+            .map(|target| target.as_ref().to_owned())
+            .collect();
+        self.filter
+            .update(|config| config.target_deny = targets.clone());
+        self
+    }
+
+    /// Only captures events that have a value for the field called `name`.
+    /// Can be called more than once to require several fields.
+    ///
+    /// Events whose callsite doesn't declare the field are rejected before
+    /// they are converted, so this is much cheaper than checking for the
+    /// field with [`with_field_filter`](Self::with_field_filter). Events that
+    /// declare it but leave it empty are converted and then rejected.
+    pub fn with_required_field(mut self, name: impl Into<String>) -> Self {
+        self.field_filter.required.push(name.into());
+        self
+    }
+
+    /// Only captures events whose fields pass `predicate`, e.g. to keep just
+    /// the events for one request id. Replaces any predicate set before.
+    ///
+    /// ```
+    /// use tracing_bridge::{BridgeLayer, FieldValue};
+    ///
+    /// let layer = BridgeLayer::new(|event| println!("{:?}", event))
+    ///     .with_field_filter(|fields| {
+    ///         fields.get("request_id") == Some(&FieldValue::Str("abc123".to_owned()))
+    ///     });
+    /// ```
+    ///
+    /// The predicate is called with the converted fields, so every event that
+    /// passes the level and target filters pays the full cost of conversion
+    /// before it can be rejected. Spans are not filtered by their fields.
+    pub fn with_field_filter<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&TracingFields) -> bool + Send + Sync + 'static,
+    {
+        self.field_filter.predicate = Some(Arc::new(predicate));
+        self
+    }
+
+    /// Formats values recorded with `Debug` as `debug_format` says. Defaults
+    /// to [`DebugFormat::Compact`].
+    pub fn with_debug_format(mut self, debug_format: DebugFormat) -> Self {
+        self.field_options.debug_format = debug_format;
+        self
+    }
+
+    /// Calls `hook` with the metadata of every captured event and span, and
+    /// reports the location it returns, if any, instead of the callsite's
+    /// own file, line and module path.
+    ///
+    /// This lets crates that wrap `tracing` in their own macros, or generate
+    /// callsites in procedural macros, report the logical source of an event
+    /// rather than the place the callsite was expanded. The metadata's name,
+    /// which `tracing` derives from the expansion site, is left as it is.
+    ///
+    /// ```
+    /// use tracing_bridge::{BridgeLayer, Location};
+    ///
+    /// let layer = BridgeLayer::new(|event| println!("{:?}", event))
+    ///     .with_location_override(|metadata| {
+    ///         if metadata.target() != "generated" {
+    ///             return None;
+    ///         }
+    ///         Some(Location {
+    ///             file: Some("schema/orders.sql".to_owned()),
+    ///             line: Some(12),
+    ///             module_path: metadata.module_path().map(str::to_owned),
+    ///         })
+    ///     });
+    /// ```
+    pub fn with_location_override<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&Metadata<'_>) -> Option<Location> + Send + Sync + 'static,
+    {
+        self.location_override = Some(Arc::new(hook));
+        self
+    }
+
+    /// Calls `rewrite` with the target of every captured event and span, and
+    /// reports the target it returns instead, e.g. to group the targets of a
+    /// third-party crate under one name. See [`truncate_target_depth`] for
+    /// the common case of keeping only the first few segments.
+    ///
+    /// Filters and sampling still see the original target, while
+    /// [metrics](Self::with_metrics) count events by the rewritten one.
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    ///
+    /// use tracing_bridge::BridgeLayer;
+    ///
+    /// let layer = BridgeLayer::new(|event| println!("{:?}", event))
+    ///     .with_target_rewrite(|target| {
+    ///         if target.starts_with("hyper::") || target.starts_with("h2::") {
+    ///             Cow::Borrowed("http")
+    ///         } else {
+    ///             Cow::Borrowed(target)
+    ///         }
+    ///     });
+    /// ```
+    ///
+    /// [`truncate_target_depth`]: crate::truncate_target_depth
+    pub fn with_target_rewrite<F>(mut self, rewrite: F) -> Self
+    where
+        F: Fn(&str) -> Cow<'_, str> + Send + Sync + 'static,
+    {
+        self.target_rewrite = Some(Arc::new(rewrite));
+        self
+    }
+
+    /// Calls `extractor` with the metadata and extensions of the spans each
+    /// event is in, nearest first, and adds the field it returns for the
+    /// first span it returns one for to the event. This pulls in data other
+    /// layers keep in span extensions, such as the trace and span ids of
+    /// `tracing-opentelemetry`, so that captured events can be correlated
+    /// with distributed traces.
+    ///
+    /// ```
+    /// use tracing_bridge::{BridgeLayer, FieldValue};
+    ///
+    /// /// Inserted into span extensions by another layer.
+    /// struct TraceId(u128);
+    ///
+    /// let layer = BridgeLayer::new(|event| println!("{:?}", event))
+    ///     .with_span_extension_extractor(|_metadata, extensions| {
+    ///         let TraceId(id) = extensions.get::<TraceId>()?;
+    ///         Some(("trace_id".to_owned(), FieldValue::from(format!("{:032x}", id))))
+    ///     });
+    /// ```
+    ///
+    /// The extractor sees the span's extensions rather than the span itself
+    /// because the layer doesn't know the type of the subscriber it is used
+    /// with. Extensions are only available from subscribers that store span
+    /// data, such as [`tracing_subscriber::Registry`], and the layer must come
+    /// after the layers whose extensions are read, so that they have been
+    /// inserted by the time the event is seen.
+    ///
+    /// The field comes after the event's own and any
+    /// [inherited](Self::with_inherited_fields) fields, and before
+    /// [global](Self::with_global_fields) ones. It is left out of an event
+    /// that already has a field with the same name, and like global fields
+    /// it is added after [field filters](Self::with_field_filter) run.
+    pub fn with_span_extension_extractor<F>(mut self, extractor: F) -> Self
+    where
+        F: Fn(&Metadata<'_>, &Extensions<'_>) -> Option<(String, FieldValue)>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.span_extension_extractor = Some(Arc::new(extractor));
+        self
+    }
+
+    /// Reports the spans the layer captures with ids from `generator`
+    /// instead of the ids the subscriber assigned, e.g. to keep the ids of
+    /// different runs or hosts apart. See [`IdGenerator`].
+    ///
+    /// The ids are used for [`TracingSpan::id`] and
+    /// [`parent_id`](TracingSpan::parent_id), in [`SpanEvent`]s and in
+    /// events' [`span_context`](crate::TracingEvent::span_context), so that
+    /// events still join to their spans. Spans that didn't pass the layer's
+    /// filters, and so were never reported, keep the subscriber's ids. An
+    /// event's [`current_span_id`](crate::TracingEvent::current_span_id)
+    /// also stays the subscriber's id, since that is the id other layers
+    /// know the span by.
+    pub fn with_id_generator<G: IdGenerator>(mut self, generator: G) -> Self {
+        self.id_generator = Some(Arc::new(generator));
+        self
+    }
+
+    /// Sets the maximum length in bytes of captured string values, or `None`
+    /// to capture values of any length. Defaults to 8 KiB.
+    ///
+    /// Longer values are cut short, never in the middle of a character.
+    /// String and `Debug` field values are then captured as
+    /// [`FieldValue::Truncated`], which records their original length, while
+    /// the event's message and each message of an error chain are suffixed
+    /// with a marker saying how many bytes were dropped.
+    pub fn with_max_field_len(mut self, max_field_len: Option<usize>) -> Self {
+        self.field_options.max_field_len = max_field_len;
+        self
+    }
+
+    /// Escapes ASCII control characters other than tab in captured string
+    /// values, `Debug` values, error messages and event messages, so that
+    /// strict parsers downstream don't reject them. Defaults to `false`.
+    ///
+    /// Characters are escaped as `char::escape_debug` escapes them, e.g.
+    /// `\0`, `\n` and `\u{1b}`, so [`DebugFormat::Pretty`] values are
+    /// captured on a single line. The escapes count towards
+    /// [`with_max_field_len`](Self::with_max_field_len).
+    pub fn with_escaped_control_chars(mut self, escape: bool) -> Self {
+        self.field_options.escape_control = escape;
+        self
+    }
+
+    /// Returns a handle for replacing the layer's level and target filters
+    /// while it is running, e.g. from a control endpoint.
+    ///
+    /// ```
+    /// use tracing_bridge::{BridgeLayer, FilterConfig, TracingLevel};
+    ///
+    /// let layer = BridgeLayer::new(|event| println!("{:?}", event));
+    /// let filter = layer.filter_handle();
+    ///
+    /// filter.set(FilterConfig {
+    ///     min_level: Some(TracingLevel::Warn),
+    ///     ..FilterConfig::default()
+    /// });
+    /// ```
+    pub fn filter_handle(&self) -> FilterHandle {
+        self.filter.clone()
+    }
+
+    /// Returns a guard that shuts down the sink when dropped, so that events
+    /// it buffers aren't lost when the program exits. See [`FlushGuard`].
+    pub fn flush_guard(&self) -> FlushGuard {
+        let error_handler = self.error_handler.clone();
+        let drop_stats = self.drop_stats.clone();
+        FlushGuard::new(
+            self.sink.clone(),
+            Box::new(move |error| handle_error(error_handler.as_ref(), &drop_stats, error)),
+        )
+    }
+
+    /// Emits the event reporting that `suppressed` events from the callsite
+    /// described by `metadata` were dropped by the rate limit.
+    fn emit_summary(&self, metadata: &Metadata<'_>, suppressed: u64, now: SystemTime) {
+        let mut callsite = TracingMetadata::from(metadata);
+        if let Some(location) = self.location(metadata) {
+            callsite.relocate(&location);
+        }
+        if let Some(target) = self.target(metadata) {
+            callsite.target = target;
+        }
+        let mut summary = rate_limit::summary(&callsite, suppressed).with_current_thread();
+        summary.timestamp = unix_nanos(now);
+        summary.monotonic_nanos = Some(self.baseline.elapsed_nanos());
+        summary.seq = Some(self.next_seq.fetch_add(1, Ordering::Relaxed));
+        self.report(self.sink.emit(summary));
+    }
+
+    /// Returns whether the layer tells `tracing` that `metadata` is enabled:
+    /// always, unless the layer is disabled or filters globally and rejects
+    /// it.
+    fn enables(&self, metadata: &Metadata<'_>) -> bool {
+        let filter = self.filter.load();
+        !filter.disabled && (!self.global_filtering || filter.enabled(metadata))
+    }
+
+    fn location(&self, metadata: &Metadata<'_>) -> Option<Location> {
+        self.location_override
+            .as_ref()
+            .and_then(|hook| hook(metadata))
+    }
+
+    /// Returns the rewritten target, or `None` if it is unchanged.
+    fn target(&self, metadata: &Metadata<'_>) -> Option<String> {
+        let rewrite = self.target_rewrite.as_ref()?;
+        match rewrite(metadata.target()) {
+            Cow::Borrowed(target) if target == metadata.target() => None,
+            target => Some(target.into_owned()),
+        }
+    }
+
+    fn count(&self, level: TracingLevel, target: &str) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record(level, target);
+        }
+    }
+
+    fn report(&self, result: Result<(), SinkError>) {
+        if let Err(error) = result {
+            handle_error(self.error_handler.as_ref(), &self.drop_stats, error);
+        }
+    }
+
+    /// Returns a reference to the sink events are forwarded to.
+    pub fn sink(&self) -> &K {
+        &self.sink
+    }
+}
+
+impl<S, K> Layer<S> for BridgeLayer<K>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    K: EventSink,
+{
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        if self.enables(metadata) {
+            Interest::always()
+        } else {
+            Interest::never()
+        }
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, _ctx: Context<'_, S>) -> bool {
+        self.enables(metadata)
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        let filter = self.filter.load();
+        if !self.global_filtering && !filter.disabled {
+            return None;
+        }
+        filter.level_hint()
+    }
+
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if !self.filter.load().enabled(attrs.metadata()) {
+            return;
+        }
+        let span = match ctx.span(id) {
+            Some(span) => span,
+            None => return,
+        };
+        let (id, parent_id) = match &self.id_generator {
+            Some(generator) => (
+                generator.next_span_id(),
+                span.parent().map(|parent| reported_id(&parent)),
+            ),
+            None => (
+                id.into_u64(),
+                span.parent().map(|parent| parent.id().into_u64()),
+            ),
+        };
+        let mut captured = TracingSpan::new(id, parent_id, attrs, &self.field_options);
+        if let Some(location) = self.location(attrs.metadata()) {
+            captured.metadata.relocate(&location);
+        }
+        if let Some(target) = self.target(attrs.metadata()) {
+            captured.metadata.target = target;
+        }
+
+        let mut extensions = span.extensions_mut();
+        extensions.insert(captured.clone());
+        extensions.insert(SpanTimings::new());
+        drop(extensions);
+        self.report(self.sink.emit_span(SpanEvent::New(captured)));
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        if !self.filter.load().enabled(metadata)
+            || !self.field_filter.declared(metadata)
+            || !self.sampler.sample(metadata.level().into())
+        {
+            self.drop_stats.record_filtered();
+            return;
+        }
+        let now = self.clock.now();
+        if let Some(limiter) = &self.rate_limiter {
+            for (callsite, suppressed) in limiter.due_summaries(now) {
+                self.emit_summary(callsite, suppressed, now);
+            }
+            if !limiter.check(metadata, now) {
+                self.drop_stats.record_rate_limited();
+                return;
+            }
+        }
+        let mut borrowed = TracingEventRef::with_options(event, &self.field_options);
+        borrowed.timestamp = unix_nanos(now);
+        borrowed.monotonic_nanos = Some(self.baseline.elapsed_nanos());
+        borrowed.thread = Some(std::thread::current());
+        borrowed.location = self.location(metadata);
+        borrowed.target = self.target(metadata);
+        // `event_scope` starts from the event's explicit `parent:` if it has
+        // one, and from the current span only if the event is contextual, so
+        // `parent: None` events are in no spans.
+        if let Some(scope) = ctx.event_scope(event) {
+            borrowed.span_context = match self.id_generator {
+                Some(_) => scope.from_root().map(|span| reported_id(&span)).collect(),
+                None => scope.from_root().map(|span| span.id().into_u64()).collect(),
+            };
+        }
+        borrowed.current_span_id = ctx.current_span().id().map(Id::into_u64);
+
+        // Only convert the event if the layer itself needs its fields.
+        if self.field_filter.is_empty()
+            && !self.inherited_fields
+            && self.span_extension_extractor.is_none()
+            && self.global_fields.is_empty()
+        {
+            borrowed.seq = Some(self.next_seq.fetch_add(1, Ordering::Relaxed));
+            self.count(metadata.level().into(), borrowed.target());
+            self.report(self.sink.emit_ref(&borrowed));
+            return;
+        }
+
+        let mut captured = borrowed.to_owned();
+        if !self.field_filter.matches(&captured.fields) {
+            self.drop_stats.record_filtered();
+            return;
+        }
+        captured.seq = Some(self.next_seq.fetch_add(1, Ordering::Relaxed));
+        if self.inherited_fields {
+            if let Some(scope) = ctx.event_scope(event) {
+                inherit_fields(&mut captured.fields, scope);
+            }
+        }
+        if let Some(extractor) = &self.span_extension_extractor {
+            if let Some(scope) = ctx.event_scope(event) {
+                extract_span_extension(&mut captured.fields, scope, extractor);
+            }
+        }
+        add_global_fields(&mut captured.fields, &self.global_fields);
+        self.count(captured.metadata.level, &captured.metadata.target);
+        self.report(self.sink.emit(captured));
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(captured) = span.extensions_mut().get_mut::<TracingSpan>() {
+                captured.record(values, span.metadata().fields(), &self.field_options);
+            }
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(id) = update_timings(id, &ctx, SpanTimings::enter) {
+            self.report(self.sink.emit_span(SpanEvent::Enter(id)));
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        if let Some(id) = update_timings(id, &ctx, SpanTimings::exit) {
+            self.report(self.sink.emit_span(SpanEvent::Exit(id)));
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let span = match ctx.span(&id) {
+            Some(span) => span,
+            None => return,
+        };
+        let mut extensions = span.extensions_mut();
+        let mut captured = match extensions.remove::<TracingSpan>() {
+            Some(captured) => captured,
+            None => return,
+        };
+        if let Some(timings) = extensions.remove::<SpanTimings>() {
+            timings.close(&mut captured);
+        }
+        drop(extensions);
+        self.report(self.sink.emit_span(SpanEvent::Close(captured)));
+    }
+}
+
+type LocationOverride = Arc<dyn Fn(&Metadata<'_>) -> Option<Location> + Send + Sync>;
+
+type TargetRewrite = Arc<dyn Fn(&str) -> Cow<'_, str> + Send + Sync>;
+
+type SpanExtensionExtractor =
+    Arc<dyn Fn(&Metadata<'_>, &Extensions<'_>) -> Option<(String, FieldValue)> + Send + Sync>;
+
+pub(crate) type ErrorHandler = Arc<dyn Fn(SinkError) + Send + Sync>;
+
+/// Passes `error` to `handler`, or counts it in `drop_stats` if there is no
+/// handler.
+pub(crate) fn handle_error(
+    handler: Option<&ErrorHandler>,
+    drop_stats: &DropStats,
+    error: SinkError,
+) {
+    match handler {
+        Some(handler) => handler(error),
+        None => drop_stats.record_sink_error(&error),
+    }
+}
+
+/// Appends the fields of the spans in `scope`, which starts at the event's
+/// parent, to the event's `fields`, skipping any whose names are already
+/// taken by a field closer to the event.
+fn inherit_fields<'a, R: LookupSpan<'a>>(fields: &mut TracingFields, scope: Scope<'a, R>) {
+    let mut taken: HashSet<String> = fields.iter().map(|(name, _)| name.to_owned()).collect();
+    let mut inherited = Vec::new();
+    for span in scope {
+        let extensions = span.extensions();
+        let captured = match extensions.get::<TracingSpan>() {
+            Some(captured) => captured,
+            None => continue,
+        };
+        let own: Vec<_> = captured
+            .fields
+            .iter()
+            .map(|(name, value)| {
+                (
+                    format!("{}.{}", captured.metadata.name, name),
+                    value.clone(),
+                )
+            })
+            .filter(|(name, _)| !taken.contains(name))
+            .collect();
+        taken.extend(own.iter().map(|(name, _)| name.clone()));
+        inherited.push(own);
+    }
+    for (name, value) in inherited.into_iter().rev().flatten() {
+        fields.push(name, value);
+    }
+}
+
+/// Appends the field `extractor` returns for the nearest span in `scope` it
+/// returns one for, unless `fields` already has a field with its name.
+fn extract_span_extension<'a, R: LookupSpan<'a>>(
+    fields: &mut TracingFields,
+    mut scope: Scope<'a, R>,
+    extractor: &SpanExtensionExtractor,
+) {
+    let extracted = scope.find_map(|span| extractor(span.metadata(), &span.extensions()));
+    if let Some((name, value)) = extracted {
+        if fields.get(&name).is_none() {
+            fields.push(name, value);
+        }
+    }
+}
+
+/// Appends the global fields whose names `fields` doesn't already have.
+fn add_global_fields(fields: &mut TracingFields, global: &TracingFields) {
+    if global.is_empty() {
+        return;
+    }
+    let taken: HashSet<String> = fields.iter().map(|(name, _)| name.to_owned()).collect();
+    for (name, value) in global.iter() {
+        if !taken.contains(name) {
+            fields.push(name, value.clone());
+        }
+    }
+}
+
+/// Applies `update` to the timings of the span with the given id, returning
+/// the id the span was reported with if it was captured when it was created,
+/// i.e. if it passed the layer's filter.
+fn update_timings<S>(id: &Id, ctx: &Context<'_, S>, update: fn(&mut SpanTimings)) -> Option<u64>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let span = ctx.span(id)?;
+    let mut extensions = span.extensions_mut();
+    update(extensions.get_mut::<SpanTimings>()?);
+    Some(
+        extensions
+            .get_mut::<TracingSpan>()
+            .map_or(id.into_u64(), |captured| captured.id),
+    )
+}
+
+/// Returns the id `span` was reported with, or the id the subscriber
+/// assigned if it wasn't captured.
+fn reported_id<'a, R: LookupSpan<'a>>(span: &SpanRef<'a, R>) -> u64 {
+    match span.extensions().get::<TracingSpan>() {
+        Some(captured) => captured.id,
+        None => span.id().into_u64(),
+    }
+}
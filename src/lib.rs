@@ -1,44 +1,1038 @@
-use serde::{Serialize, Deserialize};
+//! Without the default `std` feature the crate is `no_std` and only needs
+//! `alloc`: the event types, their conversions from `tracing_core` and their
+//! serde implementations remain, while the layer, subscriber and sinks, and
+//! anything that reads the current thread, process or time, are absent.
 
-use std::{collections::HashMap, path::PathBuf};
+#![cfg_attr(not(feature = "std"), no_std)]
 
-#[derive(Debug, Serialize, Deserialize, Eq, PartialEq)]
+extern crate alloc;
+
+use alloc::{borrow::ToOwned, format, string::String, vec::Vec};
+use core::{convert::TryFrom, fmt};
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "std")]
+use std::path::Path;
+
+mod builder;
+#[cfg(feature = "std")]
+mod clock;
+#[cfg(feature = "ecs")]
+mod ecs;
+mod encoding;
+#[cfg(feature = "std")]
+mod event_ref;
+#[cfg(feature = "std")]
+mod filter;
+mod flat;
+mod float;
+mod format;
+#[cfg(feature = "bincode")]
+mod framed;
+#[cfg(feature = "gelf")]
+mod gelf;
+#[cfg(feature = "std")]
+mod header;
+#[cfg(feature = "std")]
+mod id;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "std")]
+mod layer;
+#[cfg(feature = "log")]
+mod log;
+mod logfmt;
+#[cfg(feature = "std")]
+mod merge;
+#[cfg(feature = "valuable")]
+mod nested;
+mod pretty;
+mod priority;
+#[cfg(feature = "std")]
+mod rate_limit;
+mod redact;
+#[cfg(feature = "std")]
+mod replay;
+#[cfg(feature = "std")]
+mod sample;
+#[cfg(feature = "schemars")]
+mod schema;
+mod semantic;
+#[cfg(feature = "std")]
+mod sink;
+#[cfg(feature = "std")]
+mod span;
+#[cfg(feature = "std")]
+mod stats;
+#[cfg(feature = "std")]
+mod subscriber;
+#[cfg(feature = "std")]
+mod syslog;
+#[cfg(feature = "std")]
+mod table;
+#[cfg(feature = "std")]
+mod target;
+mod time;
+mod timestamp;
+#[cfg(feature = "tokio")]
+pub mod tokio;
+
+pub use builder::TracingEventBuilder;
+#[cfg(feature = "std")]
+pub use clock::{Clock, FixedClock, ManualClock, MonotonicBaseline, SystemClock};
+#[cfg(feature = "ecs")]
+pub use ecs::ECS_VERSION;
+#[cfg(feature = "std")]
+pub use event_ref::TracingEventRef;
+#[cfg(feature = "std")]
+pub use filter::{FilterConfig, FilterHandle};
+pub use flat::TracingEventFlat;
+pub use format::{EventFormat, FormattedEvent};
+#[cfg(feature = "bincode")]
+pub use framed::{Frame, FramedReader, FramedWriter};
+#[cfg(feature = "std")]
+pub use header::StreamHeader;
+#[cfg(feature = "std")]
+pub use id::{IdGenerator, SequentialIds};
+#[cfg(feature = "std")]
+pub use layer::BridgeLayer;
+#[cfg(feature = "std")]
+pub use merge::{merge_sorted, MergeSorted};
+pub use priority::{LevelFormat, PrioritySchema};
+pub use redact::{redact_keys, REDACTED};
+#[cfg(feature = "std")]
+pub use replay::forward;
+#[cfg(feature = "schemars")]
+pub use schema::{event_json_schema, EVENT_SCHEMA_VERSION};
+#[cfg(all(feature = "json", any(feature = "gzip", feature = "zstd")))]
+pub use sink::compressed::CompressedSink;
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+pub use sink::compressed::{CompressedWriter, Compression};
+#[cfg(feature = "json")]
+pub use sink::json_array::JsonArraySink;
+#[cfg(feature = "json")]
+pub use sink::json_lines::{read_json_lines, InvalidLinePolicy, JsonLinesReader, JsonLinesSink};
+#[cfg(feature = "json")]
+pub use sink::non_blocking::NonBlockingSink;
+#[cfg(feature = "bincode")]
+pub use sink::serializer::BincodeSerializer;
+#[cfg(feature = "msgpack")]
+pub use sink::serializer::MsgpackSerializer;
+#[cfg(feature = "json")]
+pub use sink::serializer::{JsonLinesSerializer, JsonSerializer};
+#[cfg(feature = "std")]
+pub use sink::{
+    batching::BatchingSink,
+    capturing::CapturingSink,
+    channel::{bounded_channel, channel, ChannelSink, OverflowPolicy},
+    error::{SinkError, SinkErrorKind},
+    fan_out::FanOutSink,
+    ring_buffer::RingBufferSink,
+    serializer::EventSerializer,
+    writer::WriterSink,
+    EventSink, FlushGuard,
+};
+#[cfg(feature = "std")]
+pub use span::{SpanEvent, TracingSpan};
+#[cfg(feature = "std")]
+pub use stats::{DropCounts, DropStats, EventMetrics};
+#[cfg(feature = "std")]
+pub use subscriber::BridgeSubscriber;
+#[cfg(feature = "std")]
+pub use table::{CompactEvent, CompactLog, MetadataTable, UnknownMetadataId};
+#[cfg(feature = "std")]
+pub use target::truncate_target_depth;
+pub use timestamp::TimestampFormat;
+
+/// An event captured from `tracing`.
+///
+/// Events are often stored for a long time and read by other versions of
+/// the crate, so only `metadata` and `fields` are needed to deserialize one:
+/// every property added since is filled in with its default when missing,
+/// and properties this version doesn't know are ignored.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct TracingEvent {
     pub metadata: TracingMetadata,
-    pub fields: HashMap<String, String>,
+
+    /// The event's message, if it has one. This is recorded by `tracing` as
+    /// a field named `message`, but is stored here rather than in `fields`.
+    ///
+    /// It is the message as `tracing`'s own formatters display it. Inline
+    /// arguments such as `{count}` in `info!(count, "processed {count} items")`
+    /// are resolved by `format_args!` where the event is emitted, from the
+    /// variable `count` rather than the field, so they are already filled in
+    /// when the message is captured.
+    #[serde(default)]
+    pub message: Option<String>,
+
+    pub fields: TracingFields,
+
+    /// When the event was captured, in nanoseconds since the Unix epoch, or
+    /// `None` if no time was recorded.
+    ///
+    /// It serializes as nanoseconds unless written in another
+    /// `TimestampFormat` through an `EventFormat`, so JSON may hold it as
+    /// nanoseconds, milliseconds or an RFC 3339 string.
+    #[serde(default, deserialize_with = "timestamp::deserialize")]
+    #[cfg_attr(feature = "schemars", schemars(schema_with = "schema::timestamp"))]
+    pub timestamp: Option<u64>,
+
+    /// When the event was captured, in nanoseconds since the capturing layer
+    /// or subscriber's `MonotonicBaseline`, or `None` if not recorded.
+    ///
+    /// It is read from a monotonic clock, so unlike
+    /// [`timestamp`](Self::timestamp) it doesn't jump when the wall clock is
+    /// adjusted, and the difference between two events captured by the same
+    /// layer is the time that passed between them. Values from different
+    /// layers or processes can't be compared.
+    #[serde(default)]
+    pub monotonic_nanos: Option<u64>,
+
+    /// The position of the event among those captured by the same layer or
+    /// subscriber, or `None` if it wasn't captured by one.
+    ///
+    /// Sequence numbers start at 0 and are strictly increasing across all
+    /// threads, so unlike timestamps they order events from one process even
+    /// when the clock is coarse or goes backwards. Combined with a
+    /// [`StreamHeader`], they order the events of a whole process.
+    #[serde(default)]
+    pub seq: Option<u64>,
+
+    /// The id of the thread that emitted the event, formatted with its
+    /// `Debug` implementation (e.g. `ThreadId(2)`), if recorded.
+    #[serde(default)]
+    pub thread_id: Option<String>,
+
+    /// The name of the thread that emitted the event, or `None` if it was
+    /// unnamed or not recorded.
+    #[serde(default)]
+    pub thread_name: Option<String>,
+
+    /// The ids of the spans the event was emitted in, from the root span down
+    /// to the event's immediate parent. Empty if the event had no parent span.
+    /// The parent is the one given with `parent:` if there was one, and
+    /// otherwise the span that was current when the event was emitted.
+    #[serde(default)]
+    pub span_context: Vec<u64>,
+
+    /// The id of the span that was current when the event was emitted, as
+    /// the subscriber assigned it, or `None` if no span was current.
+    ///
+    /// Other layers of the same subscriber, such as `tracing-opentelemetry`,
+    /// know the span by this id, so it joins the event to their data. It is
+    /// usually the last id in [`span_context`](Self::span_context), but not
+    /// for an event given a `parent:` other than the current span, nor when
+    /// the layer reports spans with ids from an `IdGenerator`.
+    #[serde(default)]
+    pub current_span_id: Option<u64>,
+}
+
+impl TracingEvent {
+    /// Returns a builder for an event that didn't come from `tracing`, e.g.
+    /// for tests. See [`TracingEventBuilder`].
+    pub fn builder() -> TracingEventBuilder {
+        TracingEventBuilder::default()
+    }
+
+    /// Converts `event`, stamping it with the current system time.
+    #[cfg(feature = "std")]
+    pub fn now_from(event: &tracing_core::Event<'_>) -> Self {
+        Self {
+            timestamp: unix_nanos(std::time::SystemTime::now()),
+            ..Self::from(event)
+        }
+    }
+
+    /// Records the id and name of the current thread on this event.
+    ///
+    /// Call this on the thread that emitted the event.
+    #[cfg(feature = "std")]
+    pub fn with_current_thread(self) -> Self {
+        let thread = std::thread::current();
+        Self {
+            thread_id: Some(format!("{:?}", thread.id())),
+            thread_name: thread.name().map(str::to_owned),
+            ..self
+        }
+    }
+}
+
+/// Converts a source file path to the form stored in
+/// [`TracingMetadata::file`].
+#[cfg(feature = "std")]
+pub(crate) fn source_file(path: impl AsRef<Path>) -> String {
+    path.as_ref().to_string_lossy().replace('\\', "/")
+}
+
+/// Converts a source file path to the form stored in
+/// [`TracingMetadata::file`]. Without `std` paths are plain strings.
+#[cfg(not(feature = "std"))]
+pub(crate) fn source_file(path: impl AsRef<str>) -> String {
+    path.as_ref().replace('\\', "/")
+}
+
+/// Returns the nanoseconds elapsed between the Unix epoch and `time`, or
+/// `None` if `time` is before the epoch or too far after it to fit in a `u64`.
+#[cfg(feature = "std")]
+pub(crate) fn unix_nanos(time: std::time::SystemTime) -> Option<u64> {
+    let elapsed = time.duration_since(std::time::UNIX_EPOCH).ok()?;
+    TryFrom::try_from(elapsed.as_nanos()).ok()
+}
+
+/// Formats the event as a single human-readable line, e.g.
+/// `WARN my_app::db: connection slow {retries=3, host="db1"}`.
+impl fmt::Display for TracingEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.metadata.level, self.metadata.target)?;
+        if self.message.is_none() && self.fields.is_empty() {
+            return Ok(());
+        }
+
+        f.write_str(":")?;
+        if let Some(message) = &self.message {
+            write!(f, " {}", message)?;
+        }
+        if !self.fields.is_empty() {
+            f.write_str(" {")?;
+            for (i, (name, value)) in self.fields.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{}={}", name, value)?;
+            }
+            f.write_str("}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The value of a field recorded on an event, typed according to the
+/// [`Visit`](tracing_core::field::Visit) method that recorded it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum FieldValue {
+    /// A value recorded with `record_i64`.
+    I64(i64),
+    /// A value recorded with `record_u64`.
+    U64(u64),
+    /// A value recorded with `record_f64`. In JSON, `NaN` and the infinities
+    /// are written as the strings `"NaN"`, `"inf"` and `"-inf"`.
+    F64(#[serde(with = "float")] f64),
+    /// A value recorded with `record_bool`.
+    Bool(bool),
+    /// A value recorded with `record_str`.
+    Str(String),
+    /// The `Debug` representation of a value that could only be recorded with
+    /// `record_debug`.
+    Debug(String),
+    /// An error recorded with `record_error`, captured as the `Display` output
+    /// of the error itself and of each error in its `source()` chain.
+    Error {
+        /// The top-level error message.
+        message: String,
+        /// The messages of the error's causes, nearest cause first.
+        causes: Vec<String>,
+    },
+    /// A value that would have been captured as `Str` or `Debug`, cut short
+    /// because it was longer than the maximum field length (see
+    /// [`BridgeLayer::with_max_field_len`](crate::BridgeLayer::with_max_field_len)).
+    /// A value exactly as long as the maximum is kept whole.
+    Truncated {
+        /// The start of the value: as many whole characters as fit in the
+        /// maximum length.
+        value: String,
+        /// The length in bytes of the whole value.
+        original_len: usize,
+    },
+    /// A structured value recorded with `record_value`, such as a struct,
+    /// map or list, converted into JSON. Requires the `valuable` feature and
+    /// building with `--cfg tracing_unstable`.
+    #[cfg(feature = "valuable")]
+    Nested(#[serde(with = "nested")] serde_json::Value),
+}
+
+impl FieldValue {
+    /// Returns the kind of value, named after the [`Visit`](tracing_core::field::Visit)
+    /// method that recorded it: `"i64"`, `"u64"`, `"f64"`, `"bool"`, `"str"`,
+    /// `"debug"`, `"error"` or `"value"`, or `"truncated"` for a string or
+    /// `Debug` value that was cut short.
+    ///
+    /// `tracing` doesn't pass on a field's static type, so this is the only
+    /// type information kept, such as for picking a column type in a store.
+    pub fn kind_str(&self) -> &'static str {
+        match self {
+            Self::I64(_) => "i64",
+            Self::U64(_) => "u64",
+            Self::F64(_) => "f64",
+            Self::Bool(_) => "bool",
+            Self::Str(_) => "str",
+            Self::Debug(_) => "debug",
+            Self::Error { .. } => "error",
+            Self::Truncated { .. } => "truncated",
+            #[cfg(feature = "valuable")]
+            Self::Nested(_) => "value",
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn from_error(error: &(dyn std::error::Error + 'static), options: &FieldOptions) -> Self {
+        let display = |error: &dyn std::error::Error| {
+            let mut string = options.writer();
+            let _ = fmt::Write::write_fmt(&mut string, format_args!("{}", error));
+            string.finish()
+        };
+
+        let mut causes = Vec::new();
+        let mut source = error.source();
+        while let Some(cause) = source {
+            causes.push(display(cause));
+            source = cause.source();
+        }
+
+        Self::Error {
+            message: display(error),
+            causes,
+        }
+    }
 }
 
-#[derive(Default)]
-struct TracingMetadataFields {
-    pub fields: HashMap<String, String>,
+/// `F64` values are compared by their bit patterns, so `NaN` is equal to
+/// itself and `0.0` is not equal to `-0.0`. This keeps `Eq` lawful.
+impl PartialEq for FieldValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::I64(a), Self::I64(b)) => a == b,
+            (Self::U64(a), Self::U64(b)) => a == b,
+            (Self::F64(a), Self::F64(b)) => a.to_bits() == b.to_bits(),
+            (Self::Bool(a), Self::Bool(b)) => a == b,
+            (Self::Str(a), Self::Str(b)) => a == b,
+            (Self::Debug(a), Self::Debug(b)) => a == b,
+            (
+                Self::Error {
+                    message: a,
+                    causes: a_causes,
+                },
+                Self::Error {
+                    message: b,
+                    causes: b_causes,
+                },
+            ) => a == b && a_causes == b_causes,
+            (
+                Self::Truncated {
+                    value: a,
+                    original_len: a_len,
+                },
+                Self::Truncated {
+                    value: b,
+                    original_len: b_len,
+                },
+            ) => a == b && a_len == b_len,
+            #[cfg(feature = "valuable")]
+            (Self::Nested(a), Self::Nested(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for FieldValue {}
+
+/// Hashes floats by their bits, and nested values by their JSON text, to
+/// agree with `PartialEq`.
+impl core::hash::Hash for FieldValue {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        core::mem::discriminant(self).hash(state);
+        match self {
+            Self::I64(value) => value.hash(state),
+            Self::U64(value) => value.hash(state),
+            Self::F64(value) => value.to_bits().hash(state),
+            Self::Bool(value) => value.hash(state),
+            Self::Str(value) | Self::Debug(value) => value.hash(state),
+            Self::Error { message, causes } => {
+                message.hash(state);
+                causes.hash(state);
+            }
+            Self::Truncated {
+                value,
+                original_len,
+            } => {
+                value.hash(state);
+                original_len.hash(state);
+            }
+            #[cfg(feature = "valuable")]
+            Self::Nested(value) => value.to_string().hash(state),
+        }
+    }
+}
+
+macro_rules! field_value_from {
+    ($($ty:ty => $variant:ident,)*) => {
+        $(
+            impl From<$ty> for FieldValue {
+                fn from(value: $ty) -> Self {
+                    Self::$variant(value.into())
+                }
+            }
+        )*
+    };
 }
 
-impl TracingMetadataFields {
-    fn fields_from_event(event: &tracing_core::Event<'_>) -> HashMap<String, String> {
-        let mut visitor = Self::default();
+field_value_from! {
+    i32 => I64,
+    i64 => I64,
+    u32 => U64,
+    u64 => U64,
+    f64 => F64,
+    bool => Bool,
+    String => Str,
+    &str => Str,
+}
+
+/// Formats the value the way `tracing`'s own formatters would: strings are
+/// quoted, `Debug` output is written as-is, and errors are written as their
+/// message followed by each of their causes, separated by `: `. Truncated
+/// values are written as the part that was kept, followed by a marker saying
+/// how many bytes were dropped. Nested values are written as JSON.
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::I64(value) => write!(f, "{}", value),
+            Self::U64(value) => write!(f, "{}", value),
+            Self::F64(value) => write!(f, "{}", value),
+            Self::Bool(value) => write!(f, "{}", value),
+            Self::Str(value) => write!(f, "{:?}", value),
+            Self::Debug(value) => f.write_str(value),
+            Self::Error { message, causes } => {
+                f.write_str(message)?;
+                for cause in causes {
+                    write!(f, ": {}", cause)?;
+                }
+                Ok(())
+            }
+            Self::Truncated {
+                value,
+                original_len,
+            } => {
+                let dropped = original_len.saturating_sub(value.len());
+                write!(f, "{}… (truncated {} bytes)", value, dropped)
+            }
+            #[cfg(feature = "valuable")]
+            Self::Nested(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// The fields recorded on an event, kept in the order they were recorded.
+///
+/// `tracing` allows a field name to be recorded more than once, e.g. by
+/// `info!(x = 1, x = 2)`, and every value is kept: [`get`](Self::get) returns
+/// the first, and [`get_all`](Self::get_all) and [`iter`](Self::iter) return
+/// them all in recording order.
+///
+/// Serializes as a map whose entries appear in recording order, with a
+/// repeated name appearing once per value, so the same fields always
+/// serialize to the same bytes, which suits hashing or diffing the output.
+/// Equality is order-independent: two sets of fields are equal if they hold
+/// the same name/value pairs, regardless of the order they were recorded in,
+/// except that the values of a repeated name must be in the same order.
+///
+/// Nothing is allocated until the first field is pushed, so events without
+/// fields, such as a bare `info!("started")`, cost nothing for them.
+#[derive(Debug, Clone, Default)]
+pub struct TracingFields {
+    entries: Vec<(String, FieldValue)>,
+}
+
+impl TracingFields {
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the first value recorded for the field called `name`, if any.
+    pub fn get(&self, name: &str) -> Option<&FieldValue> {
+        self.entries
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns every value recorded for the field called `name`, in the order
+    /// they were recorded.
+    ///
+    /// ```
+    /// use tracing_bridge::{FieldValue, TracingFields};
+    ///
+    /// let mut fields = TracingFields::new();
+    /// fields.push("retry", FieldValue::U64(1));
+    /// fields.push("retry", FieldValue::U64(2));
+    ///
+    /// assert_eq!(fields.get("retry"), Some(&FieldValue::U64(1)));
+    /// assert_eq!(
+    ///     fields.get_all("retry").collect::<Vec<_>>(),
+    ///     [&FieldValue::U64(1), &FieldValue::U64(2)],
+    /// );
+    /// ```
+    pub fn get_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a FieldValue> + 'a {
+        self.entries
+            .iter()
+            .filter(move |(key, _)| key == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Appends a value for the field called `name`, keeping any values
+    /// already recorded for it. This is how captured fields are recorded.
+    pub fn push(&mut self, name: impl Into<String>, value: FieldValue) {
+        self.entries.push((name.into(), value));
+    }
+
+    /// Sets the field called `name` to `value`. If the field is already
+    /// present its first value is replaced in place and any others are
+    /// removed, otherwise it is appended.
+    pub fn insert(&mut self, name: impl Into<String>, value: FieldValue) {
+        let name = name.into();
+        let mut value = Some(value);
+        self.entries.retain_mut(|(key, existing)| {
+            if *key != name {
+                true
+            } else if let Some(value) = value.take() {
+                *existing = value;
+                true
+            } else {
+                false
+            }
+        });
+        if let Some(value) = value {
+            self.entries.push((name, value));
+        }
+    }
+
+    /// Sets the field called `name` to `value` as [`insert`](Self::insert)
+    /// does, except that a field not already present is placed before the
+    /// first field that `rank` orders after it, rather than at the end.
+    #[cfg(feature = "std")]
+    pub(crate) fn insert_ranked(
+        &mut self,
+        name: String,
+        value: FieldValue,
+        rank: impl Fn(&str) -> usize,
+    ) {
+        if self.get(&name).is_some() {
+            return self.insert(name, value);
+        }
+        let position = rank(&name);
+        let index = self
+            .entries
+            .iter()
+            .position(|(key, _)| rank(key) > position)
+            .unwrap_or(self.entries.len());
+        self.entries.insert(index, (name, value));
+    }
+
+    /// Iterates over the fields in the order they were recorded.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &FieldValue)> {
+        self.entries
+            .iter()
+            .map(|(key, value)| (key.as_str(), value))
+    }
+
+    fn sorted(&self) -> Vec<&(String, FieldValue)> {
+        let mut entries: Vec<_> = self.entries.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        entries
+    }
+}
+
+impl PartialEq for TracingFields {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.sorted() == other.sorted()
+    }
+}
+
+impl Eq for TracingFields {}
+
+/// Hashes the fields in name order, so that the hash is independent of the
+/// order they were recorded in, like equality.
+impl core::hash::Hash for TracingFields {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.sorted().hash(state);
+    }
+}
+
+impl IntoIterator for TracingFields {
+    type Item = (String, FieldValue);
+    type IntoIter = alloc::vec::IntoIter<(String, FieldValue)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<K: Into<String>> core::iter::FromIterator<(K, FieldValue)> for TracingFields {
+    fn from_iter<I: IntoIterator<Item = (K, FieldValue)>>(iter: I) -> Self {
+        let mut fields = Self::new();
+        fields.extend(iter);
+        fields
+    }
+}
+
+/// Appends every pair as [`push`](TracingFields::push) does, so repeated
+/// names keep all their values and collecting a `TracingFields` back from its
+/// own iterator gives equal fields.
+impl<K: Into<String>> Extend<(K, FieldValue)> for TracingFields {
+    fn extend<I: IntoIterator<Item = (K, FieldValue)>>(&mut self, iter: I) {
+        for (name, value) in iter {
+            self.push(name, value);
+        }
+    }
+}
+
+impl Serialize for TracingFields {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(self.entries.len()))?;
+        for (name, value) in &self.entries {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for TracingFields {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldsVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for FieldsVisitor {
+            type Value = TracingFields;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("a map of field names to values")
+            }
+
+            fn visit_map<A: serde::de::MapAccess<'de>>(
+                self,
+                mut access: A,
+            ) -> Result<Self::Value, A::Error> {
+                let mut fields = TracingFields::new();
+                while let Some((name, value)) = access.next_entry::<String, FieldValue>()? {
+                    fields.push(name, value);
+                }
+                Ok(fields)
+            }
+        }
+
+        deserializer.deserialize_map(FieldsVisitor)
+    }
+}
+
+/// Options controlling how field values are captured.
+#[derive(Clone, Default)]
+pub(crate) struct FieldOptions {
+    /// The maximum length in bytes of captured string values, or `None` for
+    /// no limit.
+    pub max_field_len: Option<usize>,
+
+    /// Called on every captured field before it is stored.
+    pub redactor: Option<redact::Redactor>,
+
+    /// How values recorded with `Debug` are formatted.
+    pub debug_format: DebugFormat,
+
+    /// Whether ASCII control characters other than tab are escaped in
+    /// captured string values.
+    pub escape_control: bool,
+}
+
+/// How field values recorded with their `Debug` implementation are
+/// formatted when captured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DebugFormat {
+    /// `{:?}`, e.g. `Point { x: 1, y: 2 }`.
+    #[default]
+    Compact,
+
+    /// `{:#?}`, spread over several indented lines, which is easier to read
+    /// for large structs.
+    Pretty,
+}
+
+impl FieldOptions {
+    /// The default limit applied by [`BridgeLayer`]: 8 KiB.
+    #[cfg(feature = "std")]
+    pub const DEFAULT_MAX_FIELD_LEN: usize = 8 * 1024;
+
+    fn string(&self, value: &str) -> BoundedString {
+        let mut string = self.writer();
+        let _ = fmt::Write::write_str(&mut string, value);
+        string
+    }
+
+    fn debug(&self, value: &dyn fmt::Debug) -> BoundedString {
+        let mut string = self.writer();
+        let _ = match self.debug_format {
+            DebugFormat::Compact => fmt::Write::write_fmt(&mut string, format_args!("{:?}", value)),
+            DebugFormat::Pretty => fmt::Write::write_fmt(&mut string, format_args!("{:#?}", value)),
+        };
+        string
+    }
+
+    fn writer(&self) -> BoundedString {
+        BoundedString {
+            buf: String::new(),
+            max_len: self.max_field_len.unwrap_or(usize::MAX),
+            dropped: 0,
+            escape_control: self.escape_control,
+        }
+    }
+}
+
+/// A string that stops growing at `max_len` bytes, counting what it drops so
+/// oversized values are never held in memory in full.
+struct BoundedString {
+    buf: String,
+    max_len: usize,
+    dropped: usize,
+    escape_control: bool,
+}
+
+impl BoundedString {
+    /// Returns the string, followed by a marker saying how many bytes were
+    /// dropped if any were.
+    fn finish(mut self) -> String {
+        if self.dropped > 0 {
+            self.buf
+                .push_str(&format!("… (truncated {} bytes)", self.dropped));
+        }
+        self.buf
+    }
+
+    /// Returns the string as a value of kind `whole`, or as a
+    /// [`FieldValue::Truncated`] if any bytes were dropped.
+    fn into_value(self, whole: fn(String) -> FieldValue) -> FieldValue {
+        if self.dropped == 0 {
+            return whole(self.buf);
+        }
+        FieldValue::Truncated {
+            original_len: self.buf.len() + self.dropped,
+            value: self.buf,
+        }
+    }
+
+    fn push(&mut self, s: &str) -> fmt::Result {
+        // Once anything has been dropped nothing more is kept, so the kept
+        // part is always a prefix of the full value.
+        let room = if self.dropped > 0 {
+            0
+        } else {
+            self.max_len - self.buf.len()
+        };
+
+        // Cut at a char boundary so multibyte characters are never split.
+        let mut end = room.min(s.len());
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+
+        self.buf.push_str(&s[..end]);
+        self.dropped += s.len() - end;
+        Ok(())
+    }
+}
+
+impl fmt::Write for BoundedString {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.escape_control {
+            escape_control(s, |s| self.push(s))
+        } else {
+            self.push(s)
+        }
+    }
+}
+
+/// Passes `s` to `write` in pieces, with each ASCII control character other
+/// than tab replaced by its escape as `char::escape_debug` writes it, e.g.
+/// `\0`, `\n` or `\u{1b}`.
+pub(crate) fn escape_control(s: &str, mut write: impl FnMut(&str) -> fmt::Result) -> fmt::Result {
+    let mut rest = s;
+    while let Some(i) = rest.find(|c: char| c.is_ascii_control() && c != '\t') {
+        write(&rest[..i])?;
+        // Control characters are ASCII, so they are a single byte.
+        for escaped in char::from(rest.as_bytes()[i]).escape_debug() {
+            write(escaped.encode_utf8(&mut [0; 4]))?;
+        }
+        rest = &rest[i + 1..];
+    }
+    write(rest)
+}
+
+struct TracingMetadataFields<'a> {
+    pub fields: TracingFields,
+    options: &'a FieldOptions,
+    /// Whether the `message` field is captured separately from the others.
+    capture_message: bool,
+    message: Option<String>,
+}
+
+impl<'a> TracingMetadataFields<'a> {
+    fn new(options: &'a FieldOptions, capture_message: bool) -> Self {
+        Self {
+            fields: TracingFields::new(),
+            options,
+            capture_message,
+            message: None,
+        }
+    }
+
+    /// Returns the fields of `event`, and its message.
+    fn fields_from_event(
+        event: &tracing_core::Event<'_>,
+        options: &FieldOptions,
+    ) -> (TracingFields, Option<String>) {
+        let mut visitor = TracingMetadataFields::new(options, true);
         event.record(&mut visitor);
+        (visitor.fields, visitor.message)
+    }
+
+    #[cfg(feature = "std")]
+    fn fields_from_attributes(
+        attrs: &tracing_core::span::Attributes<'_>,
+        options: &FieldOptions,
+    ) -> TracingFields {
+        let mut visitor = TracingMetadataFields::new(options, false);
+        attrs.record(&mut visitor);
         visitor.fields
     }
+
+    #[cfg(feature = "std")]
+    fn fields_from_record(
+        values: &tracing_core::span::Record<'_>,
+        options: &FieldOptions,
+    ) -> TracingFields {
+        let mut visitor = TracingMetadataFields::new(options, false);
+        values.record(&mut visitor);
+        visitor.fields
+    }
+
+    fn is_message(&self, field: &tracing_core::Field) -> bool {
+        self.capture_message && field.name() == "message"
+    }
+
+    fn insert(&mut self, field: &tracing_core::Field, mut value: FieldValue) {
+        if let Some(redactor) = &self.options.redactor {
+            redactor(field.name(), &mut value);
+        }
+        self.fields.push(field.name(), value);
+    }
 }
 
-impl tracing_core::field::Visit for TracingMetadataFields {
-    fn record_debug(&mut self, field: &tracing_core::Field, value: &dyn std::fmt::Debug) {
-        self.fields.insert(field.name().to_owned(), format!("{:?}", value));
+impl tracing_core::field::Visit for TracingMetadataFields<'_> {
+    fn record_i64(&mut self, field: &tracing_core::Field, value: i64) {
+        self.insert(field, FieldValue::I64(value));
+    }
+
+    fn record_u64(&mut self, field: &tracing_core::Field, value: u64) {
+        self.insert(field, FieldValue::U64(value));
+    }
+
+    fn record_f64(&mut self, field: &tracing_core::Field, value: f64) {
+        self.insert(field, FieldValue::F64(value));
+    }
+
+    fn record_bool(&mut self, field: &tracing_core::Field, value: bool) {
+        self.insert(field, FieldValue::Bool(value));
+    }
+
+    fn record_str(&mut self, field: &tracing_core::Field, value: &str) {
+        let value = self.options.string(value);
+        if self.is_message(field) {
+            self.message = Some(value.finish());
+        } else {
+            self.insert(field, value.into_value(FieldValue::Str));
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn record_error(
+        &mut self,
+        field: &tracing_core::Field,
+        value: &(dyn std::error::Error + 'static),
+    ) {
+        let value = FieldValue::from_error(value, self.options);
+        self.insert(field, value);
+    }
+
+    fn record_debug(&mut self, field: &tracing_core::Field, value: &dyn fmt::Debug) {
+        // The message of `info!("...")` is recorded as `fmt::Arguments`,
+        // whose `Debug` output is the formatted message without quotes.
+        let value = self.options.debug(value);
+        if self.is_message(field) {
+            self.message = Some(value.finish());
+        } else {
+            self.insert(field, value.into_value(FieldValue::Debug));
+        }
+    }
+
+    #[cfg(all(tracing_unstable, feature = "valuable"))]
+    fn record_value(&mut self, field: &tracing_core::Field, value: valuable::Value<'_>) {
+        use valuable::Value as V;
+
+        let value = match value {
+            V::I8(value) => FieldValue::I64(value.into()),
+            V::I16(value) => FieldValue::I64(value.into()),
+            V::I32(value) => FieldValue::I64(value.into()),
+            V::I64(value) => FieldValue::I64(value),
+            V::U8(value) => FieldValue::U64(value.into()),
+            V::U16(value) => FieldValue::U64(value.into()),
+            V::U32(value) => FieldValue::U64(value.into()),
+            V::U64(value) => FieldValue::U64(value),
+            V::F32(value) => FieldValue::F64(value.into()),
+            V::F64(value) => FieldValue::F64(value),
+            V::Bool(value) => FieldValue::Bool(value),
+            V::String(value) => return self.record_str(field, value),
+            V::Error(value) => return self.record_error(field, value),
+            value => FieldValue::Nested(nested::from_valuable(value)),
+        };
+        self.insert(field, value);
     }
 }
 
 impl From<&tracing_core::Event<'_>> for TracingEvent {
     fn from(event: &tracing_core::Event<'_>) -> Self {
-        let fields = TracingMetadataFields::fields_from_event(event);
-        
+        Self::capture(event, &FieldOptions::default())
+    }
+}
+
+impl TracingEvent {
+    pub(crate) fn capture(event: &tracing_core::Event<'_>, options: &FieldOptions) -> Self {
+        let (fields, message) = TracingMetadataFields::fields_from_event(event, options);
+
         Self {
             metadata: event.metadata().into(),
+            message,
             fields,
+            timestamp: None,
+            monotonic_nanos: None,
+            seq: None,
+            thread_id: None,
+            thread_name: None,
+            span_context: Vec::new(),
+            current_span_id: None,
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Hash, Eq, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct TracingMetadata {
     /// The name of the span described by this metadata.
     pub name: String,
@@ -48,6 +1042,10 @@ pub struct TracingMetadata {
     pub target: String,
 
     /// The level of verbosity of the described span.
+    ///
+    /// It serializes as the level's name unless written as a priority
+    /// through an `EventFormat`, so JSON may hold it as a name or a number.
+    #[cfg_attr(feature = "schemars", schemars(schema_with = "schema::level"))]
     pub level: TracingLevel,
 
     /// The name of the Rust module where the span occurred, or `None` if this
@@ -56,7 +1054,11 @@ pub struct TracingMetadata {
 
     /// The name of the source code file where the span occurred, or `None` if
     /// this could not be determined.
-    pub file: Option<PathBuf>,
+    ///
+    /// Always uses `/` as the separator, whatever platform the path came
+    /// from, and replaces any bytes that aren't valid UTF-8 with `U+FFFD`, so
+    /// it reads and deserializes the same everywhere.
+    pub file: Option<String>,
 
     /// The line number in the source code file where the span occurred, or
     /// `None` if this could not be determined.
@@ -64,31 +1066,111 @@ pub struct TracingMetadata {
 
     /// The kind of the callsite.
     pub kind: TracingCallsiteKind,
+
+    /// The names of every field the callsite declares, in declaration order,
+    /// whether or not a value was recorded for them.
+    #[serde(default)]
+    pub declared_fields: Vec<String>,
+}
+
+/// A source location reported in place of the one in a callsite's metadata,
+/// returned by the hook passed to
+/// [`BridgeLayer::with_location_override`](crate::BridgeLayer::with_location_override).
+///
+/// All three parts replace the metadata's, so a `None` part is captured as
+/// unknown rather than left as it was.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Location {
+    /// The source file, normalized like [`TracingMetadata::file`] when it is
+    /// captured.
+    pub file: Option<String>,
+    pub line: Option<u32>,
+    pub module_path: Option<String>,
+}
+
+impl TracingMetadata {
+    /// Replaces the source location with `location`.
+    #[cfg(feature = "std")]
+    pub(crate) fn relocate(&mut self, location: &Location) {
+        self.file = location.file.as_deref().map(source_file);
+        self.line = location.line;
+        self.module_path = location.module_path.clone();
+    }
+
+    /// Returns the source location as `file:line`, e.g. `src/db.rs:42`, or
+    /// `None` if either the file or the line is unknown.
+    ///
+    /// The file is in the `/`-separated form stored in [`Self::file`], so the
+    /// same callsite has the same location whatever platform it was captured
+    /// on.
+    pub fn location(&self) -> Option<String> {
+        match (&self.file, self.line) {
+            (Some(file), Some(line)) => Some(format!("{}:{}", file, line)),
+            _ => None,
+        }
+    }
+
+    /// Guesses whether this metadata describes a span generated by
+    /// `#[tracing::instrument]`, which `tracing` doesn't record.
+    ///
+    /// The guess is that a span is instrumented if its name is a Rust
+    /// identifier, as a function name is, and its target is its module path,
+    /// as it is unless the attribute overrides it. This is only a heuristic:
+    /// spans created by `span!` with an identifier for a name are also
+    /// reported as instrumented, and `#[instrument]` spans whose name or
+    /// target is overridden are not. Events are never instrumented, but the
+    /// spans in their [`span_context`](TracingEvent::span_context) may be.
+    ///
+    /// ```
+    /// use tracing_bridge::{TracingCallsiteKind, TracingEvent};
+    ///
+    /// let mut metadata = TracingEvent::builder()
+    ///     .name("load_user")
+    ///     .target("my_app::db")
+    ///     .module_path("my_app::db")
+    ///     .build()
+    ///     .metadata;
+    /// metadata.kind = TracingCallsiteKind::Span;
+    /// assert!(metadata.looks_instrumented());
+    ///
+    /// metadata.name = "loading user".to_owned();
+    /// assert!(!metadata.looks_instrumented());
+    /// ```
+    pub fn looks_instrumented(&self) -> bool {
+        let mut chars = self.name.chars();
+        let is_identifier = chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+            && chars.all(|c| c.is_alphanumeric() || c == '_');
+        self.kind == TracingCallsiteKind::Span
+            && is_identifier
+            && self.module_path.as_deref() == Some(self.target.as_str())
+    }
 }
 
 impl From<&tracing_core::Metadata<'_>> for TracingMetadata {
     fn from(metadata: &tracing_core::Metadata<'_>) -> Self {
-        let kind = if metadata.is_event() {
-            TracingCallsiteKind::Event
-        } else if metadata.is_span() {
-            TracingCallsiteKind::Span
-        } else {
-            panic!("Unknown callsite kind for metadata: {:?}", metadata);
-        };
-
         Self {
             name: metadata.name().to_owned(),
             target: metadata.target().to_owned(),
             level: metadata.level().into(),
             module_path: metadata.module_path().map(|path| path.into()),
-            file: metadata.file().map(|file| file.into()),
+            file: metadata.file().map(source_file),
             line: metadata.line(),
-            kind,
+            kind: TracingCallsiteKind::of(metadata),
+            declared_fields: declared_fields(metadata.fields()),
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Hash, Eq, PartialEq)]
+/// Returns the names of the fields in `fields`, allocated once at the exact
+/// size, since `FieldSet`'s iterator doesn't report its length.
+fn declared_fields(fields: &tracing_core::field::FieldSet) -> Vec<String> {
+    let mut names = Vec::with_capacity(fields.len());
+    names.extend(fields.iter().map(|field| field.name().to_owned()));
+    names
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum TracingLevel {
     /// The "trace" level.
     ///
@@ -112,41 +1194,230 @@ pub enum TracingLevel {
     Error,
 }
 
+impl TracingLevel {
+    /// Ranks levels from least (`Trace`) to most (`Error`) severe.
+    pub(crate) fn severity(&self) -> u8 {
+        match self {
+            Self::Trace => 0,
+            Self::Debug => 1,
+            Self::Info => 2,
+            Self::Warn => 3,
+            Self::Error => 4,
+        }
+    }
+}
+
+impl TracingLevel {
+    /// Returns the OpenTelemetry `SeverityNumber` for the level: the lowest
+    /// number of the level's range in the OpenTelemetry logs data model.
+    pub fn severity_number(&self) -> u8 {
+        self.to_priority(PrioritySchema::Otel)
+    }
+
+    /// Returns the level whose OpenTelemetry severity range contains
+    /// `severity_number`, or `None` if it is outside `1..=24`.
+    ///
+    /// OpenTelemetry's `FATAL` range (21–24) has no equivalent level, so it is
+    /// mapped to `Error`.
+    pub fn from_severity_number(severity_number: u8) -> Option<Self> {
+        match severity_number {
+            1..=4 => Some(Self::Trace),
+            5..=8 => Some(Self::Debug),
+            9..=12 => Some(Self::Info),
+            13..=16 => Some(Self::Warn),
+            17..=24 => Some(Self::Error),
+            _ => None,
+        }
+    }
+}
+
+/// Levels are ordered by severity, so `Trace < Debug < Info < Warn < Error`.
+///
+/// Note that this is the reverse of `tracing_core::Level`'s ordering, which
+/// considers more verbose levels to be "greater".
+impl Ord for TracingLevel {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.severity().cmp(&other.severity())
+    }
+}
+
+impl PartialOrd for TracingLevel {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Formats the level the same way `tracing_core::Level` does, e.g. `WARN`.
+impl fmt::Display for TracingLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl TracingLevel {
+    /// Returns the uppercase name of the level, e.g. `"WARN"`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Trace => "TRACE",
+            Self::Debug => "DEBUG",
+            Self::Info => "INFO",
+            Self::Warn => "WARN",
+            Self::Error => "ERROR",
+        }
+    }
+}
+
+/// Parses a level name such as `"warn"` or `"INFO"`, ignoring case.
+impl core::str::FromStr for TracingLevel {
+    type Err = ParseLevelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            Self::Trace,
+            Self::Debug,
+            Self::Info,
+            Self::Warn,
+            Self::Error,
+        ]
+        .iter()
+        .find(|level| level.as_str().eq_ignore_ascii_case(s))
+        .copied()
+        .ok_or_else(|| ParseLevelError {
+            input: s.to_owned(),
+        })
+    }
+}
+
+/// The error returned when parsing an unknown level name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseLevelError {
+    input: String,
+}
+
+impl fmt::Display for ParseLevelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown level {:?}, expected one of TRACE, DEBUG, INFO, WARN or ERROR",
+            self.input
+        )
+    }
+}
+
+impl core::error::Error for ParseLevelError {}
+
+/// `tracing_core`'s levels are a closed set, and this match is exhaustive,
+/// so a new level would stop it compiling rather than be mishandled.
 impl From<&tracing_core::Level> for TracingLevel {
     fn from(level: &tracing_core::Level) -> Self {
-        match level {
-            &tracing_core::Level::TRACE => Self::Trace,
-            &tracing_core::Level::DEBUG => Self::Debug,
-            &tracing_core::Level::INFO => Self::Info,
-            &tracing_core::Level::WARN => Self::Warn,
-            &tracing_core::Level::ERROR => Self::Error,
+        match *level {
+            tracing_core::Level::TRACE => Self::Trace,
+            tracing_core::Level::DEBUG => Self::Debug,
+            tracing_core::Level::INFO => Self::Info,
+            tracing_core::Level::WARN => Self::Warn,
+            tracing_core::Level::ERROR => Self::Error,
         }
     }
 }
 
-impl Into<tracing_core::Level> for &TracingLevel {
-    fn into(self) -> tracing_core::Level {
-        match self {
-            &TracingLevel::Trace => tracing_core::Level::TRACE,
-            &TracingLevel::Debug => tracing_core::Level::DEBUG,
-            &TracingLevel::Info => tracing_core::Level::INFO,
-            &TracingLevel::Warn => tracing_core::Level::WARN,
-            &TracingLevel::Error => tracing_core::Level::ERROR,
+impl From<&TracingLevel> for tracing_core::Level {
+    fn from(level: &TracingLevel) -> Self {
+        match level {
+            TracingLevel::Trace => Self::TRACE,
+            TracingLevel::Debug => Self::DEBUG,
+            TracingLevel::Info => Self::INFO,
+            TracingLevel::Warn => Self::WARN,
+            TracingLevel::Error => Self::ERROR,
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Hash, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Hash, Eq, PartialEq)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub enum TracingCallsiteKind {
     Event,
     Span,
+    /// An `enabled!` hint callsite, which never produces events or spans.
+    ///
+    /// Hints that also count as events or spans are captured as `Event` or
+    /// `Span`. Callsites of any kind this crate does not recognize are
+    /// captured as hints when converting metadata, so that it never fails, but
+    /// converting their [`Kind`](tracing_core::metadata::Kind) on its own
+    /// returns a [`ConversionError`].
+    Hint,
 }
 
-impl Into<tracing_core::metadata::Kind> for &TracingCallsiteKind {
-    fn into(self) -> tracing_core::metadata::Kind {
-        match self {
-            &TracingCallsiteKind::Event => tracing_core::metadata::Kind::EVENT,
-            &TracingCallsiteKind::Span => tracing_core::metadata::Kind::SPAN,
+/// `tracing_core`'s `Kind` is a set of flags that may gain new ones, so a
+/// kind that is neither an event, a span nor a hint can't be converted.
+impl TryFrom<&tracing_core::metadata::Kind> for TracingCallsiteKind {
+    type Error = ConversionError;
+
+    fn try_from(kind: &tracing_core::metadata::Kind) -> Result<Self, Self::Error> {
+        if kind.is_event() {
+            Ok(Self::Event)
+        } else if kind.is_span() {
+            Ok(Self::Span)
+        } else if kind.is_hint() {
+            Ok(Self::Hint)
+        } else {
+            Err(ConversionError::new("callsite kind", kind))
+        }
+    }
+}
+
+impl TracingCallsiteKind {
+    /// Returns the kind of the callsite `metadata` describes.
+    pub(crate) fn of(metadata: &tracing_core::Metadata<'_>) -> Self {
+        if metadata.is_event() {
+            Self::Event
+        } else if metadata.is_span() {
+            Self::Span
+        } else {
+            Self::Hint
         }
     }
-}
\ No newline at end of file
+}
+
+impl TryFrom<tracing_core::metadata::Kind> for TracingCallsiteKind {
+    type Error = ConversionError;
+
+    fn try_from(kind: tracing_core::metadata::Kind) -> Result<Self, Self::Error> {
+        Self::try_from(&kind)
+    }
+}
+
+impl From<&TracingCallsiteKind> for tracing_core::metadata::Kind {
+    fn from(kind: &TracingCallsiteKind) -> Self {
+        match kind {
+            TracingCallsiteKind::Event => Self::EVENT,
+            TracingCallsiteKind::Span => Self::SPAN,
+            TracingCallsiteKind::Hint => Self::HINT,
+        }
+    }
+}
+
+/// The error returned when a `tracing` value has no equivalent in this crate,
+/// for example one added by a newer version of `tracing` than this crate
+/// knows about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConversionError {
+    what: &'static str,
+    value: String,
+}
+
+impl ConversionError {
+    pub(crate) fn new(what: &'static str, value: &dyn fmt::Debug) -> Self {
+        Self {
+            what,
+            value: format!("{:?}", value),
+        }
+    }
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unrecognized {}: {}", self.what, self.value)
+    }
+}
+
+impl core::error::Error for ConversionError {}
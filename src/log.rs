@@ -0,0 +1,70 @@
+use crate::{
+    source_file, TracingCallsiteKind, TracingEvent, TracingFields, TracingLevel, TracingMetadata,
+};
+
+impl From<&log::Record<'_>> for TracingEvent {
+    /// Converts a `log` record into an event, so records from libraries using
+    /// `log` can share a pipeline with `tracing` events.
+    ///
+    /// The record's formatted arguments become the event's message.
+    fn from(record: &log::Record<'_>) -> Self {
+        Self {
+            metadata: record.into(),
+            message: Some(record.args().to_string()),
+            fields: TracingFields::new(),
+            timestamp: None,
+            monotonic_nanos: None,
+            seq: None,
+            thread_id: None,
+            thread_name: None,
+            span_context: Vec::new(),
+            current_span_id: None,
+        }
+    }
+}
+
+impl From<&log::Record<'_>> for TracingMetadata {
+    fn from(record: &log::Record<'_>) -> Self {
+        // Named like the events generated by `tracing`'s macros.
+        let file = record.file().map(source_file);
+        let name = match (&file, record.line()) {
+            (Some(file), Some(line)) => format!("event {}:{}", file, line),
+            _ => "log record".to_owned(),
+        };
+
+        Self {
+            name,
+            target: record.target().to_owned(),
+            level: record.level().into(),
+            module_path: record.module_path().map(|path| path.into()),
+            file,
+            line: record.line(),
+            kind: TracingCallsiteKind::Event,
+            declared_fields: vec!["message".to_owned()],
+        }
+    }
+}
+
+impl From<log::Level> for TracingLevel {
+    fn from(level: log::Level) -> Self {
+        match level {
+            log::Level::Trace => Self::Trace,
+            log::Level::Debug => Self::Debug,
+            log::Level::Info => Self::Info,
+            log::Level::Warn => Self::Warn,
+            log::Level::Error => Self::Error,
+        }
+    }
+}
+
+impl From<TracingLevel> for log::Level {
+    fn from(level: TracingLevel) -> Self {
+        match level {
+            TracingLevel::Trace => Self::Trace,
+            TracingLevel::Debug => Self::Debug,
+            TracingLevel::Info => Self::Info,
+            TracingLevel::Warn => Self::Warn,
+            TracingLevel::Error => Self::Error,
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use alloc::string::{String, ToString};
+use core::fmt::Write;
+
+use crate::{FieldValue, TracingEvent};
+
+impl TracingEvent {
+    /// Formats the event as a single [logfmt](https://brandur.org/logfmt)
+    /// line, e.g. `level=warn target=my_app::db msg="connection slow" retries=3`.
+    ///
+    /// The level is written in lowercase, followed by the target, the message
+    /// (if any) as `msg`, and the fields in the order they were recorded.
+    /// Numbers and booleans are written as they are; other values are quoted
+    /// if they are empty or contain spaces, `=` or `"`, with quotes,
+    /// backslashes and control characters escaped.
+    pub fn to_logfmt(&self) -> String {
+        let mut line = String::new();
+        write_pair(
+            &mut line,
+            "level",
+            &self.metadata.level.as_str().to_ascii_lowercase(),
+        );
+        write_pair(&mut line, "target", &self.metadata.target);
+        if let Some(message) = &self.message {
+            write_pair(&mut line, "msg", message);
+        }
+        for (name, value) in self.fields.iter() {
+            match value {
+                FieldValue::I64(_)
+                | FieldValue::U64(_)
+                | FieldValue::F64(_)
+                | FieldValue::Bool(_) => {
+                    write_key(&mut line, name);
+                    let _ = write!(line, "{}", value);
+                }
+                FieldValue::Str(value) => write_pair(&mut line, name, value),
+                value => write_pair(&mut line, name, &value.to_string()),
+            }
+        }
+        line
+    }
+}
+
+fn write_pair(line: &mut String, key: &str, value: &str) {
+    write_key(line, key);
+    write_value(line, value);
+}
+
+/// Writes `key=`, preceded by a space if it isn't the first pair. Characters
+/// that would end the key early are replaced with `_`.
+fn write_key(line: &mut String, key: &str) {
+    if !line.is_empty() {
+        line.push(' ');
+    }
+    line.extend(key.chars().map(|c| {
+        if c == '=' || c == '"' || c.is_whitespace() || c.is_control() {
+            '_'
+        } else {
+            c
+        }
+    }));
+    line.push('=');
+}
+
+fn write_value(line: &mut String, value: &str) {
+    let needs_quotes = value.is_empty()
+        || value
+            .chars()
+            .any(|c| c == '=' || c == '"' || c == '\\' || c.is_whitespace() || c.is_control());
+    if !needs_quotes {
+        line.push_str(value);
+        return;
+    }
+
+    line.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => line.push_str("\\\""),
+            '\\' => line.push_str("\\\\"),
+            '\n' => line.push_str("\\n"),
+            '\r' => line.push_str("\\r"),
+            '\t' => line.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(line, "\\u{{{:x}}}", c as u32);
+            }
+            c => line.push(c),
+        }
+    }
+    line.push('"');
+}
@@ -0,0 +1,94 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+};
+
+use crate::TracingEvent;
+
+impl TracingEvent {
+    /// Orders events chronologically by their `timestamp`, with events that
+    /// have no timestamp sorting before all events that do.
+    ///
+    /// This is not `TracingEvent`'s `Ord` implementation because it would
+    /// disagree with its `PartialEq`: distinct events captured at the same
+    /// instant compare as equal here.
+    pub fn cmp_by_timestamp(&self, other: &Self) -> Ordering {
+        self.timestamp.cmp(&other.timestamp)
+    }
+}
+
+/// Merges streams of events that are each sorted by timestamp into a single
+/// stream sorted by timestamp (see [`TracingEvent::cmp_by_timestamp`]).
+///
+/// Events with equal timestamps are yielded in the order of the streams they
+/// came from. Each stream is only read as far as needed, so this works with
+/// unbounded streams too.
+pub fn merge_sorted<I>(streams: Vec<I>) -> MergeSorted<I>
+where
+    I: Iterator<Item = TracingEvent>,
+{
+    let mut merged = MergeSorted {
+        streams,
+        heads: BinaryHeap::new(),
+    };
+    for index in 0..merged.streams.len() {
+        merged.advance(index);
+    }
+    merged
+}
+
+/// The iterator returned by [`merge_sorted`].
+pub struct MergeSorted<I> {
+    streams: Vec<I>,
+    heads: BinaryHeap<Reverse<Head>>,
+}
+
+/// The next event of one of the merged streams.
+struct Head {
+    event: TracingEvent,
+    stream: usize,
+}
+
+impl Head {
+    fn key(&self) -> (Option<u64>, usize) {
+        (self.event.timestamp, self.stream)
+    }
+}
+
+impl PartialEq for Head {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Head {}
+
+impl PartialOrd for Head {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Head {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl<I: Iterator<Item = TracingEvent>> MergeSorted<I> {
+    fn advance(&mut self, stream: usize) {
+        if let Some(event) = self.streams[stream].next() {
+            self.heads.push(Reverse(Head { event, stream }));
+        }
+    }
+}
+
+impl<I: Iterator<Item = TracingEvent>> Iterator for MergeSorted<I> {
+    type Item = TracingEvent;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse(head) = self.heads.pop()?;
+        self.advance(head.stream);
+        Some(head.event)
+    }
+}
@@ -0,0 +1,138 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+/// Serializes a nested value as itself in human-readable formats such as
+/// JSON, and as its JSON text otherwise, since binary formats like bincode
+/// can't deserialize a self-describing `serde_json::Value`.
+pub(crate) fn serialize<S: Serializer>(value: &Value, serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        value.serialize(serializer)
+    } else {
+        value.to_string().serialize(serializer)
+    }
+}
+
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+    if deserializer.is_human_readable() {
+        Value::deserialize(deserializer)
+    } else {
+        let text = String::deserialize(deserializer)?;
+        serde_json::from_str(&text).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Converts a `valuable` value into JSON, recursing into lists, maps,
+/// structs, tuples and enums.
+///
+/// Structs with named fields and maps become objects, lists, tuples and
+/// structs with unnamed fields become arrays, and an enum variant becomes
+/// `{"Variant": fields}`, or just `"Variant"` if it has no fields. Integers
+/// too large for a JSON number are written as strings.
+#[cfg(all(tracing_unstable, feature = "valuable"))]
+pub(crate) fn from_valuable(value: valuable::Value<'_>) -> Value {
+    use serde_json::Map;
+    use std::convert::TryFrom;
+    use valuable::{Fields, Value as V};
+
+    match value {
+        V::Bool(value) => Value::from(value),
+        V::Char(value) => Value::from(value.to_string()),
+        V::F32(value) => Value::from(value),
+        V::F64(value) => Value::from(value),
+        V::I8(value) => Value::from(value),
+        V::I16(value) => Value::from(value),
+        V::I32(value) => Value::from(value),
+        V::I64(value) => Value::from(value),
+        V::Isize(value) => Value::from(value),
+        V::I128(value) => match i64::try_from(value) {
+            Ok(value) => Value::from(value),
+            Err(_) => Value::from(value.to_string()),
+        },
+        V::U8(value) => Value::from(value),
+        V::U16(value) => Value::from(value),
+        V::U32(value) => Value::from(value),
+        V::U64(value) => Value::from(value),
+        V::Usize(value) => Value::from(value),
+        V::U128(value) => match u64::try_from(value) {
+            Ok(value) => Value::from(value),
+            Err(_) => Value::from(value.to_string()),
+        },
+        V::String(value) => Value::from(value),
+        V::Path(value) => Value::from(value.to_string_lossy().into_owned()),
+        V::Error(value) => Value::from(value.to_string()),
+        V::Unit => Value::Null,
+        V::Listable(list) => {
+            let mut visitor = Collect::default();
+            list.visit(&mut visitor);
+            Value::Array(visitor.unnamed)
+        }
+        V::Tuplable(tuple) => {
+            let mut visitor = Collect::default();
+            tuple.visit(&mut visitor);
+            Value::Array(visitor.unnamed)
+        }
+        V::Mappable(map) => {
+            let mut visitor = Collect::default();
+            map.visit(&mut visitor);
+            Value::Object(visitor.named)
+        }
+        V::Structable(structable) => {
+            let mut visitor = Collect::default();
+            structable.visit(&mut visitor);
+            match structable.definition().fields() {
+                Fields::Named(_) => Value::Object(visitor.named),
+                Fields::Unnamed(_) => Value::Array(visitor.unnamed),
+            }
+        }
+        V::Enumerable(enumerable) => {
+            let variant = enumerable.variant();
+            let mut visitor = Collect::default();
+            enumerable.visit(&mut visitor);
+            let fields = match variant.fields() {
+                Fields::Named([]) | Fields::Unnamed(0) => return Value::from(variant.name()),
+                Fields::Named(_) => Value::Object(visitor.named),
+                Fields::Unnamed(_) => Value::Array(visitor.unnamed),
+            };
+            let mut object = Map::new();
+            object.insert(variant.name().to_owned(), fields);
+            Value::Object(object)
+        }
+        value => Value::from(format!("{:?}", value)),
+    }
+}
+
+/// Collects whatever a `valuable` value visits: list elements and unnamed
+/// fields in order, named fields and map entries by name.
+#[cfg(all(tracing_unstable, feature = "valuable"))]
+#[derive(Default)]
+struct Collect {
+    named: serde_json::Map<String, Value>,
+    unnamed: Vec<Value>,
+}
+
+#[cfg(all(tracing_unstable, feature = "valuable"))]
+impl valuable::Visit for Collect {
+    fn visit_value(&mut self, value: valuable::Value<'_>) {
+        self.unnamed.push(from_valuable(value));
+    }
+
+    fn visit_named_fields(&mut self, named_values: &valuable::NamedValues<'_>) {
+        for (field, value) in named_values {
+            self.named
+                .insert(field.name().to_owned(), from_valuable(*value));
+        }
+    }
+
+    fn visit_unnamed_fields(&mut self, values: &[valuable::Value<'_>]) {
+        self.unnamed
+            .extend(values.iter().map(|value| from_valuable(*value)));
+    }
+
+    fn visit_entry(&mut self, key: valuable::Value<'_>, value: valuable::Value<'_>) {
+        let key = match from_valuable(key) {
+            Value::String(key) => key,
+            key => key.to_string(),
+        };
+        self.named.insert(key, from_valuable(value));
+    }
+}
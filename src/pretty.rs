@@ -0,0 +1,159 @@
+use alloc::string::String;
+use core::fmt::{self, Display, Write};
+
+use crate::{time::Rfc3339, FieldValue, TracingEvent, TracingLevel};
+
+const RESET: &str = "\x1b[0m";
+const DIMMED: &str = "\x1b[2m";
+const ITALIC: &str = "\x1b[3m";
+
+impl TracingEvent {
+    /// Formats the event as `tracing_subscriber::fmt`'s default formatter
+    /// would have written it, e.g.
+    /// `2024-05-01T12:30:00.123456Z  WARN my_app::db: connection slow retries=3`,
+    /// so that tools built on captured events can show them as they appeared
+    /// on the console. If `with_ansi` is true, the level is colored and the
+    /// timestamp, target and field names are styled as the formatter styles
+    /// them.
+    ///
+    /// The timestamp is left out if the event has none. So are the spans the
+    /// event was emitted in, which the formatter lists before the target,
+    /// since events only record their ids. The line doesn't end with a
+    /// newline.
+    ///
+    /// As the formatter does, escape sequences and other terminal control
+    /// characters in the message and in error messages are written escaped,
+    /// e.g. as `\x1b`, so that a captured message can't restyle the
+    /// terminal.
+    ///
+    /// ```
+    /// use tracing_bridge::{TracingEvent, TracingLevel};
+    ///
+    /// let event = TracingEvent::builder()
+    ///     .level(TracingLevel::Warn)
+    ///     .target("my_app::db")
+    ///     .message("connection slow")
+    ///     .field("retries", 3_u64)
+    ///     .field("host", "db1")
+    ///     .timestamp(1_714_566_600_123_456_789)
+    ///     .build();
+    /// assert_eq!(
+    ///     event.format_pretty(false),
+    ///     r#"2024-05-01T12:30:00.123456Z  WARN my_app::db: connection slow retries=3 host="db1""#,
+    /// );
+    /// ```
+    pub fn format_pretty(&self, with_ansi: bool) -> String {
+        let mut line = String::new();
+        // Writing to a `String` never fails.
+        let _ = Pretty {
+            line: &mut line,
+            ansi: with_ansi,
+        }
+        .event(self);
+        line
+    }
+}
+
+/// Writes an event into `line`, with ANSI styles if `ansi` is true.
+struct Pretty<'a> {
+    line: &'a mut String,
+    ansi: bool,
+}
+
+impl Pretty<'_> {
+    fn event(&mut self, event: &TracingEvent) -> fmt::Result {
+        if let Some(nanos) = event.timestamp {
+            self.styled(DIMMED, Rfc3339 { nanos, digits: 6 })?;
+            self.line.push(' ');
+        }
+        let (color, label) = level_style(event.metadata.level);
+        self.styled(color, label)?;
+        self.line.push(' ');
+        self.styled(DIMMED, &event.metadata.target)?;
+        self.styled(DIMMED, ":")?;
+        self.line.push(' ');
+
+        let mut first = true;
+        let mut pad = |line: &mut String| {
+            if !core::mem::take(&mut first) {
+                line.push(' ');
+            }
+        };
+        if let Some(message) = &event.message {
+            pad(self.line);
+            write_escaped(self.line, message)?;
+        }
+        for (name, value) in event.fields.iter() {
+            pad(self.line);
+            let name = name.strip_prefix("r#").unwrap_or(name);
+            self.field(name, value)?;
+        }
+        Ok(())
+    }
+
+    /// Writes `name=value`, with the name in italics and `=` dimmed.
+    fn field(&mut self, name: &str, value: &FieldValue) -> fmt::Result {
+        self.styled(ITALIC, name)?;
+        self.styled(DIMMED, "=")?;
+        match value {
+            FieldValue::F64(value) => write!(self.line, "{:?}", value),
+            FieldValue::Error { message, causes } => {
+                write_escaped(self.line, message)?;
+                if causes.is_empty() {
+                    return Ok(());
+                }
+                self.line.push(' ');
+                self.styled(ITALIC, name)?;
+                self.styled(ITALIC, ".sources")?;
+                self.styled(DIMMED, "=")?;
+                self.line.push('[');
+                for (i, cause) in causes.iter().enumerate() {
+                    if i > 0 {
+                        self.line.push_str(", ");
+                    }
+                    write_escaped(self.line, cause)?;
+                }
+                self.line.push(']');
+                Ok(())
+            }
+            value => write!(self.line, "{}", value),
+        }
+    }
+
+    /// Writes `text`, wrapped in `style` and a reset if styles are enabled.
+    fn styled(&mut self, style: &str, text: impl Display) -> fmt::Result {
+        if self.ansi {
+            write!(self.line, "{}{}{}", style, text, RESET)
+        } else {
+            write!(self.line, "{}", text)
+        }
+    }
+}
+
+/// Returns the color and padded label the formatter writes for `level`.
+fn level_style(level: TracingLevel) -> (&'static str, &'static str) {
+    match level {
+        TracingLevel::Trace => ("\x1b[35m", "TRACE"),
+        TracingLevel::Debug => ("\x1b[34m", "DEBUG"),
+        TracingLevel::Info => ("\x1b[32m", " INFO"),
+        TracingLevel::Warn => ("\x1b[33m", " WARN"),
+        TracingLevel::Error => ("\x1b[31m", "ERROR"),
+    }
+}
+
+/// Writes `text` with the control characters terminals act on escaped, as
+/// the formatter escapes them.
+fn write_escaped(line: &mut String, text: &str) -> fmt::Result {
+    for c in text.chars() {
+        match c {
+            '\x1b' => line.push_str("\\x1b"),
+            '\x07' => line.push_str("\\x07"),
+            '\x08' => line.push_str("\\x08"),
+            '\x0c' => line.push_str("\\x0c"),
+            '\x7f' => line.push_str("\\x7f"),
+            '\u{80}'..='\u{9f}' => write!(line, "\\u{{{:x}}}", c as u32)?,
+            c => line.push(c),
+        }
+    }
+    Ok(())
+}
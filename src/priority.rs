@@ -0,0 +1,182 @@
+use core::{convert::TryFrom, fmt};
+
+use serde::{
+    de::{self, DeserializeSeed, IntoDeserializer, Unexpected},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::TracingLevel;
+
+/// A scheme for encoding levels as integers, for
+/// [`TracingLevel::to_priority`].
+///
+/// Each scheme is a table of the priorities of `Trace`, `Debug`, `Info`,
+/// `Warn` and `Error`, in that order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrioritySchema {
+    /// Syslog severities: 3 (error), 4 (warning), 6 (informational) and 7
+    /// (debug) for both `Debug` and `Trace`. Lower numbers are more severe.
+    Syslog,
+    /// OpenTelemetry `SeverityNumber`s: the lowest number of each level's
+    /// range, so 1, 5, 9, 13 and 17.
+    Otel,
+    /// GELF levels, which are syslog severities.
+    Gelf,
+    /// The given priorities of `Trace`, `Debug`, `Info`, `Warn` and `Error`.
+    Custom([u8; 5]),
+}
+
+impl PrioritySchema {
+    fn table(self) -> [u8; 5] {
+        match self {
+            Self::Syslog | Self::Gelf => [7, 7, 6, 4, 3],
+            Self::Otel => [1, 5, 9, 13, 17],
+            Self::Custom(table) => table,
+        }
+    }
+}
+
+impl TracingLevel {
+    /// Returns the priority of the level under `scheme`.
+    ///
+    /// ```
+    /// use tracing_bridge::{PrioritySchema, TracingLevel};
+    ///
+    /// assert_eq!(TracingLevel::Warn.to_priority(PrioritySchema::Syslog), 4);
+    /// assert_eq!(TracingLevel::Warn.to_priority(PrioritySchema::Otel), 13);
+    ///
+    /// let scheme = PrioritySchema::Custom([10, 20, 30, 40, 50]);
+    /// assert_eq!(TracingLevel::Trace.to_priority(scheme), 10);
+    /// ```
+    pub fn to_priority(&self, scheme: PrioritySchema) -> u8 {
+        scheme.table()[self.severity() as usize]
+    }
+
+    /// Returns the level `scheme` gives `priority`, or `None` if it gives it
+    /// to no level. Where the scheme gives several levels the same priority,
+    /// such as `Debug` and `Trace` under [`Syslog`](PrioritySchema::Syslog),
+    /// returns the most severe of them.
+    ///
+    /// ```
+    /// use tracing_bridge::{PrioritySchema, TracingLevel};
+    ///
+    /// assert_eq!(TracingLevel::from_priority(13, PrioritySchema::Otel), Some(TracingLevel::Warn));
+    /// assert_eq!(TracingLevel::from_priority(7, PrioritySchema::Syslog), Some(TracingLevel::Debug));
+    /// assert_eq!(TracingLevel::from_priority(2, PrioritySchema::Otel), None);
+    /// ```
+    pub fn from_priority(priority: u8, scheme: PrioritySchema) -> Option<Self> {
+        LEVELS
+            .iter()
+            .rev()
+            .copied()
+            .find(|level| level.to_priority(scheme) == priority)
+    }
+}
+
+/// Every level, from least to most severe.
+const LEVELS: [TracingLevel; 5] = [
+    TracingLevel::Trace,
+    TracingLevel::Debug,
+    TracingLevel::Info,
+    TracingLevel::Warn,
+    TracingLevel::Error,
+];
+
+/// How [`TracingLevel`]s are written when events are serialized, and read
+/// back when they are deserialized.
+///
+/// Levels serialize as the names of their variants, e.g. `"Warn"`. Another
+/// format is chosen with an [`EventFormat`](crate::EventFormat), which sinks
+/// and readers take with methods such as
+/// [`WriterSink::with_level_format`](crate::WriterSink::with_level_format)
+/// and
+/// [`JsonLinesReader::with_level_format`](crate::JsonLinesReader::with_level_format).
+/// Writing them as priorities lets consumers compare levels and query ranges
+/// of them as numbers.
+///
+/// Human-readable formats such as JSON read back levels written as names in
+/// any format, and as priorities in the scheme of the format read in. Other
+/// formats only read back levels written in the format read in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum LevelFormat {
+    /// The name of the level, e.g. `"Warn"`.
+    #[default]
+    Name,
+
+    /// The priority of the level under the scheme, as a number, e.g. `13`
+    /// for `Warn` under [`PrioritySchema::Otel`]. Levels that the scheme
+    /// gives the same priority are read back as the most severe of them, see
+    /// [`TracingLevel::from_priority`].
+    Priority(PrioritySchema),
+}
+
+/// A level that serializes in `format`, for serializers written by hand.
+pub(crate) struct Level {
+    pub level: TracingLevel,
+    pub format: LevelFormat,
+}
+
+impl Serialize for Level {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.format {
+            LevelFormat::Name => self.level.serialize(serializer),
+            LevelFormat::Priority(scheme) => {
+                serializer.serialize_u8(self.level.to_priority(scheme))
+            }
+        }
+    }
+}
+
+/// Deserializes a level written in a format, or as a name from a
+/// human-readable data format.
+pub(crate) struct LevelSeed(pub LevelFormat);
+
+impl<'de> DeserializeSeed<'de> for LevelSeed {
+    type Value = TracingLevel;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<TracingLevel, D::Error> {
+        match self.0 {
+            LevelFormat::Name => TracingLevel::deserialize(deserializer),
+            LevelFormat::Priority(_) if deserializer.is_human_readable() => {
+                deserializer.deserialize_any(LevelVisitor(self.0))
+            }
+            LevelFormat::Priority(_) => deserializer.deserialize_u8(LevelVisitor(self.0)),
+        }
+    }
+}
+
+/// Reads a level from its name, or from its priority in the scheme of its
+/// format.
+struct LevelVisitor(LevelFormat);
+
+impl<'de> de::Visitor<'de> for LevelVisitor {
+    type Value = TracingLevel;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            LevelFormat::Name => formatter.write_str("a level name"),
+            LevelFormat::Priority(_) => formatter.write_str("a level name or priority"),
+        }
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<TracingLevel, E> {
+        let level = match self.0 {
+            LevelFormat::Priority(scheme) => u8::try_from(value)
+                .ok()
+                .and_then(|priority| TracingLevel::from_priority(priority, scheme)),
+            LevelFormat::Name => None,
+        };
+        level.ok_or_else(|| E::invalid_value(Unexpected::Unsigned(value), &self))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<TracingLevel, E> {
+        match u64::try_from(value) {
+            Ok(value) => self.visit_u64(value),
+            Err(_) => Err(E::invalid_value(Unexpected::Signed(value), &self)),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<TracingLevel, E> {
+        TracingLevel::deserialize(value.into_deserializer())
+    }
+}
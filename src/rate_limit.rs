@@ -0,0 +1,150 @@
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    convert::TryFrom,
+    hash::BuildHasher,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use tracing_core::{callsite::Identifier, Metadata};
+
+use crate::{TracingEvent, TracingLevel, TracingMetadata};
+
+/// How often callsites that are being rate limited report how many of their
+/// events were suppressed.
+const SUMMARY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How many maps the buckets are spread over, so that events from different
+/// callsites rarely wait for the same lock.
+const SHARDS: usize = 16;
+
+/// Limits how many events each callsite may emit per second, with a token
+/// bucket per callsite.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    max_per_sec: u32,
+    shards: Box<[Mutex<HashMap<Identifier, Bucket>>]>,
+    hasher: RandomState,
+    /// When, in nanoseconds since the Unix epoch, the buckets are next swept
+    /// for suppressed events to report.
+    next_sweep: AtomicU64,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    metadata: &'static Metadata<'static>,
+    tokens: f64,
+    refilled: SystemTime,
+    suppressed: u64,
+    summarized: SystemTime,
+}
+
+impl RateLimiter {
+    pub fn new(max_per_sec: u32) -> Self {
+        Self {
+            max_per_sec,
+            shards: (0..SHARDS).map(|_| Mutex::new(HashMap::new())).collect(),
+            hasher: RandomState::new(),
+            next_sweep: AtomicU64::new(0),
+        }
+    }
+
+    /// Takes a token from the bucket of the callsite of `metadata`, refilled
+    /// for the time that passed until `now`, and returns whether there was
+    /// one. If there wasn't, the event is counted as suppressed.
+    pub fn check(&self, metadata: &'static Metadata<'static>, now: SystemTime) -> bool {
+        let max = f64::from(self.max_per_sec);
+        let callsite = metadata.callsite();
+        let mut shard = self.shard(&callsite);
+        let bucket = shard.entry(callsite).or_insert(Bucket {
+            metadata,
+            tokens: max,
+            refilled: now,
+            suppressed: 0,
+            summarized: now,
+        });
+
+        // A clock that went backwards refills nothing.
+        let elapsed = now.duration_since(bucket.refilled).unwrap_or_default();
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * max).min(max);
+        bucket.refilled = bucket.refilled.max(now);
+
+        if bucket.tokens < 1.0 {
+            bucket.suppressed += 1;
+            return false;
+        }
+        bucket.tokens -= 1.0;
+        true
+    }
+
+    /// Returns the callsites that have suppressed events and haven't reported
+    /// them for at least a second, with how many they suppressed, resetting
+    /// their counts.
+    ///
+    /// The buckets are only swept once a second, by whichever call comes
+    /// first, so the other calls cost a single atomic load.
+    pub fn due_summaries(&self, now: SystemTime) -> Vec<(&'static Metadata<'static>, u64)> {
+        let now_nanos = now
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .and_then(|since| u64::try_from(since.as_nanos()).ok())
+            .unwrap_or(0);
+        let next_sweep = self.next_sweep.load(Ordering::Relaxed);
+        if now_nanos < next_sweep
+            || self
+                .next_sweep
+                .compare_exchange(
+                    next_sweep,
+                    now_nanos.saturating_add(SUMMARY_INTERVAL.as_nanos() as u64),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                )
+                .is_err()
+        {
+            return Vec::new();
+        }
+
+        let mut due = Vec::new();
+        for shard in self.shards.iter() {
+            let mut shard = shard.lock().unwrap_or_else(PoisonError::into_inner);
+            for bucket in shard.values_mut() {
+                let since_summary = now.duration_since(bucket.summarized).unwrap_or_default();
+                if bucket.suppressed > 0 && since_summary >= SUMMARY_INTERVAL {
+                    bucket.summarized = now;
+                    due.push((bucket.metadata, std::mem::take(&mut bucket.suppressed)));
+                }
+            }
+        }
+        due
+    }
+
+    fn shard(&self, callsite: &Identifier) -> MutexGuard<'_, HashMap<Identifier, Bucket>> {
+        let index = self.hasher.hash_one(callsite) as usize % self.shards.len();
+        self.shards[index]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Builds the event reporting that `suppressed` events from the callsite
+/// described by `metadata` were dropped by the rate limit.
+pub(crate) fn summary(metadata: &TracingMetadata, suppressed: u64) -> TracingEvent {
+    let callsite = metadata.location().unwrap_or_else(|| metadata.name.clone());
+    let mut builder = TracingEvent::builder()
+        .name("rate limit summary")
+        .target(module_path!())
+        .level(TracingLevel::Warn)
+        .message(format!(
+            "suppressed {} events from {}",
+            suppressed, callsite
+        ))
+        .field("suppressed", suppressed)
+        .field("callsite.target", metadata.target.as_str());
+    if let Some(location) = metadata.location() {
+        builder = builder.field("callsite.location", location);
+    }
+    builder.build()
+}
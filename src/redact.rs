@@ -0,0 +1,30 @@
+use alloc::{borrow::ToOwned, string::String, sync::Arc, vec::Vec};
+
+use crate::FieldValue;
+
+/// A hook that can rewrite a field's value, given the field's name.
+pub(crate) type Redactor = Arc<dyn Fn(&str, &mut FieldValue) + Send + Sync>;
+
+/// The value that [`redact_keys`] replaces sensitive fields with.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Returns a redactor for [`BridgeLayer::with_redactor`] that replaces the
+/// value of any field named one of `keys` (ignoring ASCII case) with the
+/// string [`REDACTED`].
+///
+/// [`BridgeLayer::with_redactor`]: crate::BridgeLayer::with_redactor
+pub fn redact_keys<I>(keys: I) -> impl Fn(&str, &mut FieldValue) + Send + Sync + 'static
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let keys: Vec<String> = keys
+        .into_iter()
+        .map(|key| key.as_ref().to_owned())
+        .collect();
+    move |name, value| {
+        if keys.iter().any(|key| key.eq_ignore_ascii_case(name)) {
+            *value = FieldValue::Str(REDACTED.to_owned());
+        }
+    }
+}
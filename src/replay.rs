@@ -0,0 +1,297 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Mutex, OnceLock, PoisonError},
+};
+
+use tracing_core::{
+    callsite::{Callsite, Identifier},
+    dispatcher,
+    field::{FieldSet, Value},
+    subscriber::Interest,
+    Event, Metadata,
+};
+
+use crate::{FieldValue, TracingEvent, TracingLevel, TracingMetadata};
+
+/// The most synthetic callsites replayed events get, see
+/// [`TracingEvent::emit`].
+const MAX_CALLSITES: usize = 1024;
+
+/// The fields of the callsites events are replayed from once
+/// [`MAX_CALLSITES`] is reached.
+const OVERFLOW_FIELDS: [&str; 3] = ["message", "origin.target", "origin.fields"];
+
+impl TracingEvent {
+    /// Dispatches this event to the current default subscriber, as if it had
+    /// been emitted by a `tracing` macro with the stored metadata and fields.
+    ///
+    /// `tracing` requires callsites to be `'static`, so replayed events are
+    /// dispatched from synthetic callsites. One is created (and leaked) for
+    /// each distinct combination of metadata and field names, and reused for
+    /// every later event with the same shape. Subscribers that key on
+    /// callsite identity will therefore see replayed events as coming from a
+    /// different callsite than the one that originally emitted them.
+    ///
+    /// Leaked callsites are never freed, so at most 1024 are created, to
+    /// bound the memory a stream of events with ever-changing targets or
+    /// field names can take. Once the limit is reached, events of a new shape
+    /// are replayed from a fallback callsite for their level instead, with
+    /// the `tracing_bridge::replay` target and three fields: the message,
+    /// `origin.target` holding the original target, and `origin.fields`
+    /// holding the original fields written out as text, e.g.
+    /// `{retries=3, host="db1"}`. Events of shapes seen before the limit was
+    /// reached keep being replayed from their own callsites.
+    pub fn emit(&self) {
+        // The message goes first, where `tracing`'s macros put it.
+        let message = self.message.as_deref().map(|message| {
+            let value: Box<dyn Value> = Box::new(tracing_core::field::debug(Verbatim(message)));
+            ("message", value)
+        });
+        let fields = self
+            .fields
+            .iter()
+            .map(|(name, value)| (name, replay_value(value)));
+        let (names, values): (Vec<&str>, Vec<_>) = message.into_iter().chain(fields).unzip();
+
+        let names = names.into_iter().map(str::to_owned).collect();
+        let metadata = match replay_metadata(&self.metadata, names) {
+            Some(metadata) => metadata,
+            None => return self.emit_overflow(),
+        };
+
+        let values: Vec<Option<&dyn Value>> = values.iter().map(|value| Some(&**value)).collect();
+        let values = metadata.fields().value_set_all(&values);
+
+        dispatch(metadata, &values);
+    }
+
+    /// Dispatches this event from the fallback callsite for its level.
+    fn emit_overflow(&self) {
+        let metadata = overflow_metadata(self.metadata.level);
+        let message = tracing_core::field::debug(Verbatim(self.message.as_deref().unwrap_or("")));
+        let target = self.metadata.target.as_str();
+        let fields = tracing_core::field::display(FieldsText(self));
+        let values: [Option<&dyn Value>; 3] = [Some(&message), Some(&target), Some(&fields)];
+        dispatch(metadata, &metadata.fields().value_set_all(&values));
+    }
+}
+
+fn dispatch(metadata: &'static Metadata<'static>, values: &tracing_core::field::ValueSet<'_>) {
+    dispatcher::get_default(|dispatch| {
+        if dispatch.enabled(metadata) {
+            dispatch.event(&Event::new(metadata, values));
+        }
+    });
+}
+
+/// Writes an event's fields as its `Display` implementation does, e.g.
+/// `{retries=3, host="db1"}`.
+struct FieldsText<'a>(&'a TracingEvent);
+
+impl fmt::Display for FieldsText<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("{")?;
+        for (i, (name, value)) in self.0.fields.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}={}", name, value)?;
+        }
+        f.write_str("}")
+    }
+}
+
+/// Re-emits each event from `events` to the current default subscriber with
+/// [`TracingEvent::emit`], as they arrive, until the iterator ends.
+///
+/// This makes events received from elsewhere, e.g. read from a socket, show
+/// up in the local subscriber alongside local events, with their level,
+/// target, message and fields as they were captured. Since they may come
+/// from anywhere, the number of callsites created for them is capped, and
+/// events of new shapes beyond the cap arrive with their target and fields
+/// folded into fields of a fallback callsite, see [`TracingEvent::emit`].
+///
+/// The spans the events were emitted in can't be reconstructed: they don't
+/// exist in the local subscriber, so forwarded events are recorded in
+/// whatever span is current on the forwarding thread, and their
+/// [`span_context`](TracingEvent::span_context) is not carried over. To keep
+/// the origin's span ids, copy them into fields before forwarding:
+///
+/// ```
+/// use std::sync::mpsc::Receiver;
+///
+/// use tracing_bridge::{FieldValue, TracingEvent};
+///
+/// fn forward_from(received: Receiver<TracingEvent>) {
+///     tracing_bridge::forward(received.into_iter().map(|mut event| {
+///         if let Some(id) = event.current_span_id {
+///             event.fields.push("origin.span_id", FieldValue::U64(id));
+///         }
+///         event
+///     }));
+/// }
+/// ```
+pub fn forward<I: Iterator<Item = TracingEvent>>(events: I) {
+    for event in events {
+        event.emit();
+    }
+}
+
+fn replay_value(value: &FieldValue) -> Box<dyn Value + '_> {
+    match value {
+        FieldValue::I64(value) => Box::new(*value),
+        FieldValue::U64(value) => Box::new(*value),
+        FieldValue::F64(value) => Box::new(*value),
+        FieldValue::Bool(value) => Box::new(*value),
+        FieldValue::Str(value) => Box::new(value.as_str()),
+        FieldValue::Debug(value) => Box::new(tracing_core::field::debug(Verbatim(value))),
+        FieldValue::Error { message, causes } => {
+            let error: Box<dyn std::error::Error + Send + Sync> =
+                Box::new(ReplayedError::new(message, causes));
+            Box::new(error)
+        }
+        // The dropped part is lost, so replay what `Display` shows.
+        FieldValue::Truncated { .. } => Box::new(tracing_core::field::display(value)),
+        #[cfg(feature = "valuable")]
+        FieldValue::Nested(value) => Box::new(tracing_core::field::display(value)),
+    }
+}
+
+/// Writes a previously captured `Debug` representation back out unchanged.
+struct Verbatim<'a>(&'a str);
+
+impl fmt::Debug for Verbatim<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+/// Rebuilds a captured error chain so it can be recorded with `record_error`.
+#[derive(Debug)]
+struct ReplayedError {
+    message: String,
+    source: Option<Box<ReplayedError>>,
+}
+
+impl ReplayedError {
+    fn new(message: &str, causes: &[String]) -> Self {
+        Self {
+            message: message.to_owned(),
+            source: causes
+                .split_first()
+                .map(|(cause, causes)| Box::new(Self::new(cause, causes))),
+        }
+    }
+}
+
+impl fmt::Display for ReplayedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for ReplayedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn std::error::Error + 'static))
+    }
+}
+
+/// Identifies a replay callsite by the metadata and field names it reports.
+type CallsiteKey = (TracingMetadata, Vec<String>);
+
+struct ReplayCallsite {
+    metadata: OnceLock<Metadata<'static>>,
+}
+
+impl Callsite for ReplayCallsite {
+    fn set_interest(&self, _interest: Interest) {}
+
+    fn metadata(&self) -> &Metadata<'_> {
+        self.metadata
+            .get()
+            .expect("replay callsites are registered after their metadata is set")
+    }
+}
+
+/// Returns the metadata of the synthetic callsite for events with the given
+/// metadata and field names, creating and registering it on first use, or
+/// `None` if it doesn't exist and [`MAX_CALLSITES`] have been created.
+fn replay_metadata(
+    metadata: &TracingMetadata,
+    names: Vec<String>,
+) -> Option<&'static Metadata<'static>> {
+    static CALLSITES: OnceLock<Mutex<HashMap<CallsiteKey, &'static ReplayCallsite>>> =
+        OnceLock::new();
+
+    let mut callsites = CALLSITES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+
+    let key = (metadata.clone(), names);
+    if let Some(callsite) = callsites.get(&key) {
+        return Some(callsite.metadata());
+    }
+    if callsites.len() >= MAX_CALLSITES {
+        return None;
+    }
+
+    let names: Vec<&str> = key.1.iter().map(String::as_str).collect();
+    let callsite = new_callsite(metadata, &names);
+    callsites.insert(key, callsite);
+    Some(callsite.metadata())
+}
+
+/// Returns the metadata of the fallback callsite for events at `level`,
+/// creating and registering it on first use.
+fn overflow_metadata(level: TracingLevel) -> &'static Metadata<'static> {
+    static CALLSITES: OnceLock<Mutex<HashMap<TracingLevel, &'static ReplayCallsite>>> =
+        OnceLock::new();
+
+    let mut callsites = CALLSITES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    callsites
+        .entry(level)
+        .or_insert_with(|| {
+            let metadata = TracingEvent::builder()
+                .name("replayed event")
+                .target(module_path!())
+                .level(level)
+                .build()
+                .metadata;
+            new_callsite(&metadata, &OVERFLOW_FIELDS)
+        })
+        .metadata()
+}
+
+/// Creates and registers a callsite with `metadata` and fields called
+/// `names`, leaking it and its strings.
+fn new_callsite(metadata: &TracingMetadata, names: &[&str]) -> &'static ReplayCallsite {
+    let callsite: &'static ReplayCallsite = Box::leak(Box::new(ReplayCallsite {
+        metadata: OnceLock::new(),
+    }));
+    let names: Vec<&'static str> = names.iter().map(|name| leak(name)).collect();
+    let fields = FieldSet::new(Box::leak(names.into_boxed_slice()), Identifier(callsite));
+    let _ = callsite.metadata.set(Metadata::new(
+        leak(&metadata.name),
+        leak(&metadata.target),
+        (&metadata.level).into(),
+        metadata.file.as_deref().map(leak),
+        metadata.line,
+        metadata.module_path.as_deref().map(leak),
+        fields,
+        (&metadata.kind).into(),
+    ));
+
+    tracing_core::callsite::register(callsite);
+    callsite
+}
+
+fn leak(s: &str) -> &'static str {
+    Box::leak(s.to_owned().into_boxed_str())
+}
@@ -0,0 +1,68 @@
+use std::{
+    cell::Cell,
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+};
+
+use crate::TracingLevel;
+
+/// Keeps a random fraction of events, with a separate rate for each level.
+#[derive(Debug, Clone)]
+pub(crate) struct Sampler {
+    /// The fraction of events kept at each level, indexed by severity.
+    rates: [f64; 5],
+}
+
+impl Default for Sampler {
+    fn default() -> Self {
+        Self { rates: [1.0; 5] }
+    }
+}
+
+impl Sampler {
+    pub fn set_rate(&mut self, level: TracingLevel, rate: f64) {
+        self.rates[level.severity() as usize] = rate;
+    }
+
+    pub fn set_all_rates(&mut self, rate: f64) {
+        self.rates = [rate; 5];
+    }
+
+    /// Decides whether to keep an event at `level`.
+    pub fn sample(&self, level: TracingLevel) -> bool {
+        let rate = self.rates[level.severity() as usize];
+        if rate >= 1.0 {
+            true
+        } else if rate <= 0.0 {
+            false
+        } else {
+            next_f64() < rate
+        }
+    }
+}
+
+thread_local! {
+    static RNG_STATE: Cell<u64> = Cell::new(seed());
+}
+
+/// Seeds each thread's generator from the randomly keyed `RandomState`, so
+/// threads don't share a sequence.
+fn seed() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(0);
+    // xorshift never leaves the all-zero state, so make sure not to start there.
+    hasher.finish() | 1
+}
+
+/// Returns a uniformly distributed number in `[0, 1)` from a thread-local
+/// xorshift64* generator. This is not suitable for anything but sampling.
+fn next_f64() -> f64 {
+    RNG_STATE.with(|state| {
+        let mut x = state.get();
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        state.set(x);
+        (x.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 11) as f64 / (1u64 << 53) as f64
+    })
+}
@@ -0,0 +1,141 @@
+use std::borrow::Cow;
+
+use schemars::{generate::SchemaSettings, json_schema, JsonSchema, Schema, SchemaGenerator};
+
+use crate::{FieldValue, TracingEvent, TracingFields, TracingLevel};
+
+/// The version of the schema returned by [`event_json_schema`].
+///
+/// It is bumped whenever the schema changes, whether because the serialized
+/// format changed or only because its description did, so that code
+/// generated from one version can be kept until the version changes. The
+/// schema's `$id` ends with it.
+pub const EVENT_SCHEMA_VERSION: u32 = 9;
+
+/// Returns a [JSON Schema](https://json-schema.org/) (draft 2020-12)
+/// describing a [`TracingEvent`] serialized as JSON, for validating events
+/// or generating types to read them in other languages.
+///
+/// The schema describes the JSON the bridge writes rather than everything it
+/// would accept, so fields that may be missing when deserializing are still
+/// required. It always includes the `Nested` field value, whether or not the
+/// `valuable` feature is enabled, so that it doesn't depend on the features
+/// the bridge was built with.
+///
+/// ```
+/// let schema = tracing_bridge::event_json_schema();
+/// assert_eq!(schema["$id"], "urn:tracing-bridge:event:v9");
+/// assert_eq!(schema["title"], "TracingEvent");
+/// ```
+pub fn event_json_schema() -> serde_json::Value {
+    let mut schema = SchemaSettings::draft2020_12()
+        .for_serialize()
+        .into_generator()
+        .into_root_schema_for::<TracingEvent>();
+    schema.insert(
+        "$id".to_owned(),
+        format!("urn:tracing-bridge:event:v{}", EVENT_SCHEMA_VERSION).into(),
+    );
+    schema.to_value()
+}
+
+/// Fields serialize as a map from name to value, in recording order.
+impl JsonSchema for TracingFields {
+    fn schema_name() -> Cow<'static, str> {
+        "TracingFields".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "type": "object",
+            "additionalProperties": generator.subschema_for::<FieldValue>(),
+        })
+    }
+}
+
+/// Described by `FieldValueSchema`, which unlike [`FieldValue`] has every
+/// variant whatever features are enabled.
+impl JsonSchema for FieldValue {
+    fn schema_name() -> Cow<'static, str> {
+        "FieldValue".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        FieldValueSchema::json_schema(generator)
+    }
+}
+
+/// The value of a field recorded on an event, typed according to the `Visit`
+/// method that recorded it.
+#[derive(JsonSchema)]
+#[allow(dead_code)]
+enum FieldValueSchema {
+    /// A value recorded with `record_i64`.
+    I64(i64),
+    /// A value recorded with `record_u64`.
+    U64(u64),
+    /// A value recorded with `record_f64`. `NaN` and the infinities are
+    /// written as the strings `"NaN"`, `"inf"` and `"-inf"`.
+    F64(#[schemars(schema_with = "float")] f64),
+    /// A value recorded with `record_bool`.
+    Bool(bool),
+    /// A value recorded with `record_str`.
+    Str(String),
+    /// The `Debug` representation of a value that could only be recorded with
+    /// `record_debug`.
+    Debug(String),
+    /// An error recorded with `record_error`, captured as the `Display` output
+    /// of the error itself and of each error in its `source()` chain.
+    Error {
+        /// The top-level error message.
+        message: String,
+        /// The messages of the error's causes, nearest cause first.
+        causes: Vec<String>,
+    },
+    /// A value that would have been captured as `Str` or `Debug`, cut short
+    /// because it was longer than the maximum field length.
+    Truncated {
+        /// The start of the value: as many whole characters as fit in the
+        /// maximum length.
+        value: String,
+        /// The length in bytes of the whole value.
+        original_len: usize,
+    },
+    /// A structured value recorded with `record_value`, such as a struct,
+    /// map or list, converted into JSON. Only recorded when the bridge is
+    /// built with the `valuable` feature.
+    Nested(serde_json::Value),
+}
+
+/// Describes a float as written by the `float` serde module.
+fn float(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "anyOf": [
+            { "type": "number" },
+            { "enum": ["NaN", "inf", "-inf"] },
+        ],
+    })
+}
+
+/// Describes a timestamp as any [`TimestampFormat`](crate::TimestampFormat)
+/// writes it: a number of nanoseconds or milliseconds, or an RFC 3339 string.
+pub(crate) fn timestamp(_: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "anyOf": [
+            { "type": "integer", "format": "uint64", "minimum": 0 },
+            { "type": "string", "format": "date-time" },
+            { "type": "null" },
+        ],
+    })
+}
+
+/// Describes a level as any [`LevelFormat`](crate::LevelFormat) writes it:
+/// its name or its priority.
+pub(crate) fn level(generator: &mut SchemaGenerator) -> Schema {
+    json_schema!({
+        "anyOf": [
+            generator.subschema_for::<TracingLevel>(),
+            { "type": "integer", "format": "uint8", "minimum": 0, "maximum": 255 },
+        ],
+    })
+}
@@ -0,0 +1,108 @@
+use alloc::{borrow::Cow, string::ToString};
+
+use crate::{FieldValue, TracingEvent, TracingFields};
+
+impl FieldValue {
+    /// Returns whether the two values mean the same thing, even if they are
+    /// recorded as different variants, e.g. because one of them went through
+    /// a format that doesn't keep types, like `logfmt` or plain JSON.
+    ///
+    /// Values are semantically equal if they are equal (`==`), or if:
+    ///
+    /// - both are numbers (`I64`, `U64` or `F64`) with the same numeric
+    ///   value, so `I64(1)`, `U64(1)` and `F64(1.0)` are all equal, and any
+    ///   two `NaN`s are equal;
+    /// - one is a `Str` or `Debug` whose text is the other's text, so
+    ///   `Debug("1")` equals `I64(1)`, `Str("true")` equals `Bool(true)`, and
+    ///   `Str("x")` equals `Debug("x")`. An error's text is its message
+    ///   followed by each of its causes, separated by `: `, and a nested
+    ///   value's text is its JSON.
+    ///
+    /// Otherwise values of different variants are never semantically equal,
+    /// so `Bool(true)` doesn't equal `I64(1)`. Unlike `==`, this is not
+    /// transitive: `Str("1.0")` equals `F64(1.0)`, which equals `I64(1)`,
+    /// which doesn't equal `Str("1.0")`.
+    ///
+    /// ```
+    /// use tracing_bridge::FieldValue;
+    ///
+    /// assert!(FieldValue::I64(1).semantically_eq(&FieldValue::Debug("1".to_owned())));
+    /// assert!(FieldValue::U64(2).semantically_eq(&FieldValue::F64(2.0)));
+    /// assert!(!FieldValue::Bool(true).semantically_eq(&FieldValue::I64(1)));
+    /// ```
+    pub fn semantically_eq(&self, other: &Self) -> bool {
+        use FieldValue::*;
+
+        if self == other {
+            return true;
+        }
+        match (self, other) {
+            (I64(a), U64(b)) | (U64(b), I64(a)) => i128::from(*a) == i128::from(*b),
+            (I64(a), F64(b)) | (F64(b), I64(a)) => int_eq_float(i128::from(*a), *b),
+            (U64(a), F64(b)) | (F64(b), U64(a)) => int_eq_float(i128::from(*a), *b),
+            (F64(a), F64(b)) => a == b || (a.is_nan() && b.is_nan()),
+            (Str(text), value)
+            | (Debug(text), value)
+            | (value, Str(text))
+            | (value, Debug(text)) => value.text() == text.as_str(),
+            _ => false,
+        }
+    }
+
+    /// Returns the value as plain text, without the quotes that `Display`
+    /// puts around strings.
+    fn text(&self) -> Cow<'_, str> {
+        match self {
+            Self::Str(value) | Self::Debug(value) => Cow::Borrowed(value),
+            value => Cow::Owned(value.to_string()),
+        }
+    }
+}
+
+/// Returns whether `float` is exactly the integer `int`.
+fn int_eq_float(int: i128, float: f64) -> bool {
+    float.fract() == 0.0
+        && float >= i128::MIN as f64
+        && float < i128::MAX as f64
+        && float as i128 == int
+}
+
+impl TracingFields {
+    /// Returns whether the two sets of fields hold the same names with
+    /// semantically equal values (see [`FieldValue::semantically_eq`]).
+    ///
+    /// As with `==`, the order of different names doesn't matter, but the
+    /// values of a repeated name must be in the same order.
+    pub fn semantically_eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .sorted()
+                .into_iter()
+                .zip(other.sorted())
+                .all(|((a_name, a), (b_name, b))| a_name == b_name && a.semantically_eq(b))
+    }
+}
+
+impl TracingEvent {
+    /// Returns whether the two events are equal, except that their field
+    /// values only need to be semantically equal (see
+    /// [`FieldValue::semantically_eq`]).
+    ///
+    /// This is the comparison to use against expected events when the
+    /// captured ones went through a format that doesn't keep the types of
+    /// field values. Serializing an event with `serde` keeps them, so an
+    /// event read back from JSON, bincode or MessagePack is `==` to the
+    /// original.
+    pub fn semantically_eq(&self, other: &Self) -> bool {
+        self.metadata == other.metadata
+            && self.message == other.message
+            && self.fields.semantically_eq(&other.fields)
+            && self.timestamp == other.timestamp
+            && self.monotonic_nanos == other.monotonic_nanos
+            && self.seq == other.seq
+            && self.thread_id == other.thread_id
+            && self.thread_name == other.thread_name
+            && self.span_context == other.span_context
+            && self.current_span_id == other.current_span_id
+    }
+}
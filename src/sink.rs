@@ -0,0 +1,162 @@
+use std::{fmt, sync::Arc};
+
+use crate::{SinkError, SpanEvent, TracingEvent, TracingEventRef};
+
+pub(crate) mod batching;
+pub(crate) mod capturing;
+pub(crate) mod channel;
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+pub(crate) mod compressed;
+pub(crate) mod error;
+pub(crate) mod fan_out;
+#[cfg(feature = "json")]
+pub(crate) mod json_array;
+#[cfg(feature = "json")]
+pub(crate) mod json_lines;
+#[cfg(feature = "json")]
+pub(crate) mod non_blocking;
+pub(crate) mod ring_buffer;
+pub(crate) mod serializer;
+pub(crate) mod writer;
+
+/// A destination for the events captured by a [`BridgeLayer`](crate::BridgeLayer).
+///
+/// Sinks are called synchronously from the thread that emitted the event, so
+/// a slow sink slows down the code doing the logging. Sinks that may block
+/// (on I/O, a full queue, etc.) should hand events off to another thread and
+/// decide for themselves whether to wait or drop the event when they can't
+/// keep up.
+///
+/// Sinks report events they couldn't deliver by returning a [`SinkError`],
+/// which the layer passes to its error handler (see
+/// [`BridgeLayer::with_error_handler`](crate::BridgeLayer::with_error_handler)).
+///
+/// Any `Fn(TracingEvent)` closure can be used as a sink that never fails.
+pub trait EventSink: Send + Sync + 'static {
+    /// Receives a captured event.
+    fn emit(&self, event: TracingEvent) -> Result<(), SinkError>;
+
+    /// Receives a captured event that is only borrowed for the duration of
+    /// the call. The layer calls this instead of [`emit`](Self::emit)
+    /// whenever it doesn't need the event's fields itself, i.e. unless field
+    /// filters, inherited fields, global fields or a span extension extractor
+    /// are configured.
+    ///
+    /// The default converts the event with [`TracingEventRef::to_owned`] and
+    /// calls `emit`. Sinks that serialize events synchronously should
+    /// override it to serialize `event` directly, which skips allocating a
+    /// `String` for each field.
+    fn emit_ref(&self, event: &TracingEventRef<'_>) -> Result<(), SinkError> {
+        self.emit(event.to_owned())
+    }
+
+    /// Receives several captured events at once, in the order they were
+    /// emitted. The default calls [`emit`](Self::emit) for each event; sinks
+    /// that can amortize work across events should override it.
+    ///
+    /// If any events are lost, returns an error that counts all of them.
+    fn emit_batch(&self, events: Vec<TracingEvent>) -> Result<(), SinkError> {
+        let mut error: Option<SinkError> = None;
+        for event in events {
+            if let Err(later) = self.emit(event) {
+                error = Some(match error {
+                    Some(error) => error.merge(later),
+                    None => later,
+                });
+            }
+        }
+        error.map_or(Ok(()), Err)
+    }
+
+    /// Receives a span lifecycle transition. Sinks that only care about
+    /// events can leave this as the default, which ignores the transition.
+    fn emit_span(&self, event: SpanEvent) -> Result<(), SinkError> {
+        let _ = event;
+        Ok(())
+    }
+
+    /// Delivers any events the sink has buffered, and flushes any writer it
+    /// writes to. The default does nothing, which suits sinks that don't
+    /// buffer.
+    fn flush(&self) -> Result<(), SinkError> {
+        Ok(())
+    }
+
+    /// Flushes the sink and stops any background threads it runs, waiting
+    /// for them to exit. Called when a [`FlushGuard`] is dropped.
+    ///
+    /// The sink must still accept events afterwards, since a global
+    /// subscriber keeps running until the process exits, but it may deliver
+    /// them less promptly. The default calls [`flush`](Self::flush).
+    fn shutdown(&self) -> Result<(), SinkError> {
+        self.flush()
+    }
+}
+
+/// Shuts down the sink of a [`BridgeLayer`](crate::BridgeLayer) when dropped,
+/// delivering any events it still buffers. Errors while doing so go to the
+/// layer's error handler. Returned by
+/// [`BridgeLayer::flush_guard`](crate::BridgeLayer::flush_guard).
+///
+/// A global subscriber is never dropped, so sinks that buffer events (such as
+/// a [`BatchingSink`](crate::BatchingSink) or a compressed
+/// [`JsonLinesSink`](crate::JsonLinesSink)) lose whatever they hold when the
+/// process exits, unless a guard is dropped first. Dropping the guard is the
+/// only way to be sure the tail of the stream is delivered, so keep it alive
+/// in `main` for as long as the program runs:
+///
+/// ```
+/// use tracing_bridge::{BatchingSink, BridgeLayer};
+/// use tracing_subscriber::layer::SubscriberExt;
+///
+/// let sink = BatchingSink::background(
+///     |event| println!("{:?}", event),
+///     100,
+///     std::time::Duration::from_secs(1),
+/// );
+/// let layer = BridgeLayer::new(sink);
+/// let _guard = layer.flush_guard();
+/// let subscriber = tracing_subscriber::registry().with(layer);
+/// tracing_core::dispatcher::set_global_default(tracing_core::Dispatch::new(subscriber))
+///     .expect("no other global subscriber should be set");
+///
+/// // ... run the program; the batch is delivered when `_guard` is dropped.
+/// ```
+#[must_use = "dropping the guard immediately shuts down the sink"]
+pub struct FlushGuard {
+    sink: Arc<dyn EventSink>,
+    on_error: Box<dyn Fn(SinkError) + Send + Sync>,
+}
+
+impl FlushGuard {
+    pub(crate) fn new(
+        sink: Arc<dyn EventSink>,
+        on_error: Box<dyn Fn(SinkError) + Send + Sync>,
+    ) -> Self {
+        Self { sink, on_error }
+    }
+}
+
+impl fmt::Debug for FlushGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FlushGuard").finish_non_exhaustive()
+    }
+}
+
+impl Drop for FlushGuard {
+    fn drop(&mut self) {
+        if let Err(error) = self.sink.shutdown() {
+            (self.on_error)(error);
+        }
+    }
+}
+
+impl<F> EventSink for F
+where
+    F: Fn(TracingEvent) + Send + Sync + 'static,
+{
+    fn emit(&self, event: TracingEvent) -> Result<(), SinkError> {
+        self(event);
+        Ok(())
+    }
+}
@@ -0,0 +1,204 @@
+use std::{
+    mem,
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Arc, Mutex, PoisonError,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{EventSink, SinkError, SpanEvent, TracingEvent};
+
+/// An [`EventSink`] that collects events into batches and hands each batch to
+/// an inner sink with [`EventSink::emit_batch`].
+///
+/// A batch is flushed once it holds `max_batch` events or its oldest event has
+/// waited `max_delay`, whichever comes first. Sinks created with
+/// [`BatchingSink::new`] only check the delay when an event arrives, so a
+/// partial batch can wait indefinitely if events stop; those created with
+/// [`BatchingSink::background`] also check it from a background thread.
+///
+/// Errors from the inner sink are returned from the call that flushed the
+/// batch. Errors from flushes made by the background thread, which has no
+/// caller to return them to, are returned from the next call to
+/// [`emit`](EventSink::emit) or [`flush`](EventSink::flush) instead.
+///
+/// Any buffered events are flushed when the sink is dropped, or when it is
+/// shut down through a [`FlushGuard`](crate::FlushGuard), which also stops
+/// the background thread. Span transitions are not batched and are passed
+/// straight through.
+pub struct BatchingSink<K: EventSink> {
+    shared: Arc<Shared<K>>,
+    worker: Mutex<Option<Worker>>,
+}
+
+struct Shared<K> {
+    inner: K,
+    batch: Mutex<Batch>,
+    /// An error from a background flush, waiting to be returned.
+    pending_error: Mutex<Option<SinkError>>,
+    max_batch: usize,
+    max_delay: Duration,
+}
+
+#[derive(Default)]
+struct Batch {
+    events: Vec<TracingEvent>,
+    oldest: Option<Instant>,
+}
+
+struct Worker {
+    // Dropping the sender tells the worker to stop.
+    stop: mpsc::Sender<()>,
+    handle: thread::JoinHandle<()>,
+}
+
+impl<K: EventSink> BatchingSink<K> {
+    pub fn new(inner: K, max_batch: usize, max_delay: Duration) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                inner,
+                batch: Mutex::new(Batch::default()),
+                pending_error: Mutex::new(None),
+                max_batch,
+                max_delay,
+            }),
+            worker: Mutex::new(None),
+        }
+    }
+
+    /// Like [`BatchingSink::new`], but also spawns a thread that flushes the
+    /// batch once `max_delay` has passed, even if no new events arrive.
+    pub fn background(inner: K, max_batch: usize, max_delay: Duration) -> Self {
+        let sink = Self::new(inner, max_batch, max_delay);
+        let shared = Arc::clone(&sink.shared);
+        let (stop, stopped) = mpsc::channel::<()>();
+
+        let handle = thread::Builder::new()
+            .name("tracing-bridge-batching".to_owned())
+            .spawn(move || loop {
+                let wait = shared.time_until_due().unwrap_or(shared.max_delay);
+                match stopped.recv_timeout(wait) {
+                    Err(RecvTimeoutError::Timeout) => {
+                        if let Err(error) = shared.flush_if_due() {
+                            shared.defer_error(error);
+                        }
+                    }
+                    Ok(()) | Err(RecvTimeoutError::Disconnected) => break,
+                }
+            })
+            .expect("failed to spawn batching thread");
+
+        *sink.worker.lock().unwrap_or_else(PoisonError::into_inner) = Some(Worker { stop, handle });
+        sink
+    }
+
+    /// Stops the background thread, if there is one, and waits for it to exit.
+    fn stop_worker(&self) {
+        let worker = self
+            .worker
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        if let Some(Worker { stop, handle }) = worker {
+            drop(stop);
+            let _ = handle.join();
+        }
+    }
+}
+
+impl<K: EventSink> Shared<K> {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Batch> {
+        self.batch.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn time_until_due(&self) -> Option<Duration> {
+        let oldest = self.lock().oldest?;
+        Some(self.max_delay.saturating_sub(oldest.elapsed()))
+    }
+
+    fn flush_if_due(&self) -> Result<(), SinkError> {
+        if self.time_until_due() == Some(Duration::ZERO) {
+            self.flush()
+        } else {
+            Ok(())
+        }
+    }
+
+    fn flush(&self) -> Result<(), SinkError> {
+        let events = mem::take(&mut *self.lock()).events;
+        if events.is_empty() {
+            Ok(())
+        } else {
+            self.inner.emit_batch(events)
+        }
+    }
+
+    fn defer_error(&self, error: SinkError) {
+        let mut pending = self
+            .pending_error
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        *pending = Some(match pending.take() {
+            Some(pending) => pending.merge(error),
+            None => error,
+        });
+    }
+
+    /// Combines `result` with any error deferred from the background thread.
+    fn with_deferred(&self, result: Result<(), SinkError>) -> Result<(), SinkError> {
+        let pending = self
+            .pending_error
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        match (pending, result) {
+            (None, result) => result,
+            (Some(pending), Ok(())) => Err(pending),
+            (Some(pending), Err(error)) => Err(pending.merge(error)),
+        }
+    }
+}
+
+impl<K: EventSink> EventSink for BatchingSink<K> {
+    fn emit(&self, event: TracingEvent) -> Result<(), SinkError> {
+        let full = {
+            let mut batch = self.shared.lock();
+            batch.oldest.get_or_insert_with(Instant::now);
+            batch.events.push(event);
+            batch.events.len() >= self.shared.max_batch
+        };
+
+        let result = if full {
+            self.shared.flush()
+        } else {
+            self.shared.flush_if_due()
+        };
+        self.shared.with_deferred(result)
+    }
+
+    fn emit_span(&self, event: SpanEvent) -> Result<(), SinkError> {
+        self.shared.inner.emit_span(event)
+    }
+
+    /// Hands any buffered events to the inner sink immediately, then flushes
+    /// the inner sink.
+    fn flush(&self) -> Result<(), SinkError> {
+        let result = self.shared.flush().and(self.shared.inner.flush());
+        self.shared.with_deferred(result)
+    }
+
+    fn shutdown(&self) -> Result<(), SinkError> {
+        self.stop_worker();
+        let result = self.shared.flush().and(self.shared.inner.shutdown());
+        self.shared.with_deferred(result)
+    }
+}
+
+impl<K: EventSink> Drop for BatchingSink<K> {
+    fn drop(&mut self) {
+        self.stop_worker();
+        let _ = self.shared.flush();
+    }
+}
@@ -0,0 +1,75 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::{BridgeLayer, EventSink, SinkError, TracingEvent};
+
+/// An [`EventSink`] that keeps every event it receives in memory, for tests
+/// that assert on the events the code under test emits.
+///
+/// Clones share the same events, so a test can keep one clone and give the
+/// other to a layer; [`BridgeLayer::capturing`] does both at once.
+///
+/// ```
+/// use tracing_bridge::BridgeLayer;
+/// use tracing_subscriber::layer::SubscriberExt;
+///
+/// let (layer, captured) = BridgeLayer::capturing();
+/// let subscriber = tracing_subscriber::registry().with(layer);
+/// tracing::subscriber::with_default(subscriber, || {
+///     tracing::warn!(retries = 3, "connection slow");
+/// });
+///
+/// let events = captured.drain();
+/// assert_eq!(events.len(), 1);
+/// assert_eq!(events[0].message.as_deref(), Some("connection slow"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CapturingSink {
+    events: Arc<Mutex<Vec<TracingEvent>>>,
+}
+
+impl CapturingSink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a copy of the events captured so far, in the order they were
+    /// emitted, leaving them in the sink.
+    pub fn events(&self) -> Vec<TracingEvent> {
+        self.lock().clone()
+    }
+
+    /// Removes and returns the events captured so far, in the order they were
+    /// emitted.
+    pub fn drain(&self) -> Vec<TracingEvent> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    /// Locks the events, ignoring poisoning: a test that panicked while
+    /// emitting shouldn't hide the events from the test reporting it.
+    fn lock(&self) -> MutexGuard<'_, Vec<TracingEvent>> {
+        self.events
+            .lock()
+            .unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+impl EventSink for CapturingSink {
+    fn emit(&self, event: TracingEvent) -> Result<(), SinkError> {
+        self.lock().push(event);
+        Ok(())
+    }
+
+    fn emit_batch(&self, events: Vec<TracingEvent>) -> Result<(), SinkError> {
+        self.lock().extend(events);
+        Ok(())
+    }
+}
+
+impl BridgeLayer<CapturingSink> {
+    /// Creates a layer that keeps every event it captures in memory, along
+    /// with a [`CapturingSink`] sharing those events, for reading them back.
+    pub fn capturing() -> (Self, CapturingSink) {
+        let sink = CapturingSink::new();
+        (Self::new(sink.clone()), sink)
+    }
+}
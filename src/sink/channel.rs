@@ -0,0 +1,78 @@
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender, TrySendError};
+
+use crate::{BridgeLayer, EventSink, SinkError, TracingEvent};
+
+/// What a bounded channel sink does with an event when the channel is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Block the emitting thread until the receiver makes room.
+    Block,
+    /// Discard the event and carry on.
+    Drop,
+}
+
+/// An [`EventSink`] that sends each event down a [`std::sync::mpsc`] channel.
+///
+/// Created by [`channel`] or [`bounded_channel`]. Events sent after the
+/// [`Receiver`] has been dropped are discarded with [`SinkError::closed`].
+/// Events dropped because a bounded channel is full are reported with
+/// [`SinkError::full`], and so counted in the layer's
+/// [`DropStats`](crate::DropStats) by default.
+pub struct ChannelSink {
+    sender: ChannelSender,
+}
+
+enum ChannelSender {
+    Unbounded(Sender<TracingEvent>),
+    Bounded(SyncSender<TracingEvent>, OverflowPolicy),
+}
+
+impl EventSink for ChannelSink {
+    fn emit(&self, event: TracingEvent) -> Result<(), SinkError> {
+        match &self.sender {
+            ChannelSender::Unbounded(sender) => sender.send(event).map_err(|_| SinkError::closed()),
+            ChannelSender::Bounded(sender, OverflowPolicy::Block) => {
+                sender.send(event).map_err(|_| SinkError::closed())
+            }
+            ChannelSender::Bounded(sender, OverflowPolicy::Drop) => {
+                sender.try_send(event).map_err(|error| match error {
+                    TrySendError::Full(_) => SinkError::full(),
+                    TrySendError::Disconnected(_) => SinkError::closed(),
+                })
+            }
+        }
+    }
+}
+
+/// Creates a [`BridgeLayer`] that sends events down an unbounded channel,
+/// along with the receiving end of that channel.
+///
+/// The receiver can be iterated directly to process events as they arrive;
+/// iteration ends once the layer has been dropped.
+///
+/// The channel never applies backpressure: if the receiver falls behind,
+/// events accumulate in memory without limit. Use [`bounded_channel`] when
+/// the receiver may not keep up.
+pub fn channel() -> (BridgeLayer<ChannelSink>, Receiver<TracingEvent>) {
+    let (sender, receiver) = mpsc::channel();
+    let sink = ChannelSink {
+        sender: ChannelSender::Unbounded(sender),
+    };
+    (BridgeLayer::new(sink), receiver)
+}
+
+/// Creates a [`BridgeLayer`] that sends events down a channel holding at most
+/// `capacity` events, along with the receiving end of that channel.
+///
+/// When the channel is full, `policy` decides whether the emitting thread
+/// waits for room or the event is dropped.
+pub fn bounded_channel(
+    capacity: usize,
+    policy: OverflowPolicy,
+) -> (BridgeLayer<ChannelSink>, Receiver<TracingEvent>) {
+    let (sender, receiver) = mpsc::sync_channel(capacity);
+    let sink = ChannelSink {
+        sender: ChannelSender::Bounded(sender, policy),
+    };
+    (BridgeLayer::new(sink), receiver)
+}
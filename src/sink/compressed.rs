@@ -0,0 +1,170 @@
+use std::io::{self, Write};
+#[cfg(feature = "json")]
+use std::{
+    fs::{File, OpenOptions},
+    io::BufWriter,
+    path::Path,
+};
+
+#[cfg(feature = "json")]
+use crate::JsonLinesSink;
+
+/// A compression format and level for [`CompressedWriter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// gzip, with a level from 0 (none) to 9 (best).
+    #[cfg(feature = "gzip")]
+    Gzip {
+        /// The compression level.
+        level: u32,
+    },
+    /// Zstandard, with a level from 1 to 22, or 0 for zstd's default.
+    #[cfg(feature = "zstd")]
+    Zstd {
+        /// The compression level.
+        level: i32,
+    },
+}
+
+/// A writer that compresses everything written to it before passing it on to
+/// `W`.
+///
+/// Every [`flush`](Write::flush) ends the current compressed frame (a gzip
+/// member, or a zstd frame) and flushes `W`, and the next write starts a new
+/// frame. Concatenated frames decompress as a single stream with standard
+/// tools such as `zcat` and `zstdcat`, and since each frame is complete on
+/// its own, a file that was cut off mid-frame (e.g. because the process
+/// crashed) can still be read up to the last flush.
+///
+/// Each frame has a small fixed overhead and compresses independently of the
+/// others, so flushing after every write compresses poorly. When used with
+/// [`JsonLinesSink`](crate::JsonLinesSink), set a flush interval with
+/// [`with_flush_interval`](crate::JsonLinesSink::with_flush_interval).
+///
+/// Dropping the writer ends the current frame, ignoring errors; use
+/// [`finish`](Self::finish) to see them. A global subscriber is never
+/// dropped, so anything written since the last flush is lost when the
+/// process exits unless the layer's [`FlushGuard`](crate::FlushGuard) is
+/// dropped first.
+pub struct CompressedWriter<W: Write> {
+    compression: Compression,
+    state: Option<State<W>>,
+}
+
+enum State<W: Write> {
+    /// Between frames.
+    Idle(W),
+    #[cfg(feature = "gzip")]
+    Gzip(flate2::write::GzEncoder<W>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::stream::write::Encoder<'static, W>),
+}
+
+impl<W: Write> CompressedWriter<W> {
+    pub fn new(writer: W, compression: Compression) -> Self {
+        Self {
+            compression,
+            state: Some(State::Idle(writer)),
+        }
+    }
+
+    /// Ends the current frame and returns the underlying writer, flushed.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.end_frame()?;
+        match self.state.take() {
+            Some(State::Idle(writer)) => Ok(writer),
+            _ => Err(poisoned()),
+        }
+    }
+
+    fn end_frame(&mut self) -> io::Result<()> {
+        let mut writer = match self.state.take().ok_or_else(poisoned)? {
+            State::Idle(writer) => writer,
+            #[cfg(feature = "gzip")]
+            State::Gzip(encoder) => encoder.finish()?,
+            #[cfg(feature = "zstd")]
+            State::Zstd(encoder) => encoder.finish()?,
+        };
+        let result = writer.flush();
+        self.state = Some(State::Idle(writer));
+        result
+    }
+
+    fn encoder(&mut self) -> io::Result<&mut dyn Write> {
+        let state = self.state.take().ok_or_else(poisoned)?;
+        let state = match state {
+            State::Idle(writer) => match self.compression {
+                #[cfg(feature = "gzip")]
+                Compression::Gzip { level } => State::Gzip(flate2::write::GzEncoder::new(
+                    writer,
+                    flate2::Compression::new(level),
+                )),
+                #[cfg(feature = "zstd")]
+                Compression::Zstd { level } => {
+                    State::Zstd(zstd::stream::write::Encoder::new(writer, level)?)
+                }
+            },
+            state => state,
+        };
+
+        let state = self.state.insert(state);
+        Ok(match state {
+            State::Idle(writer) => writer,
+            #[cfg(feature = "gzip")]
+            State::Gzip(encoder) => encoder,
+            #[cfg(feature = "zstd")]
+            State::Zstd(encoder) => encoder,
+        })
+    }
+}
+
+impl<W: Write> Write for CompressedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.encoder()?.write(buf)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.encoder()?.write_all(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.end_frame()
+    }
+}
+
+impl<W: Write> Drop for CompressedWriter<W> {
+    fn drop(&mut self) {
+        if self.state.is_some() {
+            let _ = self.end_frame();
+        }
+    }
+}
+
+/// A [`JsonLinesSink`] that compresses its output, see [`CompressedWriter`].
+#[cfg(feature = "json")]
+pub type CompressedSink<W> = JsonLinesSink<CompressedWriter<W>>;
+
+#[cfg(feature = "json")]
+impl CompressedSink<BufWriter<File>> {
+    /// Opens `path` for appending, creating it if it doesn't exist, and
+    /// writes compressed JSON lines to it.
+    ///
+    /// Appending to an existing compressed file is fine: the new frames are
+    /// read as a continuation of the old ones.
+    pub fn to_compressed_file(
+        path: impl AsRef<Path>,
+        compression: Compression,
+    ) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::new(CompressedWriter::new(
+            BufWriter::new(file),
+            compression,
+        )))
+    }
+}
+
+/// Returned once an error while ending a frame has lost the underlying
+/// writer.
+fn poisoned() -> io::Error {
+    io::Error::other("compressed writer lost its underlying writer after an error")
+}
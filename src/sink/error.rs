@@ -0,0 +1,105 @@
+use std::{error::Error, fmt, io};
+
+/// What went wrong when a sink failed to deliver events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SinkErrorKind {
+    /// The sink's queue or channel was full, so the events were dropped.
+    Full,
+    /// The sink's destination has gone away, e.g. the receiving end of a
+    /// channel was dropped.
+    Closed,
+    /// Writing the events failed, e.g. because of an I/O error.
+    Failed,
+}
+
+/// The error returned by an [`EventSink`](crate::EventSink) that couldn't
+/// deliver one or more events.
+///
+/// Unless the layer was given its own handler with
+/// [`BridgeLayer::with_error_handler`](crate::BridgeLayer::with_error_handler),
+/// errors are counted in the layer's [`DropStats`](crate::DropStats), see
+/// [`DropStats::record_sink_error`](crate::DropStats::record_sink_error).
+#[derive(Debug)]
+pub struct SinkError {
+    kind: SinkErrorKind,
+    events: usize,
+    source: Option<Box<dyn Error + Send + Sync>>,
+}
+
+impl SinkError {
+    /// The sink was full and dropped the event.
+    pub fn full() -> Self {
+        Self::new(SinkErrorKind::Full, None)
+    }
+
+    /// The sink's destination has gone away.
+    pub fn closed() -> Self {
+        Self::new(SinkErrorKind::Closed, None)
+    }
+
+    /// The sink failed to write the event because of `source`.
+    pub fn failed(source: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        Self::new(SinkErrorKind::Failed, Some(source.into()))
+    }
+
+    fn new(kind: SinkErrorKind, source: Option<Box<dyn Error + Send + Sync>>) -> Self {
+        Self {
+            kind,
+            events: 1,
+            source,
+        }
+    }
+
+    /// Sets how many events were lost, for errors that affect a whole batch.
+    /// Defaults to 1.
+    pub fn with_events(self, events: usize) -> Self {
+        Self { events, ..self }
+    }
+
+    pub fn kind(&self) -> SinkErrorKind {
+        self.kind
+    }
+
+    /// Returns how many events were lost.
+    pub fn events(&self) -> usize {
+        self.events
+    }
+
+    /// Folds a later error into this one, so that it also accounts for the
+    /// events the later error lost.
+    pub(crate) fn merge(mut self, later: SinkError) -> Self {
+        self.events += later.events;
+        self
+    }
+}
+
+impl fmt::Display for SinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            SinkErrorKind::Full => f.write_str("sink is full")?,
+            SinkErrorKind::Closed => f.write_str("sink is closed")?,
+            SinkErrorKind::Failed => f.write_str("sink failed to write")?,
+        }
+        if self.events != 1 {
+            write!(f, " ({} events lost)", self.events)?;
+        }
+        if let Some(source) = &self.source {
+            write!(f, ": {}", source)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for SinkError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn Error + 'static))
+    }
+}
+
+impl From<io::Error> for SinkError {
+    fn from(error: io::Error) -> Self {
+        Self::failed(error)
+    }
+}
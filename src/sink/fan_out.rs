@@ -0,0 +1,128 @@
+use crate::{
+    EventSink, FilterConfig, SinkError, SpanEvent, TracingEvent, TracingEventRef, TracingLevel,
+};
+
+/// An [`EventSink`] that passes each event on to every one of several sinks
+/// whose filter matches it, so that one layer can send, say, errors to a
+/// paging service and everything to a file.
+///
+/// An event is moved into the last sink that matches it and cloned for each
+/// other match, so an event that only one sink wants is never cloned.
+/// Borrowed events are passed to every matching sink without being cloned or
+/// converted. Span transitions are passed to every sink, unfiltered.
+///
+/// Errors from the sinks are merged, so the layer counts every event any of
+/// them lost. Events no sink matches are discarded without an error.
+///
+/// ```
+/// use tracing_bridge::{BridgeLayer, FanOutSink, FilterConfig, TracingLevel};
+///
+/// let errors = FilterConfig {
+///     min_level: Some(TracingLevel::Error),
+///     ..FilterConfig::default()
+/// };
+/// let sink = FanOutSink::new()
+///     .route(errors, |event| eprintln!("paging: {}", event))
+///     .route(FilterConfig::default(), |event| println!("{}", event));
+/// let layer = BridgeLayer::new(sink);
+/// ```
+#[derive(Default)]
+pub struct FanOutSink {
+    routes: Vec<(FilterConfig, Box<dyn EventSink>)>,
+}
+
+impl FanOutSink {
+    /// Creates a sink with no routes, which discards every event.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `sink`, which receives the events `filter` matches.
+    pub fn route(mut self, filter: FilterConfig, sink: impl EventSink) -> Self {
+        self.routes.push((filter, Box::new(sink)));
+        self
+    }
+
+    /// Returns the indices of the routes whose filters match `event`.
+    fn matching(&self, event: &TracingEvent) -> Vec<usize> {
+        (0..self.routes.len())
+            .filter(|&i| self.routes[i].0.matches(&event.metadata))
+            .collect()
+    }
+
+    /// Calls `f` on every sink, merging the errors.
+    fn each<F>(&self, mut f: F) -> Result<(), SinkError>
+    where
+        F: FnMut(&dyn EventSink) -> Result<(), SinkError>,
+    {
+        self.routes
+            .iter()
+            .map(|(_, sink)| f(&**sink))
+            .fold(Ok(()), merge)
+    }
+}
+
+fn merge(result: Result<(), SinkError>, later: Result<(), SinkError>) -> Result<(), SinkError> {
+    match (result, later) {
+        (Ok(()), later) => later,
+        (Err(error), Ok(())) => Err(error),
+        (Err(error), Err(later)) => Err(error.merge(later)),
+    }
+}
+
+impl EventSink for FanOutSink {
+    fn emit(&self, event: TracingEvent) -> Result<(), SinkError> {
+        let matching = self.matching(&event);
+        let (&last, others) = match matching.split_last() {
+            Some(split) => split,
+            None => return Ok(()),
+        };
+        let mut result = Ok(());
+        for &i in others {
+            result = merge(result, self.routes[i].1.emit(event.clone()));
+        }
+        merge(result, self.routes[last].1.emit(event))
+    }
+
+    fn emit_ref(&self, event: &TracingEventRef<'_>) -> Result<(), SinkError> {
+        // Route on the target as rewritten, like `emit` does.
+        let level = TracingLevel::from(event.metadata().level());
+        self.routes
+            .iter()
+            .filter(|(filter, _)| filter.allows(level, event.target()))
+            .map(|(_, sink)| sink.emit_ref(event))
+            .fold(Ok(()), merge)
+    }
+
+    fn emit_batch(&self, events: Vec<TracingEvent>) -> Result<(), SinkError> {
+        let mut batches: Vec<Vec<TracingEvent>> = self.routes.iter().map(|_| Vec::new()).collect();
+        for event in events {
+            let matching = self.matching(&event);
+            if let Some((&last, others)) = matching.split_last() {
+                for &i in others {
+                    batches[i].push(event.clone());
+                }
+                batches[last].push(event);
+            }
+        }
+
+        self.routes
+            .iter()
+            .zip(batches)
+            .filter(|(_, batch)| !batch.is_empty())
+            .map(|((_, sink), batch)| sink.emit_batch(batch))
+            .fold(Ok(()), merge)
+    }
+
+    fn emit_span(&self, event: SpanEvent) -> Result<(), SinkError> {
+        self.each(|sink| sink.emit_span(event.clone()))
+    }
+
+    fn flush(&self) -> Result<(), SinkError> {
+        self.each(|sink| sink.flush())
+    }
+
+    fn shutdown(&self) -> Result<(), SinkError> {
+        self.each(|sink| sink.shutdown())
+    }
+}
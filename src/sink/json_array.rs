@@ -0,0 +1,176 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use serde::Serialize;
+
+use crate::{EventSink, SinkError, TracingEvent, TracingEventRef};
+
+/// An [`EventSink`] that writes events to `W` as the elements of a single
+/// JSON array, for tools that want one JSON document rather than
+/// [JSON Lines](crate::JsonLinesSink).
+///
+/// The opening `[` is written with the first event, and each later event is
+/// preceded by a comma. The closing `]` is written when the sink is shut
+/// down, by [`finish`](Self::finish), or as a last resort when the sink is
+/// dropped, ignoring errors. A sink that never received an event writes
+/// `[]`. Events emitted once the array is closed are rejected with an error.
+///
+/// Unlike JSON Lines, where every complete line can still be read, an array
+/// that was never closed is not valid JSON at all, so a process that exits
+/// without closing it loses the whole document to strict parsers. A global
+/// subscriber is never dropped, so keep the layer's
+/// [`FlushGuard`](crate::FlushGuard) alive in `main`: dropping it shuts the
+/// sink down and closes the array.
+///
+/// ```
+/// use tracing_bridge::{EventSink, JsonArraySink, TracingEvent};
+///
+/// let sink = JsonArraySink::new(Vec::new());
+/// sink.emit(TracingEvent::builder().message("first").build()).unwrap();
+/// sink.emit(TracingEvent::builder().message("second").build()).unwrap();
+///
+/// let bytes = sink.finish().unwrap();
+/// let events: Vec<TracingEvent> = serde_json::from_slice(&bytes).unwrap();
+/// assert_eq!(events.len(), 2);
+/// ```
+///
+/// The writer is only flushed by [`flush`](EventSink::flush) and when the
+/// array is closed.
+pub struct JsonArraySink<W: Write> {
+    inner: Mutex<Inner<W>>,
+}
+
+struct Inner<W> {
+    /// Only taken by [`JsonArraySink::finish`].
+    writer: Option<W>,
+    buf: Vec<u8>,
+    state: State,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Nothing has been written.
+    Empty,
+    /// The opening `[` and at least one event have been written.
+    Open,
+    /// The closing `]` has been written.
+    Closed,
+}
+
+impl<W: Write> JsonArraySink<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                writer: Some(writer),
+                buf: Vec::new(),
+                state: State::Empty,
+            }),
+        }
+    }
+
+    /// Closes the array if it is still open and returns the underlying
+    /// writer, flushed.
+    pub fn finish(self) -> io::Result<W> {
+        let mut inner = self.lock();
+        inner.close()?;
+        Ok(inner.writer.take().expect("the writer is only taken once"))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner<W>> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl JsonArraySink<BufWriter<File>> {
+    /// Creates `path`, or truncates it if it exists, since appending to a
+    /// closed array wouldn't leave valid JSON.
+    pub fn to_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write> Inner<W> {
+    fn writer(&mut self) -> &mut W {
+        self.writer
+            .as_mut()
+            .expect("the writer is only taken by `finish`")
+    }
+
+    fn write<T: Serialize>(&mut self, events: impl IntoIterator<Item = T>) -> io::Result<()> {
+        if self.state == State::Closed {
+            return Err(io::Error::other("the JSON array was already closed"));
+        }
+        self.buf.clear();
+        let mut state = self.state;
+        for event in events {
+            self.buf.push(match state {
+                State::Empty => b'[',
+                _ => b',',
+            });
+            serde_json::to_writer(&mut self.buf, &event)?;
+            state = State::Open;
+        }
+        let writer = self
+            .writer
+            .as_mut()
+            .expect("the writer is only taken by `finish`");
+        writer.write_all(&self.buf)?;
+        self.state = state;
+        Ok(())
+    }
+
+    /// Writes the closing `]`, along with the opening `[` if no event was
+    /// written, and flushes the writer. Does nothing if already closed.
+    fn close(&mut self) -> io::Result<()> {
+        let end: &[u8] = match self.state {
+            State::Empty => b"[]",
+            State::Open => b"]",
+            State::Closed => return Ok(()),
+        };
+        self.writer().write_all(end)?;
+        self.state = State::Closed;
+        self.writer().flush()
+    }
+}
+
+impl<W: Write + Send + 'static> EventSink for JsonArraySink<W> {
+    fn emit(&self, event: TracingEvent) -> Result<(), SinkError> {
+        self.lock().write(Some(&event))?;
+        Ok(())
+    }
+
+    fn emit_ref(&self, event: &TracingEventRef<'_>) -> Result<(), SinkError> {
+        self.lock().write(Some(event))?;
+        Ok(())
+    }
+
+    fn emit_batch(&self, events: Vec<TracingEvent>) -> Result<(), SinkError> {
+        self.lock()
+            .write(&events)
+            .map_err(|error| SinkError::from(error).with_events(events.len()))
+    }
+
+    fn flush(&self) -> Result<(), SinkError> {
+        self.lock().writer().flush()?;
+        Ok(())
+    }
+
+    /// Closes the array. Events emitted afterwards are rejected.
+    fn shutdown(&self) -> Result<(), SinkError> {
+        self.lock().close()?;
+        Ok(())
+    }
+}
+
+impl<W: Write> Drop for JsonArraySink<W> {
+    fn drop(&mut self) {
+        let inner = self.inner.get_mut().unwrap_or_else(PoisonError::into_inner);
+        if inner.writer.is_some() {
+            let _ = inner.close();
+        }
+    }
+}
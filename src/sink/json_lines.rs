@@ -0,0 +1,264 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufWriter, Write},
+    path::Path,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use serde::{de::DeserializeSeed, Serialize};
+
+use crate::{
+    EventFormat, EventSink, LevelFormat, SinkError, TimestampFormat, TracingEvent, TracingEventRef,
+};
+
+/// An [`EventSink`] that writes each event to `W` as a single line of JSON.
+///
+/// The writer is flushed whenever an event is written and at least the flush
+/// interval has passed since the last flush. The interval defaults to zero,
+/// which flushes after every event.
+///
+/// Write errors don't panic or stop the sink: they are returned to the layer
+/// as [`SinkError`]s, and later events are still attempted.
+pub struct JsonLinesSink<W> {
+    inner: Mutex<Inner<W>>,
+    flush_interval: Duration,
+}
+
+struct Inner<W> {
+    writer: W,
+    buf: Vec<u8>,
+    last_flush: Instant,
+}
+
+impl<W: Write> JsonLinesSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                writer,
+                buf: Vec::new(),
+                last_flush: Instant::now(),
+            }),
+            flush_interval: Duration::ZERO,
+        }
+    }
+
+    /// Sets the minimum time between flushes of the underlying writer.
+    pub fn with_flush_interval(self, flush_interval: Duration) -> Self {
+        Self {
+            flush_interval,
+            ..self
+        }
+    }
+
+    /// Consumes the sink, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+            .writer
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner<W>> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl JsonLinesSink<BufWriter<File>> {
+    /// Opens `path` for appending, creating it if it doesn't exist.
+    pub fn to_file(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::new(BufWriter::new(file)))
+    }
+}
+
+impl<W: Write> Inner<W> {
+    fn write<T: Serialize>(
+        &mut self,
+        events: impl IntoIterator<Item = T>,
+        flush_interval: Duration,
+    ) -> io::Result<()> {
+        self.buf.clear();
+        for event in events {
+            serde_json::to_writer(&mut self.buf, &event)?;
+            self.buf.push(b'\n');
+        }
+        self.writer.write_all(&self.buf)?;
+
+        if self.last_flush.elapsed() >= flush_interval {
+            self.writer.flush()?;
+            self.last_flush = Instant::now();
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write + Send + 'static> EventSink for JsonLinesSink<W> {
+    fn emit(&self, event: TracingEvent) -> Result<(), SinkError> {
+        self.lock().write(Some(&event), self.flush_interval)?;
+        Ok(())
+    }
+
+    fn emit_ref(&self, event: &TracingEventRef<'_>) -> Result<(), SinkError> {
+        self.lock().write(Some(event), self.flush_interval)?;
+        Ok(())
+    }
+
+    fn emit_batch(&self, events: Vec<TracingEvent>) -> Result<(), SinkError> {
+        self.lock()
+            .write(&events, self.flush_interval)
+            .map_err(|error| SinkError::from(error).with_events(events.len()))
+    }
+
+    fn flush(&self) -> Result<(), SinkError> {
+        let mut inner = self.lock();
+        inner.writer.flush()?;
+        inner.last_flush = Instant::now();
+        Ok(())
+    }
+}
+
+/// What a [`JsonLinesReader`] does with a line that isn't a valid event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InvalidLinePolicy {
+    /// Yield an error for the line, then carry on with the next one.
+    #[default]
+    Report,
+    /// Skip the line, counting it in [`JsonLinesReader::skipped`].
+    Skip,
+    /// Yield an error for the line, then end iteration.
+    Stop,
+}
+
+/// Reads events written by a [`JsonLinesSink`], one per line. Returned by
+/// [`read_json_lines`].
+///
+/// Blank lines are skipped. Lines that aren't valid events are handled
+/// according to the reader's [`InvalidLinePolicy`], and are reported as
+/// [`io::ErrorKind::InvalidData`] errors naming the line. An error reading
+/// from the underlying reader is yielded and ends iteration, whatever the
+/// policy.
+///
+/// Timestamps written as numbers are read as nanoseconds, and levels are
+/// read as names, unless the reader is given other formats with
+/// [`with_timestamp_format`](Self::with_timestamp_format) and
+/// [`with_level_format`](Self::with_level_format).
+pub struct JsonLinesReader<R> {
+    reader: R,
+    policy: InvalidLinePolicy,
+    format: EventFormat,
+    line: String,
+    line_number: u64,
+    skipped: u64,
+    done: bool,
+}
+
+/// Reads the events in JSON Lines from `reader`, such as a file written by a
+/// [`JsonLinesSink`].
+///
+/// ```
+/// use std::io::Cursor;
+/// use tracing_bridge::{read_json_lines, InvalidLinePolicy, TracingEvent};
+///
+/// let event = TracingEvent::builder().message("hello").build();
+/// let input = format!("{}\n\nnot json\n", serde_json::to_string(&event).unwrap());
+///
+/// let mut events = read_json_lines(Cursor::new(input.as_bytes()))
+///     .with_invalid_lines(InvalidLinePolicy::Skip);
+/// assert_eq!(events.next().unwrap().unwrap(), event);
+/// assert!(events.next().is_none());
+/// assert_eq!(events.skipped(), 1);
+/// ```
+pub fn read_json_lines<R: BufRead>(reader: R) -> JsonLinesReader<R> {
+    JsonLinesReader {
+        reader,
+        policy: InvalidLinePolicy::default(),
+        format: EventFormat::default(),
+        line: String::new(),
+        line_number: 0,
+        skipped: 0,
+        done: false,
+    }
+}
+
+impl<R> JsonLinesReader<R> {
+    /// Sets what the reader does with lines that aren't valid events.
+    pub fn with_invalid_lines(self, policy: InvalidLinePolicy) -> Self {
+        Self { policy, ..self }
+    }
+
+    /// Reads timestamps written in `format`, e.g. by a
+    /// [`WriterSink`](crate::WriterSink) given the same format, rather than
+    /// as nanoseconds since the Unix epoch.
+    pub fn with_timestamp_format(self, format: TimestampFormat) -> Self {
+        Self {
+            format: self.format.with_timestamp_format(format),
+            ..self
+        }
+    }
+
+    /// Reads levels written in `format`, e.g. by a
+    /// [`WriterSink`](crate::WriterSink) given the same format, rather than
+    /// as names.
+    pub fn with_level_format(self, format: LevelFormat) -> Self {
+        Self {
+            format: self.format.with_level_format(format),
+            ..self
+        }
+    }
+
+    /// Returns the number of invalid lines skipped so far under
+    /// [`InvalidLinePolicy::Skip`].
+    pub fn skipped(&self) -> u64 {
+        self.skipped
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads one event in the reader's format from `line`.
+    fn parse(&self, line: &str) -> serde_json::Result<TracingEvent> {
+        let mut deserializer = serde_json::Deserializer::from_str(line);
+        let event = self.format.deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(event)
+    }
+}
+
+impl<R: BufRead> Iterator for JsonLinesReader<R> {
+    type Item = io::Result<TracingEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            self.line.clear();
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(error) => {
+                    self.done = true;
+                    return Some(Err(error));
+                }
+            }
+            self.line_number += 1;
+
+            let line = self.line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = match self.parse(line) {
+                Ok(event) => return Some(Ok(event)),
+                Err(error) => error,
+            };
+            match self.policy {
+                InvalidLinePolicy::Skip => self.skipped += 1,
+                InvalidLinePolicy::Report | InvalidLinePolicy::Stop => {
+                    self.done |= self.policy == InvalidLinePolicy::Stop;
+                    let message = format!("line {}: {}", self.line_number, error);
+                    return Some(Err(io::Error::new(io::ErrorKind::InvalidData, message)));
+                }
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,271 @@
+use std::{
+    fs::OpenOptions,
+    io::{self, BufWriter, Write},
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, Receiver, SyncSender, TrySendError},
+        Arc, Mutex, PoisonError, RwLock,
+    },
+    thread,
+};
+
+use serde::Serialize;
+
+use crate::{EventSink, OverflowPolicy, SinkError, TracingEvent, TracingEventRef};
+
+/// An [`EventSink`] that writes each event to `W` as a single line of JSON
+/// from a dedicated background thread, so that emitting an event never waits
+/// on the writer.
+///
+/// Events are serialized on the emitting thread and handed to the writer
+/// thread over a channel holding at most `capacity` lines. When the channel
+/// is full, the [`OverflowPolicy`] decides whether the emitting thread waits
+/// for room or the event is dropped. Dropped events are reported with
+/// [`SinkError::full`], so they are counted in the layer's
+/// [`DropStats`](crate::DropStats), and are also counted by
+/// [`dropped`](Self::dropped).
+///
+/// The writer is flushed whenever the writer thread runs out of lines to
+/// write. Write errors on that thread, which has no caller to return them
+/// to, are returned from the next call to [`emit`](EventSink::emit) or
+/// [`flush`](EventSink::flush) instead.
+///
+/// Lines still queued when the sink is dropped, or shut down through a
+/// [`FlushGuard`](crate::FlushGuard), are written before the writer thread
+/// exits. Events emitted after a shutdown are written synchronously.
+///
+/// ```
+/// use tracing_bridge::{BridgeLayer, NonBlockingSink, OverflowPolicy};
+/// use tracing_subscriber::layer::SubscriberExt;
+///
+/// let sink = NonBlockingSink::new(std::io::stdout(), 1024, OverflowPolicy::Drop);
+/// let layer = BridgeLayer::new(sink);
+/// let _guard = layer.flush_guard();
+/// let subscriber = tracing_subscriber::registry().with(layer);
+/// ```
+pub struct NonBlockingSink<W> {
+    shared: Arc<Shared<W>>,
+    /// `None` once the sink has been shut down. Dropping the sender tells
+    /// the writer thread to write what it has queued and exit.
+    sender: RwLock<Option<SyncSender<Message>>>,
+    worker: Mutex<Option<thread::JoinHandle<()>>>,
+    policy: OverflowPolicy,
+}
+
+struct Shared<W> {
+    writer: Mutex<W>,
+    /// An error from the writer thread, waiting to be returned.
+    pending_error: Mutex<Option<SinkError>>,
+    dropped: AtomicU64,
+}
+
+enum Message {
+    Line(Vec<u8>),
+    /// Flush the writer, then report the result.
+    Flush(mpsc::Sender<io::Result<()>>),
+}
+
+impl<W: Write + Send + 'static> NonBlockingSink<W> {
+    /// Spawns the writer thread, which writes to `writer` the lines queued on
+    /// a channel holding at most `capacity` of them.
+    pub fn new(writer: W, capacity: usize, policy: OverflowPolicy) -> Self {
+        let shared = Arc::new(Shared {
+            writer: Mutex::new(writer),
+            pending_error: Mutex::new(None),
+            dropped: AtomicU64::new(0),
+        });
+        let (sender, receiver) = mpsc::sync_channel(capacity);
+
+        let worker = Arc::clone(&shared);
+        let handle = thread::Builder::new()
+            .name("tracing-bridge-non-blocking".to_owned())
+            .spawn(move || worker.run(receiver))
+            .expect("failed to spawn non-blocking writer thread");
+
+        Self {
+            shared,
+            sender: RwLock::new(Some(sender)),
+            worker: Mutex::new(Some(handle)),
+            policy,
+        }
+    }
+
+    /// Returns the number of events dropped so far because the channel was
+    /// full.
+    pub fn dropped(&self) -> u64 {
+        self.shared.dropped.load(Ordering::Relaxed)
+    }
+
+    fn send<T: Serialize>(&self, event: &T) -> Result<(), SinkError> {
+        let mut line = Vec::new();
+        serde_json::to_writer(&mut line, event).map_err(io::Error::from)?;
+        line.push(b'\n');
+
+        let result = match &*self.sender.read().unwrap_or_else(PoisonError::into_inner) {
+            Some(sender) => self.enqueue(sender, line),
+            None => self.shared.write(&line).map_err(SinkError::from),
+        };
+        self.shared.with_deferred(result)
+    }
+
+    fn enqueue(&self, sender: &SyncSender<Message>, line: Vec<u8>) -> Result<(), SinkError> {
+        let message = Message::Line(line);
+        let unsent = match self.policy {
+            OverflowPolicy::Block => match sender.send(message) {
+                Ok(()) => return Ok(()),
+                Err(mpsc::SendError(message)) => message,
+            },
+            OverflowPolicy::Drop => match sender.try_send(message) {
+                Ok(()) => return Ok(()),
+                Err(TrySendError::Full(_)) => {
+                    self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+                    return Err(SinkError::full());
+                }
+                Err(TrySendError::Disconnected(message)) => message,
+            },
+        };
+
+        // The writer thread only exits early if it panicked, so write the
+        // line here rather than lose it.
+        match unsent {
+            Message::Line(line) => self.shared.write(&line).map_err(SinkError::from),
+            Message::Flush(_) => unreachable!("only lines are enqueued"),
+        }
+    }
+}
+
+impl<W> NonBlockingSink<W> {
+    /// Stops the writer thread, if it is still running, and waits for it to
+    /// write what it has queued.
+    fn stop_worker(&self) {
+        drop(
+            self.sender
+                .write()
+                .unwrap_or_else(PoisonError::into_inner)
+                .take(),
+        );
+        let worker = self
+            .worker
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        if let Some(handle) = worker {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl NonBlockingSink<BufWriter<std::fs::File>> {
+    /// Opens `path` for appending, creating it if it doesn't exist, and
+    /// spawns a writer thread for it.
+    pub fn to_file(
+        path: impl AsRef<Path>,
+        capacity: usize,
+        policy: OverflowPolicy,
+    ) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::new(BufWriter::new(file), capacity, policy))
+    }
+}
+
+impl<W: Write> Shared<W> {
+    fn lock(&self) -> std::sync::MutexGuard<'_, W> {
+        self.writer.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self, line: &[u8]) -> io::Result<()> {
+        let mut writer = self.lock();
+        writer.write_all(line)?;
+        writer.flush()
+    }
+
+    /// Writes queued lines until every sender has been dropped, flushing the
+    /// writer whenever the queue runs dry.
+    fn run(&self, receiver: Receiver<Message>) {
+        while let Ok(message) = receiver.recv() {
+            let mut next = Some(message);
+            while let Some(message) = next {
+                match message {
+                    Message::Line(line) => {
+                        if let Err(error) = self.lock().write_all(&line) {
+                            self.defer_error(error.into());
+                        }
+                    }
+                    Message::Flush(done) => {
+                        let _ = done.send(self.lock().flush());
+                    }
+                }
+                next = receiver.try_recv().ok();
+            }
+            if let Err(error) = self.lock().flush() {
+                self.defer_error(error.into());
+            }
+        }
+    }
+
+    fn defer_error(&self, error: SinkError) {
+        let mut pending = self
+            .pending_error
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        *pending = Some(match pending.take() {
+            Some(pending) => pending.merge(error),
+            None => error,
+        });
+    }
+
+    /// Combines `result` with any error deferred from the writer thread.
+    fn with_deferred(&self, result: Result<(), SinkError>) -> Result<(), SinkError> {
+        let pending = self
+            .pending_error
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        match (pending, result) {
+            (None, result) => result,
+            (Some(pending), Ok(())) => Err(pending),
+            (Some(pending), Err(error)) => Err(pending.merge(error)),
+        }
+    }
+}
+
+impl<W: Write + Send + 'static> EventSink for NonBlockingSink<W> {
+    fn emit(&self, event: TracingEvent) -> Result<(), SinkError> {
+        self.send(&event)
+    }
+
+    fn emit_ref(&self, event: &TracingEventRef<'_>) -> Result<(), SinkError> {
+        self.send(event)
+    }
+
+    /// Waits for the writer thread to write every line queued so far, then
+    /// flushes the writer.
+    fn flush(&self) -> Result<(), SinkError> {
+        let sender = self.sender.read().unwrap_or_else(PoisonError::into_inner);
+        let result = match &*sender {
+            Some(sender) => {
+                let (done, flushed) = mpsc::channel();
+                match sender.send(Message::Flush(done)) {
+                    Ok(()) => flushed.recv().unwrap_or(Ok(())),
+                    Err(_) => self.shared.lock().flush(),
+                }
+            }
+            None => self.shared.lock().flush(),
+        };
+        drop(sender);
+        self.shared.with_deferred(result.map_err(SinkError::from))
+    }
+
+    fn shutdown(&self) -> Result<(), SinkError> {
+        self.stop_worker();
+        let result = self.shared.lock().flush().map_err(SinkError::from);
+        self.shared.with_deferred(result)
+    }
+}
+
+impl<W> Drop for NonBlockingSink<W> {
+    fn drop(&mut self) {
+        self.stop_worker();
+    }
+}
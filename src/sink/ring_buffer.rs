@@ -0,0 +1,109 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, MutexGuard, PoisonError, TryLockError},
+};
+
+use crate::{EventSink, SinkError, TracingEvent};
+
+/// How many times [`RingBufferSink::snapshot`] tries to take the lock before
+/// giving up.
+const SNAPSHOT_ATTEMPTS: usize = 100;
+
+/// An [`EventSink`] that keeps only the most recent events in memory,
+/// overwriting the oldest, so that recent context can be dumped when the
+/// process crashes, like a flight recorder.
+///
+/// The ring is allocated up front, so emitting an event never allocates, and
+/// the lock is only held to move the event in: the event it overwrites is
+/// dropped after the lock is released. Clones share the same ring, so one
+/// clone can be given to a layer and another to a panic hook.
+///
+/// ```
+/// use tracing_bridge::{BridgeLayer, RingBufferSink};
+/// use tracing_subscriber::layer::SubscriberExt;
+///
+/// let recent = RingBufferSink::new(256);
+/// let subscriber = tracing_subscriber::registry().with(BridgeLayer::new(recent.clone()));
+///
+/// let default_hook = std::panic::take_hook();
+/// std::panic::set_hook(Box::new(move |info| {
+///     for event in recent.snapshot() {
+///         eprintln!("{}", event);
+///     }
+///     default_hook(info);
+/// }));
+/// # let _ = std::panic::take_hook();
+/// # drop(subscriber);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RingBufferSink {
+    ring: Arc<Mutex<VecDeque<TracingEvent>>>,
+    capacity: usize,
+}
+
+impl RingBufferSink {
+    /// Creates a sink keeping the last `capacity` events. A capacity of 0
+    /// keeps none.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            ring: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    /// Returns the most events the ring keeps.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns a copy of the events in the ring, oldest first, leaving them
+    /// in place.
+    ///
+    /// It is safe to call from a panic hook: it never blocks, and if the
+    /// ring stays locked, e.g. because the panicking thread was emitting to
+    /// it, it returns no events rather than deadlocking. A ring poisoned by
+    /// a panic while it was locked is still read.
+    pub fn snapshot(&self) -> Vec<TracingEvent> {
+        for _ in 0..SNAPSHOT_ATTEMPTS {
+            match self.ring.try_lock() {
+                Ok(ring) => return ring.iter().cloned().collect(),
+                Err(TryLockError::Poisoned(error)) => {
+                    return error.into_inner().iter().cloned().collect()
+                }
+                Err(TryLockError::WouldBlock) => std::thread::yield_now(),
+            }
+        }
+        Vec::new()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, VecDeque<TracingEvent>> {
+        self.ring.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Adds `event` to the ring, returning the event it overwrote, if any.
+    fn push(
+        ring: &mut VecDeque<TracingEvent>,
+        capacity: usize,
+        event: TracingEvent,
+    ) -> Option<TracingEvent> {
+        if capacity == 0 {
+            return Some(event);
+        }
+        let overwritten = if ring.len() == capacity {
+            ring.pop_front()
+        } else {
+            None
+        };
+        ring.push_back(event);
+        overwritten
+    }
+}
+
+impl EventSink for RingBufferSink {
+    fn emit(&self, event: TracingEvent) -> Result<(), SinkError> {
+        let overwritten = Self::push(&mut self.lock(), self.capacity, event);
+        // Dropped only now, once the lock has been released.
+        drop(overwritten);
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use crate::{FormattedEvent, SinkError, TracingEvent};
+
+/// Encodes events into bytes, for a [`WriterSink`](crate::WriterSink) to
+/// write, so that the format of a sink is chosen separately from where it
+/// writes.
+///
+/// Implement it to add a format; the built-in formats are
+/// [`JsonSerializer`], [`JsonLinesSerializer`], [`BincodeSerializer`] and
+/// [`MsgpackSerializer`], each behind the feature of the same name.
+pub trait EventSerializer: Send + Sync + 'static {
+    /// Appends the encoding of `event` to `buf`, leaving what is already in
+    /// it as it is.
+    ///
+    /// An event that can't be encoded is reported as a
+    /// [`SinkError::failed`], and may leave part of its encoding in `buf`.
+    fn serialize(&self, event: &TracingEvent, buf: &mut Vec<u8>) -> Result<(), SinkError>;
+
+    /// Appends the encoding of `event`, with its timestamp and level written
+    /// in the [`EventFormat`](crate::EventFormat) it carries, to `buf`.
+    ///
+    /// [`WriterSink`](crate::WriterSink) calls this rather than
+    /// [`serialize`](Self::serialize). The default ignores the format and
+    /// encodes the event as `serialize` does, so serializers that encode
+    /// events with serde should override it to serialize `event` instead.
+    fn serialize_formatted(
+        &self,
+        event: &FormattedEvent<'_>,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), SinkError> {
+        self.serialize(event.event(), buf)
+    }
+}
+
+/// Encodes each event as a single JSON document, the same as serializing it
+/// with `serde_json`, with nothing between one event and the next.
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonSerializer;
+
+#[cfg(feature = "json")]
+impl EventSerializer for JsonSerializer {
+    fn serialize(&self, event: &TracingEvent, buf: &mut Vec<u8>) -> Result<(), SinkError> {
+        serde_json::to_writer(buf, event).map_err(SinkError::failed)
+    }
+
+    fn serialize_formatted(
+        &self,
+        event: &FormattedEvent<'_>,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), SinkError> {
+        serde_json::to_writer(buf, event).map_err(SinkError::failed)
+    }
+}
+
+/// Encodes each event as a line of JSON, in the format a
+/// [`JsonLinesSink`](crate::JsonLinesSink) writes and
+/// [`read_json_lines`](crate::read_json_lines) reads.
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonLinesSerializer;
+
+#[cfg(feature = "json")]
+impl EventSerializer for JsonLinesSerializer {
+    fn serialize(&self, event: &TracingEvent, buf: &mut Vec<u8>) -> Result<(), SinkError> {
+        JsonSerializer.serialize(event, buf)?;
+        buf.push(b'\n');
+        Ok(())
+    }
+
+    fn serialize_formatted(
+        &self,
+        event: &FormattedEvent<'_>,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), SinkError> {
+        JsonSerializer.serialize_formatted(event, buf)?;
+        buf.push(b'\n');
+        Ok(())
+    }
+}
+
+/// Encodes each event as [`TracingEvent::to_bincode`] does. `bincode` knows
+/// where each event ends, so consecutive events can be decoded one after the
+/// other with `bincode::deserialize_from`.
+#[cfg(feature = "bincode")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BincodeSerializer;
+
+#[cfg(feature = "bincode")]
+impl EventSerializer for BincodeSerializer {
+    fn serialize(&self, event: &TracingEvent, buf: &mut Vec<u8>) -> Result<(), SinkError> {
+        bincode::serialize_into(buf, event).map_err(SinkError::failed)
+    }
+
+    fn serialize_formatted(
+        &self,
+        event: &FormattedEvent<'_>,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), SinkError> {
+        bincode::serialize_into(buf, event).map_err(SinkError::failed)
+    }
+}
+
+/// Encodes each event as [`TracingEvent::to_msgpack`] does, one MessagePack
+/// value after another.
+#[cfg(feature = "msgpack")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MsgpackSerializer;
+
+#[cfg(feature = "msgpack")]
+impl EventSerializer for MsgpackSerializer {
+    fn serialize(&self, event: &TracingEvent, buf: &mut Vec<u8>) -> Result<(), SinkError> {
+        rmp_serde::encode::write_named(buf, event).map_err(SinkError::failed)
+    }
+
+    fn serialize_formatted(
+        &self,
+        event: &FormattedEvent<'_>,
+        buf: &mut Vec<u8>,
+    ) -> Result<(), SinkError> {
+        rmp_serde::encode::write_named(buf, event).map_err(SinkError::failed)
+    }
+}
@@ -0,0 +1,113 @@
+use std::{
+    io::Write,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use crate::{
+    EventFormat, EventSerializer, EventSink, FormattedEvent, LevelFormat, SinkError,
+    TimestampFormat, TracingEvent,
+};
+
+/// An [`EventSink`] that encodes each event with an [`EventSerializer`] and
+/// writes the bytes to `W`, so that any format can be written anywhere.
+///
+/// Each event, or each batch, is encoded into a buffer and written with a
+/// single `write_all`, so events are never interleaved or written in part.
+/// The writer is only flushed by [`flush`](EventSink::flush).
+///
+/// ```
+/// # #[cfg(all(feature = "json", feature = "bincode"))]
+/// # {
+/// use std::{fs::File, net::TcpStream};
+///
+/// use tracing_bridge::{
+///     BincodeSerializer, FanOutSink, FilterConfig, JsonLinesSerializer, WriterSink,
+/// };
+///
+/// # fn example(file: File, socket: TcpStream) {
+/// let sink = FanOutSink::new()
+///     .route(FilterConfig::default(), WriterSink::new(JsonLinesSerializer, file))
+///     .route(FilterConfig::default(), WriterSink::new(BincodeSerializer, socket));
+/// # }
+/// # }
+/// ```
+pub struct WriterSink<S, W> {
+    serializer: S,
+    format: EventFormat,
+    inner: Mutex<Inner<W>>,
+}
+
+struct Inner<W> {
+    writer: W,
+    buf: Vec<u8>,
+}
+
+impl<S: EventSerializer, W: Write> WriterSink<S, W> {
+    pub fn new(serializer: S, writer: W) -> Self {
+        Self {
+            serializer,
+            format: EventFormat::default(),
+            inner: Mutex::new(Inner {
+                writer,
+                buf: Vec::new(),
+            }),
+        }
+    }
+
+    /// Writes event timestamps in `format` rather than as nanoseconds since
+    /// the Unix epoch, through
+    /// [`EventSerializer::serialize_formatted`].
+    pub fn with_timestamp_format(mut self, format: TimestampFormat) -> Self {
+        self.format = self.format.with_timestamp_format(format);
+        self
+    }
+
+    /// Writes levels in `format` rather than as their names, through
+    /// [`EventSerializer::serialize_formatted`].
+    pub fn with_level_format(mut self, format: LevelFormat) -> Self {
+        self.format = self.format.with_level_format(format);
+        self
+    }
+
+    /// Consumes the sink, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+            .writer
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner<W>> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Encodes `events` and writes them at once. Nothing is written if any
+    /// of them can't be encoded.
+    fn write(&self, events: &[TracingEvent]) -> Result<(), SinkError> {
+        let mut inner = self.lock();
+        let Inner { writer, buf } = &mut *inner;
+        buf.clear();
+        events.iter().try_for_each(|event| {
+            let event = FormattedEvent::new(event, self.format);
+            self.serializer.serialize_formatted(&event, buf)
+        })?;
+        writer.write_all(buf)?;
+        Ok(())
+    }
+}
+
+impl<S: EventSerializer, W: Write + Send + 'static> EventSink for WriterSink<S, W> {
+    fn emit(&self, event: TracingEvent) -> Result<(), SinkError> {
+        self.write(std::slice::from_ref(&event))
+    }
+
+    fn emit_batch(&self, events: Vec<TracingEvent>) -> Result<(), SinkError> {
+        self.write(&events)
+            .map_err(|error| error.with_events(events.len()))
+    }
+
+    fn flush(&self) -> Result<(), SinkError> {
+        self.lock().writer.flush()?;
+        Ok(())
+    }
+}
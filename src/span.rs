@@ -0,0 +1,141 @@
+use std::{
+    convert::TryFrom,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{FieldOptions, TracingFields, TracingMetadata, TracingMetadataFields};
+
+/// A span captured by a [`BridgeLayer`](crate::BridgeLayer).
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
+pub struct TracingSpan {
+    /// The id the subscriber assigned to the span, as in `tracing`'s
+    /// `span::Id`, or the id the layer's
+    /// [`IdGenerator`](crate::IdGenerator) gave it if it has one. Events
+    /// emitted in the span list the same id in their
+    /// [`span_context`](crate::TracingEvent::span_context), so the two can be
+    /// joined.
+    ///
+    /// Subscribers may reuse the id of a span once it has closed, as
+    /// [`tracing_subscriber::Registry`] does, so unless an id generator
+    /// avoids it, an id only identifies a span between its
+    /// [`SpanEvent::New`] and [`SpanEvent::Close`].
+    pub id: u64,
+
+    /// The id of the span's parent, or `None` if it is a root span.
+    pub parent_id: Option<u64>,
+
+    pub metadata: TracingMetadata,
+    pub fields: TracingFields,
+
+    /// The total time the span was entered for, in nanoseconds. Only known
+    /// once the span has closed, and 0 before then.
+    #[serde(default)]
+    pub busy_nanos: u64,
+
+    /// The total time the span was open but not entered, in nanoseconds.
+    /// Only known once the span has closed, and 0 before then.
+    #[serde(default)]
+    pub idle_nanos: u64,
+}
+
+impl TracingSpan {
+    pub(crate) fn new(
+        id: u64,
+        parent_id: Option<u64>,
+        attrs: &tracing_core::span::Attributes<'_>,
+        options: &FieldOptions,
+    ) -> Self {
+        Self {
+            id,
+            parent_id,
+            metadata: attrs.metadata().into(),
+            fields: TracingMetadataFields::fields_from_attributes(attrs, options),
+            busy_nanos: 0,
+            idle_nanos: 0,
+        }
+    }
+
+    /// Applies values recorded after the span was created, each replacing
+    /// any value the field already had. A field that was declared `Empty`
+    /// takes its place in the order the span's callsite declared its fields,
+    /// rather than going after the fields recorded before it.
+    pub(crate) fn record(
+        &mut self,
+        values: &tracing_core::span::Record<'_>,
+        declared: &tracing_core::field::FieldSet,
+        options: &FieldOptions,
+    ) {
+        let rank = |name: &str| {
+            declared
+                .field(name)
+                .map_or(usize::MAX, |field| field.index())
+        };
+        for (name, value) in TracingMetadataFields::fields_from_record(values, options) {
+            self.fields.insert_ranked(name, value, rank);
+        }
+    }
+}
+
+/// Accumulates how long a span spends entered and not entered, stored in the
+/// span's extensions alongside its [`TracingSpan`].
+pub(crate) struct SpanTimings {
+    busy: Duration,
+    idle: Duration,
+    last: Instant,
+}
+
+impl SpanTimings {
+    pub fn new() -> Self {
+        Self {
+            busy: Duration::ZERO,
+            idle: Duration::ZERO,
+            last: Instant::now(),
+        }
+    }
+
+    pub fn enter(&mut self) {
+        let elapsed = self.lap();
+        self.idle += elapsed;
+    }
+
+    pub fn exit(&mut self) {
+        let elapsed = self.lap();
+        self.busy += elapsed;
+    }
+
+    /// Records the final idle time and stores the totals on `span`.
+    pub fn close(mut self, span: &mut TracingSpan) {
+        let elapsed = self.lap();
+        self.idle += elapsed;
+        span.busy_nanos = nanos(self.busy);
+        span.idle_nanos = nanos(self.idle);
+    }
+
+    fn lap(&mut self) -> Duration {
+        let now = Instant::now();
+        let elapsed = now.saturating_duration_since(self.last);
+        self.last = now;
+        elapsed
+    }
+}
+
+fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+/// A transition in the lifecycle of a span.
+#[derive(Debug, Clone, Serialize, Deserialize, Hash, Eq, PartialEq)]
+pub enum SpanEvent {
+    /// A new span was created.
+    New(TracingSpan),
+    /// The span with the given id was entered.
+    Enter(u64),
+    /// The span with the given id was exited.
+    Exit(u64),
+    /// The span was closed and will not be entered again. Its fields include
+    /// any values recorded after it was created, and its busy and idle times
+    /// are filled in.
+    Close(TracingSpan),
+}
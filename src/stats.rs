@@ -0,0 +1,151 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, PoisonError, RwLock,
+    },
+};
+
+use crate::{SinkError, SinkErrorKind, TracingLevel};
+
+/// Counts events that were captured but never delivered, so data loss is
+/// visible rather than silent.
+///
+/// `DropStats` is a cheap handle to shared counters: clones update and read
+/// the same counts, which lets a [`BridgeLayer`](crate::BridgeLayer) and the
+/// sinks behind it report into one place.
+#[derive(Debug, Clone, Default)]
+pub struct DropStats {
+    counters: Arc<Counters>,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    dropped_full: AtomicU64,
+    dropped_error: AtomicU64,
+    filtered: AtomicU64,
+    rate_limited: AtomicU64,
+}
+
+/// A point-in-time copy of the counts in a [`DropStats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DropCounts {
+    /// Events dropped because a queue or channel was full.
+    pub dropped_full: u64,
+    /// Events dropped because a sink failed to deliver them.
+    pub dropped_error: u64,
+    /// Events deliberately not captured by a filter.
+    pub filtered: u64,
+    /// Events suppressed because their callsite exceeded its rate limit.
+    pub rate_limited: u64,
+}
+
+impl DropStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_full(&self) {
+        self.counters.dropped_full.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_error(&self) {
+        self.counters.dropped_error.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_filtered(&self) {
+        self.counters.filtered.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_rate_limited(&self) {
+        self.counters.rate_limited.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts the events lost to `error`: as dropped because they were full
+    /// for [`SinkErrorKind::Full`], as dropped because of an error for
+    /// [`SinkErrorKind::Failed`], and not at all for
+    /// [`SinkErrorKind::Closed`], since a closed sink usually just means the
+    /// program is shutting down.
+    pub fn record_sink_error(&self, error: &SinkError) {
+        let events = error.events() as u64;
+        match error.kind() {
+            SinkErrorKind::Full => {
+                self.counters
+                    .dropped_full
+                    .fetch_add(events, Ordering::Relaxed);
+            }
+            SinkErrorKind::Failed => {
+                self.counters
+                    .dropped_error
+                    .fetch_add(events, Ordering::Relaxed);
+            }
+            SinkErrorKind::Closed => {}
+        }
+    }
+
+    pub fn snapshot(&self) -> DropCounts {
+        DropCounts {
+            dropped_full: self.counters.dropped_full.load(Ordering::Relaxed),
+            dropped_error: self.counters.dropped_error.load(Ordering::Relaxed),
+            filtered: self.counters.filtered.load(Ordering::Relaxed),
+            rate_limited: self.counters.rate_limited.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Counts the events a [`BridgeLayer`](crate::BridgeLayer) has delivered to
+/// its sink, by level and target, for a quick overview of what a program is
+/// logging. Enabled by [`BridgeLayer::with_metrics`](crate::BridgeLayer::with_metrics).
+///
+/// Like [`DropStats`], `EventMetrics` is a cheap handle: clones count into
+/// and read the same counts. Counting an event from a target that has been
+/// seen before only takes a shared lock and an atomic increment.
+#[derive(Debug, Clone, Default)]
+pub struct EventMetrics {
+    /// The counts for each target, indexed by level severity.
+    targets: Arc<RwLock<HashMap<String, [AtomicU64; 5]>>>,
+}
+
+impl EventMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts one event at `level` from `target`.
+    pub fn record(&self, level: TracingLevel, target: &str) {
+        let index = level.severity() as usize;
+        let targets = self.targets.read().unwrap_or_else(PoisonError::into_inner);
+        if let Some(counts) = targets.get(target) {
+            counts[index].fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        drop(targets);
+
+        let mut targets = self.targets.write().unwrap_or_else(PoisonError::into_inner);
+        let counts = targets.entry(target.to_owned()).or_default();
+        counts[index].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the number of events counted for each level and target. Pairs
+    /// with no events are left out.
+    pub fn snapshot(&self) -> HashMap<(TracingLevel, String), u64> {
+        let levels = [
+            TracingLevel::Trace,
+            TracingLevel::Debug,
+            TracingLevel::Info,
+            TracingLevel::Warn,
+            TracingLevel::Error,
+        ];
+        let targets = self.targets.read().unwrap_or_else(PoisonError::into_inner);
+        let mut snapshot = HashMap::new();
+        for (target, counts) in targets.iter() {
+            for (&level, count) in levels.iter().zip(counts) {
+                let count = count.load(Ordering::Relaxed);
+                if count > 0 {
+                    snapshot.insert((level, target.clone()), count);
+                }
+            }
+        }
+        snapshot
+    }
+}
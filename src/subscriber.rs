@@ -0,0 +1,176 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use tracing_core::{
+    span::{Attributes, Id, Record},
+    subscriber::Interest,
+    Event, LevelFilter, Metadata, Subscriber,
+};
+
+use crate::{
+    filter::FilterHandle,
+    layer::{handle_error, ErrorHandler},
+    unix_nanos, Clock, DropStats, EventSink, FieldOptions, FlushGuard, IdGenerator,
+    MonotonicBaseline, SequentialIds, SinkError, SystemClock, TracingEventRef, TracingLevel,
+};
+
+/// A [`Subscriber`] that converts every event it sees into a
+/// [`TracingEvent`](crate::TracingEvent) and forwards it to an
+/// [`EventSink`], for programs that don't use `tracing-subscriber`'s
+/// registry and so can't use a [`BridgeLayer`](crate::BridgeLayer).
+///
+/// ```
+/// use tracing_bridge::{BridgeSubscriber, TracingLevel};
+///
+/// let subscriber = BridgeSubscriber::new(|event| println!("{:?}", event))
+///     .with_min_level(TracingLevel::Info);
+/// tracing_core::dispatcher::set_global_default(subscriber.into()).unwrap();
+/// ```
+///
+/// It keeps no span data: spans are given ids so they can be entered, but
+/// are not reported to [`EventSink::emit_span`], and events are captured
+/// without a span context. Since it is the whole subscriber, its filter
+/// decides which callsites are enabled at all, like a layer with
+/// [`with_global_filtering`](crate::BridgeLayer::with_global_filtering).
+pub struct BridgeSubscriber<K> {
+    sink: Arc<K>,
+    field_options: FieldOptions,
+    filter: FilterHandle,
+    drop_stats: DropStats,
+    error_handler: Option<ErrorHandler>,
+    clock: Arc<dyn Clock>,
+    baseline: MonotonicBaseline,
+    id_generator: Arc<dyn IdGenerator>,
+    next_seq: AtomicU64,
+}
+
+impl<K: EventSink> BridgeSubscriber<K> {
+    pub fn new(sink: K) -> Self {
+        Self {
+            sink: Arc::new(sink),
+            field_options: FieldOptions {
+                max_field_len: Some(FieldOptions::DEFAULT_MAX_FIELD_LEN),
+                ..FieldOptions::default()
+            },
+            filter: FilterHandle::default(),
+            drop_stats: DropStats::new(),
+            error_handler: None,
+            clock: Arc::new(SystemClock),
+            baseline: MonotonicBaseline::now(),
+            id_generator: Arc::new(SequentialIds::new()),
+            next_seq: AtomicU64::new(0),
+        }
+    }
+
+    /// Only enables events and spans at `min_level` or more severe.
+    pub fn with_min_level(self, min_level: TracingLevel) -> Self {
+        self.filter
+            .update(|config| config.min_level = Some(min_level));
+        self
+    }
+
+    /// Timestamps events with `clock` instead of the system clock.
+    pub fn with_clock<C: Clock>(mut self, clock: C) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Gives spans ids from `generator` instead of numbering them from 1,
+    /// e.g. to keep the ids of different runs or hosts apart. See
+    /// [`IdGenerator`].
+    ///
+    /// # Panics
+    ///
+    /// Creating a span panics if `generator` returns zero.
+    pub fn with_id_generator<G: IdGenerator>(mut self, generator: G) -> Self {
+        self.id_generator = Arc::new(generator);
+        self
+    }
+
+    /// Returns the baseline the subscriber measures
+    /// [`TracingEvent::monotonic_nanos`](crate::TracingEvent::monotonic_nanos)
+    /// from, read when the subscriber was created.
+    pub fn monotonic_baseline(&self) -> MonotonicBaseline {
+        self.baseline
+    }
+
+    /// Calls `handler` with every error the sink returns, instead of counting
+    /// the events it lost in the subscriber's [`DropStats`].
+    pub fn with_error_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(SinkError) + Send + Sync + 'static,
+    {
+        self.error_handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// Returns a handle for replacing the subscriber's level and target
+    /// filters while it is running.
+    pub fn filter_handle(&self) -> FilterHandle {
+        self.filter.clone()
+    }
+
+    /// Returns a handle to the counters of events the sink lost.
+    pub fn drop_stats(&self) -> DropStats {
+        self.drop_stats.clone()
+    }
+
+    /// Returns a guard that shuts down the sink when dropped. See
+    /// [`FlushGuard`].
+    pub fn flush_guard(&self) -> FlushGuard {
+        let error_handler = self.error_handler.clone();
+        let drop_stats = self.drop_stats.clone();
+        FlushGuard::new(
+            self.sink.clone(),
+            Box::new(move |error| handle_error(error_handler.as_ref(), &drop_stats, error)),
+        )
+    }
+
+    /// Returns a reference to the sink events are forwarded to.
+    pub fn sink(&self) -> &K {
+        &self.sink
+    }
+}
+
+impl<K: EventSink> Subscriber for BridgeSubscriber<K> {
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        if self.filter.load().enabled(metadata) {
+            Interest::always()
+        } else {
+            Interest::never()
+        }
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        self.filter.load().enabled(metadata)
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        self.filter.load().level_hint()
+    }
+
+    fn new_span(&self, _attrs: &Attributes<'_>) -> Id {
+        Id::from_u64(self.id_generator.next_span_id())
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut captured = TracingEventRef::with_options(event, &self.field_options);
+        captured.timestamp = unix_nanos(self.clock.now());
+        captured.monotonic_nanos = Some(self.baseline.elapsed_nanos());
+        captured.seq = Some(self.next_seq.fetch_add(1, Ordering::Relaxed));
+        captured.thread = Some(std::thread::current());
+        if let Err(error) = self.sink.emit_ref(&captured) {
+            handle_error(self.error_handler.as_ref(), &self.drop_stats, error);
+        }
+    }
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
@@ -0,0 +1,180 @@
+use std::{collections::HashMap, fmt};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{TracingEvent, TracingFields, TracingMetadata};
+
+/// Interns [`TracingMetadata`] so that events from the same callsite can refer
+/// to it by a small id instead of repeating it.
+///
+/// Ids are assigned in order of first use, starting at zero. The table
+/// serializes as the list of interned metadata, in id order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetadataTable {
+    entries: Vec<TracingMetadata>,
+    ids: HashMap<TracingMetadata, u32>,
+}
+
+impl MetadataTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the id of `metadata`, adding it to the table if necessary.
+    pub fn intern(&mut self, metadata: TracingMetadata) -> u32 {
+        if let Some(&id) = self.ids.get(&metadata) {
+            return id;
+        }
+        let id = self.entries.len() as u32;
+        self.entries.push(metadata.clone());
+        self.ids.insert(metadata, id);
+        id
+    }
+
+    /// Returns the metadata with the given id.
+    pub fn get(&self, id: u32) -> Option<&TracingMetadata> {
+        self.entries.get(id as usize)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Replaces the event's metadata with its id in this table.
+    pub fn compact(&mut self, event: TracingEvent) -> CompactEvent {
+        CompactEvent {
+            metadata_id: self.intern(event.metadata),
+            message: event.message,
+            fields: event.fields,
+            timestamp: event.timestamp,
+            monotonic_nanos: event.monotonic_nanos,
+            seq: event.seq,
+            thread_id: event.thread_id,
+            thread_name: event.thread_name,
+            span_context: event.span_context,
+            current_span_id: event.current_span_id,
+        }
+    }
+
+    /// Restores the full event from a [`CompactEvent`] produced by this table.
+    pub fn rehydrate(&self, event: CompactEvent) -> Result<TracingEvent, UnknownMetadataId> {
+        let metadata = self
+            .get(event.metadata_id)
+            .ok_or(UnknownMetadataId(event.metadata_id))?
+            .clone();
+
+        Ok(TracingEvent {
+            metadata,
+            message: event.message,
+            fields: event.fields,
+            timestamp: event.timestamp,
+            monotonic_nanos: event.monotonic_nanos,
+            seq: event.seq,
+            thread_id: event.thread_id,
+            thread_name: event.thread_name,
+            span_context: event.span_context,
+            current_span_id: event.current_span_id,
+        })
+    }
+}
+
+impl Serialize for MetadataTable {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.entries.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for MetadataTable {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut table = Self::new();
+        for metadata in Vec::<TracingMetadata>::deserialize(deserializer)? {
+            table.intern(metadata);
+        }
+        Ok(table)
+    }
+}
+
+/// A [`TracingEvent`] whose metadata has been replaced by its id in a
+/// [`MetadataTable`].
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct CompactEvent {
+    pub metadata_id: u32,
+    pub message: Option<String>,
+    pub fields: TracingFields,
+    pub timestamp: Option<u64>,
+    #[serde(default)]
+    pub monotonic_nanos: Option<u64>,
+    #[serde(default)]
+    pub seq: Option<u64>,
+    pub thread_id: Option<String>,
+    pub thread_name: Option<String>,
+    #[serde(default)]
+    pub span_context: Vec<u64>,
+    #[serde(default)]
+    pub current_span_id: Option<u64>,
+}
+
+/// A list of events stored with their metadata written only once.
+///
+/// ```
+/// # use tracing_bridge::{CompactLog, TracingEvent};
+/// # fn example(events: Vec<TracingEvent>) -> Result<(), Box<dyn std::error::Error>> {
+/// let log: CompactLog = events.into_iter().collect();
+/// // ... serialize `log`, and deserialize it elsewhere ...
+/// let events = log.into_events()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct CompactLog {
+    pub metadata: MetadataTable,
+    pub events: Vec<CompactEvent>,
+}
+
+impl CompactLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, event: TracingEvent) {
+        let event = self.metadata.compact(event);
+        self.events.push(event);
+    }
+
+    /// Restores the full events, failing if any refers to metadata missing
+    /// from the table.
+    pub fn into_events(self) -> Result<Vec<TracingEvent>, UnknownMetadataId> {
+        let metadata = self.metadata;
+        self.events
+            .into_iter()
+            .map(|event| metadata.rehydrate(event))
+            .collect()
+    }
+}
+
+impl std::iter::FromIterator<TracingEvent> for CompactLog {
+    fn from_iter<I: IntoIterator<Item = TracingEvent>>(iter: I) -> Self {
+        let mut log = Self::new();
+        for event in iter {
+            log.push(event);
+        }
+        log
+    }
+}
+
+/// The error returned when a [`CompactEvent`] refers to metadata that isn't
+/// in the [`MetadataTable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownMetadataId(pub u32);
+
+impl fmt::Display for UnknownMetadataId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no metadata with id {} in the table", self.0)
+    }
+}
+
+impl std::error::Error for UnknownMetadataId {}
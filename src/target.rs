@@ -0,0 +1,27 @@
+use std::borrow::Cow;
+
+/// Returns a [target rewrite](crate::BridgeLayer::with_target_rewrite) that
+/// keeps only the first `depth` `::`-separated segments of each target, so
+/// that e.g. with a depth of 1 `h2::codec::framed_write` is captured as `h2`.
+///
+/// Targets with `depth` segments or fewer are left as they are, and a depth
+/// of 0 is treated as 1.
+///
+/// ```
+/// use tracing_bridge::{truncate_target_depth, BridgeLayer};
+///
+/// let layer = BridgeLayer::new(|event| println!("{:?}", event))
+///     .with_target_rewrite(truncate_target_depth(2));
+/// ```
+pub fn truncate_target_depth(
+    depth: usize,
+) -> impl Fn(&str) -> Cow<'_, str> + Send + Sync + 'static {
+    move |target| truncate(target, depth.max(1))
+}
+
+fn truncate(target: &str, depth: usize) -> Cow<'_, str> {
+    match target.match_indices("::").nth(depth - 1) {
+        Some((end, _)) => Cow::Borrowed(&target[..end]),
+        None => Cow::Borrowed(target),
+    }
+}
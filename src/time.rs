@@ -0,0 +1,143 @@
+use core::{convert::TryFrom, fmt};
+
+/// Formats a timestamp in nanoseconds since the Unix epoch as an RFC 3339
+/// UTC date and time, e.g. `2024-05-01T12:30:00.123Z`, with `digits`
+/// fractional second digits (at most 9, and none if `digits` is 0).
+pub(crate) struct Rfc3339 {
+    pub nanos: u64,
+    pub digits: u32,
+}
+
+impl fmt::Display for Rfc3339 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+        let secs = self.nanos / NANOS_PER_SEC;
+        let (year, month, day) = civil_from_days(secs / 86_400);
+        let time = secs % 86_400;
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            year,
+            month,
+            day,
+            time / 3600,
+            time % 3600 / 60,
+            time % 60,
+        )?;
+
+        let digits = self.digits.min(9);
+        if digits > 0 {
+            let fraction = self.nanos % NANOS_PER_SEC / 10u64.pow(9 - digits);
+            write!(f, ".{:0width$}", fraction, width = digits as usize)?;
+        }
+        f.write_str("Z")
+    }
+}
+
+/// Parses an RFC 3339 date and time, e.g. `2024-05-01T12:30:00.123Z` or
+/// `2024-05-01T14:30:00+02:00`, into nanoseconds since the Unix epoch.
+/// Digits of the fraction beyond nanoseconds are ignored.
+///
+/// Returns `None` if `s` isn't a date and time in that format, or is before
+/// 1970 or too far in the future to fit.
+pub(crate) fn parse_rfc3339(s: &str) -> Option<u64> {
+    let bytes = s.as_bytes();
+    let number = |start: usize, len: usize| -> Option<u64> {
+        bytes
+            .get(start..start + len)?
+            .iter()
+            .try_fold(0, |number, &digit| {
+                digit
+                    .is_ascii_digit()
+                    .then(|| number * 10 + u64::from(digit - b'0'))
+            })
+    };
+    let is = |i: usize, expected: &[u8]| bytes.get(i).is_some_and(|b| expected.contains(b));
+
+    if !(is(4, b"-") && is(7, b"-") && is(10, b"Tt ") && is(13, b":") && is(16, b":")) {
+        return None;
+    }
+    let (year, month, day) = (number(0, 4)?, number(5, 2)?, number(8, 2)?);
+    let (hour, minute, second) = (number(11, 2)?, number(14, 2)?, number(17, 2)?);
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return None;
+    }
+
+    let mut i = 19;
+    let mut fraction = 0;
+    if is(i, b".") {
+        let digits = bytes[i + 1..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        if digits == 0 {
+            return None;
+        }
+        let kept = digits.min(9);
+        fraction = number(i + 1, kept)? * 10u64.pow(9 - kept as u32);
+        i += 1 + digits;
+    }
+    let offset = match &bytes[i..] {
+        b"Z" | b"z" => 0,
+        [sign @ (b'+' | b'-'), ..] if bytes.len() == i + 6 && is(i + 3, b":") => {
+            let offset = (number(i + 1, 2)? * 3600 + number(i + 4, 2)? * 60) as i64;
+            if *sign == b'-' {
+                -offset
+            } else {
+                offset
+            }
+        }
+        _ => return None,
+    };
+
+    let time = (hour * 3600 + minute * 60 + second) as i64;
+    let secs = days_from_civil(year, month, day)? * 86_400 + time - offset;
+    u64::try_from(secs)
+        .ok()?
+        .checked_mul(1_000_000_000)?
+        .checked_add(fraction)
+}
+
+/// Converts a proleptic Gregorian date from year 1 on into the number of
+/// days since 1970-01-01, the inverse of [`civil_from_days`].
+fn days_from_civil(year: u64, month: u64, day: u64) -> Option<i64> {
+    let year = if month <= 2 {
+        year.checked_sub(1)?
+    } else {
+        year
+    };
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let month_index = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    Some((era * 146_097 + day_of_era) as i64 - 719_468)
+}
+
+/// Converts a number of days since 1970-01-01 into a proleptic Gregorian
+/// `(year, month, day)`, using Howard Hinnant's `civil_from_days` algorithm.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    // Shift the epoch to 0000-03-01, so that leap days fall at the end of
+    // each 400-year era.
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,158 @@
+use core::{convert::TryFrom, fmt};
+
+use serde::{
+    de::{self, DeserializeSeed, Unexpected},
+    Deserializer, Serialize, Serializer,
+};
+
+use crate::time::{parse_rfc3339, Rfc3339};
+
+/// How [`TracingEvent::timestamp`](crate::TracingEvent::timestamp) is
+/// written when events are serialized, and read back when they are
+/// deserialized.
+///
+/// Events serialize their timestamps as nanoseconds since the Unix epoch.
+/// Another format is chosen with an [`EventFormat`](crate::EventFormat),
+/// which sinks and readers take with methods such as
+/// [`WriterSink::with_timestamp_format`](crate::WriterSink::with_timestamp_format)
+/// and
+/// [`JsonLinesReader::with_timestamp_format`](crate::JsonLinesReader::with_timestamp_format).
+///
+/// Human-readable formats such as JSON read back timestamps written in any
+/// format: numbers are read as milliseconds if the format read in is
+/// [`EpochMillis`](Self::EpochMillis) and as nanoseconds otherwise, and
+/// strings as RFC 3339. Other formats only read back timestamps written in
+/// the format read in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TimestampFormat {
+    /// Nanoseconds since the Unix epoch, as a number, e.g.
+    /// `1714566600123000000`.
+    #[default]
+    EpochNanos,
+
+    /// Milliseconds since the Unix epoch, as a number, e.g. `1714566600123`.
+    /// Anything finer than a millisecond is dropped.
+    EpochMillis,
+
+    /// An RFC 3339 UTC date and time with nanoseconds, as a string, e.g.
+    /// `"2024-05-01T12:30:00.123000000Z"`.
+    Rfc3339,
+}
+
+/// Deserializes a timestamp written as nanoseconds, or as RFC 3339 in a
+/// human-readable data format.
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<u64>, D::Error> {
+    TimestampSeed(TimestampFormat::EpochNanos).deserialize(deserializer)
+}
+
+/// A timestamp that serializes in `format`, for serializers written by hand.
+pub(crate) struct Timestamp {
+    pub nanos: Option<u64>,
+    pub format: TimestampFormat,
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let nanos = match self.nanos {
+            Some(nanos) => nanos,
+            None => return serializer.serialize_none(),
+        };
+        match self.format {
+            TimestampFormat::EpochNanos => serializer.serialize_some(&nanos),
+            TimestampFormat::EpochMillis => serializer.serialize_some(&(nanos / 1_000_000)),
+            TimestampFormat::Rfc3339 => serializer.serialize_some(&Rfc3339Str(nanos)),
+        }
+    }
+}
+
+struct Rfc3339Str(u64);
+
+impl Serialize for Rfc3339Str {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&Rfc3339 {
+            nanos: self.0,
+            digits: 9,
+        })
+    }
+}
+
+/// Deserializes a timestamp written in a format, or in any format from a
+/// human-readable data format.
+pub(crate) struct TimestampSeed(pub TimestampFormat);
+
+impl<'de> DeserializeSeed<'de> for TimestampSeed {
+    type Value = Option<u64>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Option<u64>, D::Error> {
+        deserializer.deserialize_option(OptionVisitor(self.0))
+    }
+}
+
+struct OptionVisitor(TimestampFormat);
+
+impl<'de> de::Visitor<'de> for OptionVisitor {
+    type Value = Option<u64>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a timestamp or nothing")
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        let visitor = NanosVisitor(self.0);
+        let nanos = if deserializer.is_human_readable() {
+            deserializer.deserialize_any(visitor)
+        } else if visitor.0 == TimestampFormat::Rfc3339 {
+            deserializer.deserialize_str(visitor)
+        } else {
+            deserializer.deserialize_u64(visitor)
+        };
+        nanos.map(Some)
+    }
+}
+
+/// Reads a timestamp as nanoseconds, taking numbers to be in the unit of its
+/// format.
+struct NanosVisitor(TimestampFormat);
+
+impl de::Visitor<'_> for NanosVisitor {
+    type Value = u64;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            TimestampFormat::EpochMillis => {
+                formatter.write_str("milliseconds since the Unix epoch or an RFC 3339 date")
+            }
+            _ => formatter.write_str("nanoseconds since the Unix epoch or an RFC 3339 date"),
+        }
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<u64, E> {
+        match self.0 {
+            TimestampFormat::EpochMillis => value
+                .checked_mul(1_000_000)
+                .ok_or_else(|| E::invalid_value(Unexpected::Unsigned(value), &self)),
+            _ => Ok(value),
+        }
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<u64, E> {
+        match u64::try_from(value) {
+            Ok(value) => self.visit_u64(value),
+            Err(_) => Err(E::invalid_value(Unexpected::Signed(value), &self)),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<u64, E> {
+        parse_rfc3339(value).ok_or_else(|| E::invalid_value(Unexpected::Str(value), &self))
+    }
+}
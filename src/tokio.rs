@@ -0,0 +1,38 @@
+//! Shipping captured events to async code over a Tokio channel.
+
+use tokio::sync::mpsc::{self, error::TrySendError, Receiver, Sender};
+
+use crate::{BridgeLayer, EventSink, SinkError, TracingEvent};
+
+/// An [`EventSink`] that sends each event down a bounded Tokio channel.
+///
+/// Events are sent with [`Sender::try_send`], so the emitting thread never
+/// waits: blocking inside `tracing` on an async runtime can deadlock it. When
+/// the channel is full the event is dropped with [`SinkError::full`], and so
+/// counted in the layer's [`DropStats`](crate::DropStats) by default. Events
+/// sent after the [`Receiver`] has been dropped are discarded with
+/// [`SinkError::closed`].
+pub struct TokioSink {
+    sender: Sender<TracingEvent>,
+}
+
+impl EventSink for TokioSink {
+    fn emit(&self, event: TracingEvent) -> Result<(), SinkError> {
+        self.sender.try_send(event).map_err(|error| match error {
+            TrySendError::Full(_) => SinkError::full(),
+            TrySendError::Closed(_) => SinkError::closed(),
+        })
+    }
+}
+
+/// Creates a [`BridgeLayer`] that sends events down a Tokio channel holding
+/// at most `capacity` events, along with the receiving end of that channel.
+///
+/// ```
+/// let (layer, mut events) = tracing_bridge::tokio::channel(1024);
+/// let drop_stats = layer.drop_stats();
+/// ```
+pub fn channel(capacity: usize) -> (BridgeLayer<TokioSink>, Receiver<TracingEvent>) {
+    let (sender, receiver) = mpsc::channel(capacity);
+    (BridgeLayer::new(TokioSink { sender }), receiver)
+}
@@ -0,0 +1,84 @@
+//! Counts the allocations made while capturing events, to check that events
+//! without fields allocate nothing for them.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use tracing_bridge::{BridgeLayer, EventSink, TracingEvent};
+use tracing_subscriber::layer::SubscriberExt;
+
+/// Counts the allocations made on each thread, so that tests running in
+/// parallel don't count each other's.
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// Returns the number of allocations `f` makes on the current thread.
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+/// Emits each event twice with `sink` installed, and counts the allocations
+/// made the second time, once anything allocated on first use is.
+fn count_emitting<K: EventSink>(sink: K, emit: &[fn()]) -> Vec<usize> {
+    let subscriber = tracing_subscriber::registry().with(BridgeLayer::new(sink));
+    tracing::subscriber::with_default(subscriber, || {
+        emit.iter()
+            .map(|emit| {
+                emit();
+                allocations(emit)
+            })
+            .collect()
+    })
+}
+
+#[test]
+fn allocates_nothing_for_events_without_fields() {
+    // Ignores the event, which was allocated before the sink gets it.
+    let sink = |event: TracingEvent| drop(event);
+    let counts = count_emitting(
+        sink,
+        &[
+            || tracing::info!(rows = tracing::field::Empty, "started"),
+            || tracing::info!(rows = 3, "started"),
+        ],
+    );
+
+    // The second event has the same metadata, and only allocates the vector
+    // of fields and the field's name on top of the first.
+    assert_eq!(counts[1] - counts[0], 2);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn allocates_nothing_when_serializing_borrowed_events() {
+    let sink = tracing_bridge::JsonLinesSink::new(std::io::sink());
+    let counts = count_emitting(
+        sink,
+        &[
+            || tracing::info!("started"),
+            || tracing::info!(rows = 3, path = "/orders", "handled request"),
+        ],
+    );
+    assert_eq!(counts, [0, 0]);
+}
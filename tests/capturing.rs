@@ -0,0 +1,45 @@
+//! Uses a capturing layer the way an application's own tests would: run the
+//! code under test with the layer installed, then assert on what it logged.
+
+use tracing_bridge::{BridgeLayer, FieldValue, TracingLevel};
+use tracing_subscriber::layer::SubscriberExt;
+
+/// Stands in for application code that logs as it works.
+fn transfer(amount: u64, balance: u64) -> Result<u64, &'static str> {
+    tracing::info!(amount, "starting transfer");
+    if amount > balance {
+        tracing::error!(amount, balance, "insufficient funds");
+        return Err("insufficient funds");
+    }
+    Ok(balance - amount)
+}
+
+#[test]
+fn asserts_on_the_events_the_code_under_test_emits() {
+    let (layer, captured) = BridgeLayer::capturing();
+    let subscriber = tracing_subscriber::registry().with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        assert_eq!(transfer(30, 20), Err("insufficient funds"));
+    });
+
+    let events = captured.events();
+    let levels: Vec<_> = events.iter().map(|event| event.metadata.level).collect();
+    assert_eq!(levels, [TracingLevel::Info, TracingLevel::Error]);
+    assert_eq!(events[1].message.as_deref(), Some("insufficient funds"));
+    assert_eq!(events[1].fields.get("balance"), Some(&FieldValue::U64(20)));
+}
+
+#[test]
+fn drains_events_between_steps() {
+    let (layer, captured) = BridgeLayer::capturing();
+    let subscriber = tracing_subscriber::registry().with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        transfer(10, 20).unwrap();
+        assert_eq!(captured.drain().len(), 1);
+
+        transfer(30, 20).unwrap_err();
+        assert_eq!(captured.drain().len(), 2);
+    });
+
+    assert!(captured.events().is_empty());
+}
@@ -0,0 +1,132 @@
+//! Deserializes events written by older and newer versions of the crate, and
+//! reads current events the way an older consumer would.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde_json::json;
+use tracing_bridge::{FieldValue, TracingCallsiteKind, TracingEvent, TracingLevel};
+
+/// Metadata as the first versions of the crate wrote it.
+fn historical_metadata() -> serde_json::Value {
+    json!({
+        "name": "event src/main.rs:12",
+        "target": "my_app",
+        "level": "Info",
+        "module_path": "my_app",
+        "file": "src/main.rs",
+        "line": 12,
+        "kind": "Event",
+    })
+}
+
+#[test]
+fn reads_events_with_only_metadata_and_fields() {
+    let event: TracingEvent = serde_json::from_value(json!({
+        "metadata": historical_metadata(),
+        "fields": { "rows": { "U64": 3 } },
+    }))
+    .unwrap();
+
+    assert_eq!(event.metadata.target, "my_app");
+    assert_eq!(event.metadata.level, TracingLevel::Info);
+    assert_eq!(event.metadata.kind, TracingCallsiteKind::Event);
+    assert!(event.metadata.declared_fields.is_empty());
+    assert_eq!(event.fields.get("rows"), Some(&FieldValue::U64(3)));
+    assert_eq!(event.message, None);
+    assert_eq!(event.timestamp, None);
+    assert_eq!(event.monotonic_nanos, None);
+    assert_eq!(event.seq, None);
+    assert_eq!(event.thread_id, None);
+    assert_eq!(event.thread_name, None);
+    assert!(event.span_context.is_empty());
+    assert_eq!(event.current_span_id, None);
+}
+
+#[test]
+fn ignores_properties_added_by_newer_versions() {
+    let mut metadata = historical_metadata();
+    metadata["color"] = json!("green");
+    let event: TracingEvent = serde_json::from_value(json!({
+        "metadata": metadata,
+        "message": "ready",
+        "fields": {},
+        "seq": 7,
+        "host": { "name": "web-1" },
+    }))
+    .unwrap();
+
+    assert_eq!(event.message.as_deref(), Some("ready"));
+    assert_eq!(event.seq, Some(7));
+}
+
+/// An event as a consumer that only knows `metadata` and `fields` reads it.
+#[derive(Deserialize)]
+struct HistoricalEvent {
+    metadata: HistoricalMetadata,
+    fields: HashMap<String, serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct HistoricalMetadata {
+    name: String,
+    target: String,
+    level: String,
+    module_path: Option<String>,
+    file: Option<String>,
+    line: Option<u32>,
+    kind: String,
+}
+
+#[test]
+fn writes_events_older_consumers_can_read() {
+    let event = TracingEvent::builder()
+        .name("event src/main.rs:12")
+        .target("my_app")
+        .message("ready")
+        .field("rows", 3_u64)
+        .timestamp(1_700_000_000_000_000_000)
+        .seq(7)
+        .build();
+
+    let read: HistoricalEvent =
+        serde_json::from_str(&serde_json::to_string(&event).unwrap()).unwrap();
+    assert_eq!(read.metadata.name, "event src/main.rs:12");
+    assert_eq!(read.metadata.target, "my_app");
+    assert_eq!(read.metadata.level, "Info");
+    assert_eq!(read.metadata.module_path, event.metadata.module_path);
+    assert_eq!(read.metadata.file, event.metadata.file);
+    assert_eq!(read.metadata.line, event.metadata.line);
+    assert_eq!(read.metadata.kind, "Event");
+    assert_eq!(
+        read.fields,
+        HashMap::from([("rows".to_owned(), json!({ "U64": 3 }))])
+    );
+}
+
+/// Files captured on one platform read the same on any other: separators
+/// are `/` and bytes that aren't UTF-8 are replaced.
+#[cfg(unix)]
+#[test]
+fn round_trips_files_with_backslashes_and_invalid_utf8() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    let event = TracingEvent::builder()
+        .file(OsStr::from_bytes(b"src\\net\\caf\xe9.rs"))
+        .build();
+    assert_eq!(
+        event.metadata.file.as_deref(),
+        Some("src/net/caf\u{fffd}.rs")
+    );
+
+    let json = serde_json::to_string(&event).unwrap();
+    let read: TracingEvent = serde_json::from_str(&json).unwrap();
+    assert_eq!(read, event);
+    assert_eq!(read.metadata.file, event.metadata.file);
+
+    #[cfg(feature = "bincode")]
+    {
+        let bytes = event.to_bincode().unwrap();
+        assert_eq!(TracingEvent::from_bincode(&bytes).unwrap(), event);
+    }
+}
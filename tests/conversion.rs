@@ -0,0 +1,96 @@
+//! Converts callsite kinds, including `enabled!` hints, both directly and
+//! through metadata seen by a real subscriber.
+
+use std::{
+    convert::TryFrom,
+    sync::{Arc, Mutex},
+};
+
+use tracing_bridge::{TracingCallsiteKind, TracingMetadata};
+use tracing_core::{
+    metadata::Kind,
+    span::{Attributes, Id, Record},
+    subscriber::Interest,
+    Event, Metadata, Subscriber,
+};
+
+#[test]
+fn converts_every_kind() {
+    let kinds = [
+        (Kind::EVENT, TracingCallsiteKind::Event),
+        (Kind::SPAN, TracingCallsiteKind::Span),
+        (Kind::HINT, TracingCallsiteKind::Hint),
+    ];
+    for (kind, expected) in kinds {
+        assert_eq!(TracingCallsiteKind::try_from(&kind), Ok(expected));
+        assert_eq!(
+            TracingCallsiteKind::try_from(Kind::from(&expected)),
+            Ok(expected)
+        );
+    }
+}
+
+#[test]
+fn round_trips_events_and_spans_through_callsite_kinds() {
+    for kind in [Kind::EVENT, Kind::SPAN] {
+        let converted = TracingCallsiteKind::try_from(kind.clone()).unwrap();
+        assert_eq!(Kind::from(&converted), kind);
+    }
+}
+
+#[test]
+fn converts_hints_that_are_also_events_or_spans() {
+    assert_eq!(
+        TracingCallsiteKind::try_from(Kind::EVENT.hint()),
+        Ok(TracingCallsiteKind::Event)
+    );
+    assert_eq!(
+        TracingCallsiteKind::try_from(Kind::SPAN.hint()),
+        Ok(TracingCallsiteKind::Span)
+    );
+}
+
+/// Converts the metadata of every callsite it is asked about.
+#[derive(Default)]
+struct Callsites(Arc<Mutex<Vec<TracingMetadata>>>);
+
+impl Subscriber for Callsites {
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        self.0.lock().unwrap().push(metadata.into());
+        Interest::always()
+    }
+
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, _span: &Attributes<'_>) -> Id {
+        Id::from_u64(1)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event<'_>) {}
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
+
+#[test]
+fn converts_the_metadata_of_enabled_hints() {
+    let subscriber = Callsites::default();
+    let callsites = subscriber.0.clone();
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::enabled!(target: "hints", tracing::Level::INFO);
+    });
+
+    let callsites = callsites.lock().unwrap();
+    let hint = callsites
+        .iter()
+        .find(|metadata| metadata.target == "hints")
+        .unwrap();
+    assert_eq!(hint.kind, TracingCallsiteKind::Hint);
+}
@@ -0,0 +1,781 @@
+//! Emits events through real `tracing` macros and checks what the bridge
+//! captures, to cover the conversion path that hand-built events skip.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{mpsc::Receiver, Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tracing::level_filters::LevelFilter;
+use tracing_bridge::{
+    BridgeLayer, EventSink, FieldValue, FilterConfig, SinkError, SpanEvent, TracingCallsiteKind,
+    TracingEvent, TracingLevel, TracingSpan,
+};
+use tracing_subscriber::layer::SubscriberExt;
+
+/// Runs `f` with a bridge installed as the default subscriber, and returns
+/// every event it captured.
+fn capture(f: impl FnOnce()) -> Vec<TracingEvent> {
+    let (layer, events) = tracing_bridge::channel();
+    let subscriber = tracing_subscriber::registry().with(layer);
+    tracing::subscriber::with_default(subscriber, f);
+    drain(events)
+}
+
+fn drain(events: Receiver<TracingEvent>) -> Vec<TracingEvent> {
+    events.try_iter().collect()
+}
+
+#[derive(Debug)]
+#[allow(dead_code)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug)]
+struct Outer(Inner);
+
+#[derive(Debug)]
+struct Inner;
+
+impl fmt::Display for Outer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("request failed")
+    }
+}
+
+impl fmt::Display for Inner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("connection reset")
+    }
+}
+
+impl std::error::Error for Outer {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+impl std::error::Error for Inner {}
+
+#[test]
+fn captures_metadata_and_levels() {
+    let events = capture(|| {
+        tracing::info!("started");
+        tracing::warn!(target: "my_app::db", "connection slow");
+    });
+
+    assert_eq!(events.len(), 2);
+
+    let started = &events[0];
+    assert_eq!(started.metadata.level, TracingLevel::Info);
+    assert_eq!(started.metadata.target, module_path!());
+    assert_eq!(
+        started.metadata.module_path.as_deref(),
+        Some(module_path!())
+    );
+    assert_eq!(started.metadata.file.as_deref(), Some("tests/dispatch.rs"));
+    assert!(started.metadata.line.is_some());
+    assert_eq!(started.metadata.kind, TracingCallsiteKind::Event);
+    assert_eq!(started.metadata.declared_fields, ["message"]);
+    assert_eq!(started.message.as_deref(), Some("started"));
+    assert!(started.fields.is_empty());
+    assert!(started.timestamp.is_some());
+    assert!(started.thread_id.is_some());
+
+    let slow = &events[1];
+    assert_eq!(slow.metadata.level, TracingLevel::Warn);
+    assert_eq!(slow.metadata.target, "my_app::db");
+    assert_eq!(slow.message.as_deref(), Some("connection slow"));
+    assert!(slow.seq > started.seq);
+}
+
+#[test]
+fn captures_typed_field_values() {
+    let events = capture(|| {
+        let error = Outer(Inner);
+        tracing::info!(
+            signed = -3i64,
+            unsigned = 7u64,
+            float = 1.5,
+            flag = true,
+            text = "hello",
+            point = ?Point { x: 1, y: 2 },
+            shown = %"displayed",
+            error = &error as &(dyn std::error::Error + 'static),
+            "value {}",
+            42,
+        );
+    });
+
+    let event = &events[0];
+    let fields = &event.fields;
+    assert_eq!(event.message.as_deref(), Some("value 42"));
+    assert_eq!(fields.get("signed"), Some(&FieldValue::I64(-3)));
+    assert_eq!(fields.get("unsigned"), Some(&FieldValue::U64(7)));
+    assert_eq!(fields.get("float"), Some(&FieldValue::F64(1.5)));
+    assert_eq!(fields.get("flag"), Some(&FieldValue::Bool(true)));
+    assert_eq!(
+        fields.get("text"),
+        Some(&FieldValue::Str("hello".to_owned()))
+    );
+    assert_eq!(
+        fields.get("point"),
+        Some(&FieldValue::Debug("Point { x: 1, y: 2 }".to_owned()))
+    );
+    assert_eq!(
+        fields.get("shown"),
+        Some(&FieldValue::Debug("displayed".to_owned()))
+    );
+    assert_eq!(
+        fields.get("error"),
+        Some(&FieldValue::Error {
+            message: "request failed".to_owned(),
+            causes: vec!["connection reset".to_owned()],
+        })
+    );
+}
+
+#[test]
+fn keeps_every_value_of_repeated_fields() {
+    let events = capture(|| tracing::info!(x = 1, x = 2));
+    assert_eq!(
+        events[0].fields.get_all("x").collect::<Vec<_>>(),
+        [&FieldValue::I64(1), &FieldValue::I64(2)]
+    );
+}
+
+#[test]
+fn captures_messages_with_inline_arguments_resolved() {
+    let events = capture(|| {
+        let count = 3;
+        let path = "/tmp/out";
+        tracing::info!(count, "processed {count} items");
+        tracing::info!(count = count * 2, "wrote {count} items to {path:?}");
+    });
+
+    assert_eq!(events[0].message.as_deref(), Some("processed 3 items"));
+    assert_eq!(events[0].fields.get("count"), Some(&FieldValue::I64(3)));
+    // The message names the variable, not the field of the same name.
+    assert_eq!(
+        events[1].message.as_deref(),
+        Some("wrote 3 items to \"/tmp/out\"")
+    );
+    assert_eq!(events[1].fields.get("count"), Some(&FieldValue::I64(6)));
+}
+
+#[test]
+fn names_the_kind_of_each_field_value() {
+    let events = capture(|| {
+        let error = Outer(Inner);
+        tracing::info!(
+            signed = -3i64,
+            unsigned = 7u64,
+            float = 1.5,
+            flag = true,
+            text = "hello",
+            point = ?Point { x: 1, y: 2 },
+            error = &error as &(dyn std::error::Error + 'static),
+        );
+    });
+
+    let kinds: Vec<_> = events[0]
+        .fields
+        .iter()
+        .map(|(name, value)| (name, value.kind_str()))
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            ("signed", "i64"),
+            ("unsigned", "u64"),
+            ("float", "f64"),
+            ("flag", "bool"),
+            ("text", "str"),
+            ("point", "debug"),
+            ("error", "error"),
+        ]
+    );
+}
+
+#[test]
+fn escapes_control_chars_when_asked() {
+    let (layer, events) = tracing_bridge::channel();
+    let subscriber = tracing_subscriber::registry().with(layer.with_escaped_control_chars(true));
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::info!(text = "nul\0 esc\x1b[31m tab\t", shown = %"bell\x07", "line\nbreak");
+    });
+
+    let event = &drain(events)[0];
+    assert_eq!(event.message.as_deref(), Some("line\\nbreak"));
+    assert_eq!(
+        event.fields.get("text"),
+        Some(&FieldValue::Str("nul\\0 esc\\u{1b}[31m tab\t".to_owned()))
+    );
+    assert_eq!(
+        event.fields.get("shown"),
+        Some(&FieldValue::Debug("bell\\u{7}".to_owned()))
+    );
+
+    let event = &capture(|| tracing::info!(text = "nul\0"))[0];
+    assert_eq!(
+        event.fields.get("text"),
+        Some(&FieldValue::Str("nul\0".to_owned()))
+    );
+}
+
+#[test]
+fn skips_empty_fields() {
+    let events = capture(|| {
+        tracing::info!(user = tracing::field::Empty, attempt = 1, "login");
+    });
+
+    let event = &events[0];
+    assert_eq!(event.fields.get("user"), None);
+    assert_eq!(event.fields.get("attempt"), Some(&FieldValue::I64(1)));
+    assert_eq!(
+        event.metadata.declared_fields,
+        ["message", "user", "attempt"]
+    );
+}
+
+#[test]
+fn records_span_context() {
+    let mut ids = Vec::new();
+    let events = capture(|| {
+        let outer = tracing::info_span!("request", id = 5);
+        let _outer = outer.enter();
+        let inner = tracing::debug_span!("query");
+        let _inner = inner.enter();
+        ids.extend(
+            [outer.id(), inner.id()]
+                .iter()
+                .flatten()
+                .map(|id| id.into_u64()),
+        );
+        tracing::info!("in span");
+    });
+
+    assert_eq!(events[0].span_context, ids);
+}
+
+#[test]
+fn records_the_explicit_parent_instead_of_the_current_span() {
+    let mut ids = Vec::new();
+    let events = capture(|| {
+        let request = tracing::info_span!("request");
+        let query = request.in_scope(|| tracing::info_span!("query"));
+        let _unrelated = tracing::info_span!("unrelated").entered();
+        ids.extend(
+            [request.id(), query.id()]
+                .iter()
+                .flatten()
+                .map(|id| id.into_u64()),
+        );
+        tracing::info!(parent: &query, "explicit");
+        tracing::info!(parent: None, "root");
+    });
+
+    assert_eq!(events[0].span_context, ids);
+    assert!(events[1].span_context.is_empty());
+}
+
+#[test]
+fn records_the_current_span_for_contextual_events() {
+    let mut ids = Vec::new();
+    let events = capture(|| {
+        let request = tracing::info_span!("request");
+        let _request = request.enter();
+        let detached = tracing::info_span!(parent: None, "detached");
+        let _detached = detached.enter();
+        ids.extend(detached.id().map(|id| id.into_u64()));
+        tracing::info!("contextual");
+    });
+
+    assert_eq!(events[0].span_context, ids);
+}
+
+#[test]
+fn records_the_current_span_id() {
+    let mut ids = Vec::new();
+    let events = capture(|| {
+        tracing::info!("outside");
+        let request = tracing::info_span!("request");
+        let _request = request.enter();
+        let detached = tracing::info_span!(parent: None, "detached");
+        ids.extend(
+            [request.id(), detached.id()]
+                .iter()
+                .flatten()
+                .map(|id| id.into_u64()),
+        );
+        tracing::info!("inside");
+        tracing::info!(parent: &detached, "elsewhere");
+    });
+
+    assert_eq!(events[0].current_span_id, None);
+    assert_eq!(events[1].current_span_id, Some(ids[0]));
+    // An explicit parent doesn't change which span is current.
+    assert_eq!(events[2].span_context, [ids[1]]);
+    assert_eq!(events[2].current_span_id, Some(ids[0]));
+}
+
+#[test]
+fn records_monotonic_timestamps_from_the_baseline() {
+    let (layer, captured) = BridgeLayer::capturing();
+    let baseline = layer.monotonic_baseline();
+    let subscriber = tracing_subscriber::registry().with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::info!("first");
+        std::thread::sleep(Duration::from_millis(2));
+        tracing::info!("second");
+    });
+
+    let events = captured.events();
+    let first = events[0].monotonic_nanos.unwrap();
+    let second = events[1].monotonic_nanos.unwrap();
+    assert!(second - first >= 2_000_000);
+    assert!(baseline.to_instant(second) <= Instant::now());
+    assert!(baseline.to_system_time(first) >= baseline.system_time());
+}
+
+#[test]
+fn rewrites_targets() {
+    let (layer, captured) = BridgeLayer::capturing();
+    let layer = layer.with_target_rewrite(tracing_bridge::truncate_target_depth(1));
+    let subscriber = tracing_subscriber::registry().with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::info!(target: "h2::codec::framed_write", "wrote frame");
+        tracing::info!(target: "app", "ready");
+    });
+
+    let targets: Vec<_> = captured
+        .events()
+        .into_iter()
+        .map(|event| event.metadata.target)
+        .collect();
+    assert_eq!(targets, ["h2", "app"]);
+}
+
+#[test]
+fn marks_values_longer_than_the_limit_as_truncated() {
+    let (layer, captured) = BridgeLayer::capturing();
+    let subscriber = tracing_subscriber::registry().with(layer.with_max_field_len(Some(5)));
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::info!(at_limit = "abcde", over_limit = "abcdef", debug = ?"abcd");
+    });
+
+    let fields = &captured.events()[0].fields;
+    assert_eq!(
+        fields.get("at_limit"),
+        Some(&FieldValue::Str("abcde".to_owned()))
+    );
+    assert_eq!(
+        fields.get("over_limit"),
+        Some(&FieldValue::Truncated {
+            value: "abcde".to_owned(),
+            original_len: 6,
+        })
+    );
+    // Quoted, the `Debug` output is one byte over.
+    assert_eq!(
+        fields.get("debug"),
+        Some(&FieldValue::Truncated {
+            value: "\"abcd".to_owned(),
+            original_len: 6,
+        })
+    );
+}
+
+#[test]
+fn respects_the_level_filter() {
+    let (layer, events) = tracing_bridge::channel();
+    let subscriber = tracing_subscriber::registry().with(layer.with_min_level(TracingLevel::Warn));
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::info!("dropped");
+        tracing::error!("kept");
+    });
+
+    let events = drain(events);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].message.as_deref(), Some("kept"));
+}
+
+#[test]
+fn takes_tracing_level_filters() {
+    let (layer, events) = tracing_bridge::channel();
+    let subscriber =
+        tracing_subscriber::registry().with(layer.with_level_filter(LevelFilter::INFO));
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::debug!("dropped");
+        tracing::info!("kept");
+    });
+    let messages: Vec<_> = drain(events)
+        .into_iter()
+        .filter_map(|event| event.message)
+        .collect();
+    assert_eq!(messages, ["kept"]);
+
+    let (layer, events) = tracing_bridge::channel();
+    let layer = layer
+        .with_level_filter(LevelFilter::OFF)
+        .with_global_filtering(true);
+    let subscriber = tracing_subscriber::registry().with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        assert!(!tracing::enabled!(tracing::Level::ERROR));
+        let _span = tracing::error_span!("request").entered();
+        tracing::error!("dropped");
+    });
+    assert!(drain(events).is_empty());
+
+    let error = TracingEvent::builder().level(TracingLevel::Error).build();
+    assert!(FilterConfig::from(LevelFilter::ERROR).matches(&error.metadata));
+    assert!(!FilterConfig::from(LevelFilter::OFF).matches(&error.metadata));
+}
+
+#[test]
+fn turns_the_layer_off_without_global_filtering() {
+    let (layer, events) = tracing_bridge::channel();
+    let layer = layer.with_level_filter(LevelFilter::OFF);
+    let handle = layer.filter_handle();
+    let subscriber = tracing_subscriber::registry().with(layer);
+    assert_eq!(
+        tracing::Subscriber::max_level_hint(&subscriber),
+        Some(LevelFilter::OFF)
+    );
+    tracing::subscriber::with_default(subscriber, || {
+        assert!(!tracing::enabled!(tracing::Level::ERROR));
+        tracing::error!("dropped");
+
+        handle.update(|config| config.set_level_filter(LevelFilter::INFO));
+        assert!(tracing::enabled!(tracing::Level::ERROR));
+        tracing::error!("kept");
+    });
+    let messages: Vec<_> = drain(events)
+        .into_iter()
+        .filter_map(|event| event.message)
+        .collect();
+    assert_eq!(messages, ["kept"]);
+}
+
+#[test]
+fn hints_the_minimum_level_when_filtering_globally() {
+    let (layer, _events) = tracing_bridge::channel();
+    let layer = layer
+        .with_min_level(TracingLevel::Warn)
+        .with_global_filtering(true);
+    let subscriber = tracing_subscriber::registry().with(layer);
+    assert_eq!(
+        tracing::Subscriber::max_level_hint(&subscriber),
+        Some(LevelFilter::WARN)
+    );
+
+    // Other layers may want what the bridge rejects, so there is no hint
+    // unless the bridge filters for the whole subscriber.
+    let (layer, _events) = tracing_bridge::channel();
+    let subscriber = tracing_subscriber::registry().with(layer.with_min_level(TracingLevel::Warn));
+    assert_eq!(tracing::Subscriber::max_level_hint(&subscriber), None);
+}
+
+#[test]
+fn counts_events_by_level_and_target() {
+    let (layer, _events) = tracing_bridge::channel();
+    let layer = layer.with_metrics().with_min_level(TracingLevel::Info);
+    let metrics = layer.metrics().unwrap();
+    let subscriber = tracing_subscriber::registry().with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::error!(target: "db", "first");
+        tracing::error!(target: "db", "second");
+        tracing::info!(target: "db", "connected");
+        tracing::error!(target: "http", "timeout");
+        tracing::debug!(target: "http", "filtered");
+    });
+
+    let expected: HashMap<_, _> = vec![
+        ((TracingLevel::Error, "db".to_owned()), 2),
+        ((TracingLevel::Info, "db".to_owned()), 1),
+        ((TracingLevel::Error, "http".to_owned()), 1),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(metrics.snapshot(), expected);
+
+    let (layer, _events) = tracing_bridge::channel();
+    assert!(layer.metrics().is_none());
+    assert!(layer.metrics_snapshot().is_empty());
+}
+
+#[test]
+fn inherits_span_fields() {
+    let (layer, events) = tracing_bridge::channel();
+    let subscriber = tracing_subscriber::registry().with(layer.with_inherited_fields(true));
+    tracing::subscriber::with_default(subscriber, || {
+        let request = tracing::info_span!("request", id = 5, user = "alice");
+        let _request = request.enter();
+        let query = tracing::info_span!("query", table = "users");
+        let _query = query.enter();
+        let nested = tracing::info_span!("request", id = 6);
+        let _nested = nested.enter();
+        tracing::info!(rows = 3, "query.table" = "orders", "done");
+    });
+
+    let fields: Vec<_> = drain(events)[0]
+        .fields
+        .iter()
+        .map(|(name, value)| (name.to_owned(), value.to_string()))
+        .collect();
+    let expected = [
+        ("rows", "3"),
+        ("query.table", "\"orders\""),
+        ("request.user", "\"alice\""),
+        ("request.id", "6"),
+    ];
+    let expected: Vec<_> = expected
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    assert_eq!(fields, expected);
+}
+
+#[test]
+fn adds_global_fields_with_the_lowest_precedence() {
+    let global = HashMap::from([
+        ("service.name".to_owned(), FieldValue::from("checkout")),
+        ("region".to_owned(), FieldValue::from("global")),
+        ("request.id".to_owned(), FieldValue::from(0u64)),
+    ]);
+    let (layer, events) = tracing_bridge::channel();
+    let layer = layer.with_inherited_fields(true).with_global_fields(global);
+    let subscriber = tracing_subscriber::registry().with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        let request = tracing::info_span!("request", id = 5);
+        let _request = request.enter();
+        tracing::info!(region = "eu", "done");
+    });
+
+    let fields: Vec<_> = drain(events)[0]
+        .fields
+        .iter()
+        .map(|(name, value)| (name.to_owned(), value.to_string()))
+        .collect();
+    let expected = [
+        ("region", "\"eu\""),
+        ("request.id", "5"),
+        ("service.name", "\"checkout\""),
+    ];
+    let expected: Vec<_> = expected
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect();
+    assert_eq!(fields, expected);
+}
+
+/// A trace id kept in span extensions, as `tracing-opentelemetry` keeps its
+/// span context.
+struct TraceId(u64);
+
+/// Gives every root span a new trace id.
+struct Tracer;
+
+impl<S> tracing_subscriber::Layer<S> for Tracer
+where
+    S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+{
+    fn on_new_span(
+        &self,
+        _attrs: &tracing::span::Attributes<'_>,
+        id: &tracing::span::Id,
+        ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        let span = ctx.span(id).unwrap();
+        if span.parent().is_none() {
+            span.extensions_mut().insert(TraceId(id.into_u64() * 100));
+        }
+    }
+}
+
+#[test]
+fn extracts_fields_from_span_extensions() {
+    let (layer, events) = tracing_bridge::channel();
+    let layer = layer.with_span_extension_extractor(|_metadata, extensions| {
+        let TraceId(id) = extensions.get::<TraceId>()?;
+        Some(("trace_id".to_owned(), FieldValue::from(*id)))
+    });
+    let subscriber = tracing_subscriber::registry().with(Tracer).with(layer);
+    let mut root_id = 0;
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::info!("outside");
+        let request = tracing::info_span!("request");
+        root_id = request.id().unwrap().into_u64();
+        let _request = request.enter();
+        let _query = tracing::info_span!("query").entered();
+        tracing::info!("inside");
+        tracing::info!(trace_id = "own", "overridden");
+    });
+
+    let events = drain(events);
+    assert_eq!(events[0].fields.get("trace_id"), None);
+    assert_eq!(
+        events[1].fields.get("trace_id"),
+        Some(&FieldValue::U64(root_id * 100))
+    );
+    assert_eq!(
+        events[2].fields.get_all("trace_id").collect::<Vec<_>>(),
+        [&FieldValue::from("own")]
+    );
+}
+
+/// Collects the events and created and closed spans a bridge sees.
+#[derive(Clone, Default)]
+struct Spans {
+    spans: Arc<Mutex<Vec<TracingSpan>>>,
+    closed: Arc<Mutex<Vec<TracingSpan>>>,
+    events: Arc<Mutex<Vec<TracingEvent>>>,
+}
+
+impl Spans {
+    fn capture(f: impl FnOnce()) -> Self {
+        let sink = Self::default();
+        let subscriber = tracing_subscriber::registry().with(BridgeLayer::new(sink.clone()));
+        tracing::subscriber::with_default(subscriber, f);
+        sink
+    }
+}
+
+impl EventSink for Spans {
+    fn emit(&self, event: TracingEvent) -> Result<(), SinkError> {
+        self.events.lock().unwrap().push(event);
+        Ok(())
+    }
+
+    fn emit_span(&self, event: SpanEvent) -> Result<(), SinkError> {
+        match event {
+            SpanEvent::New(span) => self.spans.lock().unwrap().push(span),
+            SpanEvent::Close(span) => self.closed.lock().unwrap().push(span),
+            SpanEvent::Enter(_) | SpanEvent::Exit(_) => {}
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn updates_span_fields_recorded_after_creation() {
+    let captured = Spans::capture(|| {
+        let span = tracing::info_span!("request", status = tracing::field::Empty);
+        span.record("status", 404_u64);
+        span.record("status", 200_u64);
+    });
+
+    let spans = captured.spans.lock().unwrap();
+    assert_eq!(spans[0].fields.get("status"), None);
+    let closed = captured.closed.lock().unwrap();
+    assert_eq!(
+        closed[0].fields.get_all("status").collect::<Vec<_>>(),
+        [&FieldValue::U64(200)]
+    );
+}
+
+#[test]
+fn keeps_fields_recorded_later_in_declaration_order() {
+    let captured = Spans::capture(|| {
+        let span = tracing::info_span!(
+            "request",
+            method = "GET",
+            status = tracing::field::Empty,
+            path = "/users",
+        );
+        span.record("status", 200_u64);
+    });
+
+    let closed = captured.closed.lock().unwrap();
+    let names: Vec<_> = closed[0].fields.iter().map(|(name, _)| name).collect();
+    assert_eq!(names, ["method", "status", "path"]);
+    assert_eq!(closed[0].fields.get("status"), Some(&FieldValue::U64(200)));
+}
+
+#[tracing::instrument]
+fn load_user(id: u64) {}
+
+#[test]
+fn guesses_which_spans_are_instrumented() {
+    let captured = Spans::capture(|| {
+        load_user(7);
+        let _span = tracing::info_span!("handling request");
+    });
+
+    let spans = captured.spans.lock().unwrap();
+    assert_eq!(spans[0].metadata.name, "load_user");
+    assert!(spans[0].metadata.looks_instrumented());
+    assert!(!spans[1].metadata.looks_instrumented());
+}
+
+#[test]
+fn joins_events_to_their_spans() {
+    let captured = Spans::capture(|| {
+        let outer = tracing::info_span!("request");
+        let _outer = outer.enter();
+        tracing::info!("in outer");
+        let inner = tracing::info_span!("query");
+        let _inner = inner.enter();
+        tracing::info!("in inner");
+    });
+
+    let spans = captured.spans.lock().unwrap();
+    let events = captured.events.lock().unwrap();
+    let (outer, inner) = (&spans[0], &spans[1]);
+    assert_eq!(inner.parent_id, Some(outer.id));
+    assert_eq!(events[0].span_context, [outer.id]);
+    assert_eq!(events[1].span_context, [outer.id, inner.id]);
+}
+
+#[test]
+fn reports_spans_with_generated_ids() {
+    let first = 7 << 32;
+    let captured = Spans::default();
+    let layer = BridgeLayer::new(captured.clone())
+        .with_id_generator(tracing_bridge::SequentialIds::starting_at(first));
+    let subscriber = tracing_subscriber::registry().with(layer);
+    let mut current = None;
+    tracing::subscriber::with_default(subscriber, || {
+        let outer = tracing::info_span!("request");
+        let _outer = outer.enter();
+        let inner = tracing::info_span!("query");
+        let _inner = inner.enter();
+        current = inner.id().map(|id| id.into_u64());
+        tracing::info!("in inner");
+    });
+
+    let spans = captured.spans.lock().unwrap();
+    let ids: Vec<_> = spans.iter().map(|span| span.id).collect();
+    assert_eq!(ids, [first, first + 1]);
+    assert_eq!(spans[1].parent_id, Some(first));
+
+    let events = captured.events.lock().unwrap();
+    assert_eq!(events[0].span_context, [first, first + 1]);
+    assert_eq!(events[0].current_span_id, current);
+    assert_ne!(current, Some(first + 1));
+
+    let closed: Vec<_> = captured
+        .closed
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|span| span.id)
+        .collect();
+    assert_eq!(closed, [first + 1, first]);
+}
+
+#[test]
+fn gives_spans_ids_from_the_subscribers_generator() {
+    let subscriber = tracing_bridge::BridgeSubscriber::new(|_: TracingEvent| {})
+        .with_id_generator(tracing_bridge::SequentialIds::starting_at(42));
+    tracing::subscriber::with_default(subscriber, || {
+        let ids: Vec<_> = (0..2)
+            .map(|_| tracing::info_span!("request").id().unwrap().into_u64())
+            .collect();
+        assert_eq!(ids, [42, 43]);
+    });
+}
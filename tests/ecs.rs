@@ -0,0 +1,25 @@
+//! Checks the labels events convert to in the Elastic Common Schema.
+#![cfg(feature = "ecs")]
+
+use serde_json::json;
+use tracing_bridge::TracingEvent;
+
+#[test]
+fn keeps_the_first_value_of_repeated_labels() {
+    let event = TracingEvent::builder()
+        .field("peer", "10.0.0.1")
+        .field("retries", 3_u64)
+        .push_field("peer", "10.0.0.2")
+        .field("db.pool", "main")
+        .field("db_pool", "replica")
+        .build();
+
+    assert_eq!(
+        event.to_ecs_value()["labels"],
+        json!({
+            "peer": "10.0.0.1",
+            "retries": "3",
+            "db_pool": "main",
+        })
+    );
+}
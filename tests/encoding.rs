@@ -0,0 +1,82 @@
+//! Encodes events holding every kind of field value in each binary format
+//! and checks they decode to the same event.
+#![cfg(any(feature = "bincode", feature = "msgpack"))]
+
+use tracing_bridge::{FieldValue, TracingEvent};
+
+/// An event with a field for each variant of [`FieldValue`], and every
+/// optional property set.
+fn every_variant() -> TracingEvent {
+    #[allow(unused_mut)]
+    let mut builder = TracingEvent::builder()
+        .message("request failed")
+        .field("signed", -3_i64)
+        .field("unsigned", u64::MAX)
+        .field("float", 1.5)
+        .field("flag", true)
+        .field("text", "hello")
+        .field("debug", FieldValue::Debug("Point { x: 1 }".to_owned()))
+        .field(
+            "error",
+            FieldValue::Error {
+                message: "request failed".to_owned(),
+                causes: vec!["connection reset".to_owned(), "timed out".to_owned()],
+            },
+        )
+        .field(
+            "body",
+            FieldValue::Truncated {
+                value: "abc".to_owned(),
+                original_len: 1024,
+            },
+        )
+        .timestamp(1_700_000_000_123_456_789)
+        .monotonic_nanos(42)
+        .seq(7)
+        .thread("ThreadId(2)", Some("worker".to_owned()))
+        .span_context(vec![1, 3])
+        .current_span_id(3);
+    #[cfg(feature = "valuable")]
+    {
+        builder = builder.field(
+            "nested",
+            FieldValue::Nested(serde_json::json!({"x": 1, "tags": ["a", "b"]})),
+        );
+    }
+    builder.build()
+}
+
+#[cfg(feature = "bincode")]
+#[test]
+fn round_trips_every_field_value_through_bincode() {
+    let event = every_variant();
+    let bytes = event.to_bincode().unwrap();
+    let decoded = TracingEvent::from_bincode(&bytes).unwrap();
+    assert_eq!(decoded, event);
+
+    let names: Vec<_> = decoded.fields.iter().map(|(name, _)| name).collect();
+    let expected: Vec<_> = event.fields.iter().map(|(name, _)| name).collect();
+    assert_eq!(names, expected);
+}
+
+#[cfg(feature = "msgpack")]
+#[test]
+fn round_trips_every_field_value_through_msgpack() {
+    let event = every_variant();
+    let bytes = event.to_msgpack().unwrap();
+    assert_eq!(TracingEvent::from_msgpack(&bytes).unwrap(), event);
+}
+
+#[cfg(feature = "msgpack")]
+#[test]
+fn encodes_msgpack_smaller_than_json() {
+    let event = every_variant();
+    let msgpack = event.to_msgpack().unwrap();
+    let json = serde_json::to_vec(&event).unwrap();
+    assert!(
+        msgpack.len() < json.len(),
+        "msgpack is {} bytes, JSON is {} bytes",
+        msgpack.len(),
+        json.len()
+    );
+}
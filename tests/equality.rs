@@ -0,0 +1,213 @@
+//! Pins down when events and field values compare equal, both with `==`
+//! across serialization and with `semantically_eq`.
+
+use tracing_bridge::{FieldValue, TracingEvent, TracingFields};
+
+fn error() -> FieldValue {
+    FieldValue::Error {
+        message: "request failed".to_owned(),
+        causes: vec!["connection reset".to_owned()],
+    }
+}
+
+fn every_variant() -> Vec<FieldValue> {
+    #[allow(unused_mut)]
+    let mut values = vec![
+        FieldValue::I64(-3),
+        FieldValue::U64(u64::MAX),
+        FieldValue::F64(1.5),
+        FieldValue::F64(-0.0),
+        FieldValue::F64(f64::NAN),
+        FieldValue::Bool(true),
+        FieldValue::Str("hello".to_owned()),
+        FieldValue::Debug("Point { x: 1 }".to_owned()),
+        error(),
+    ];
+    #[cfg(feature = "valuable")]
+    values.push(FieldValue::Nested(
+        serde_json::json!({"x": 1, "tags": ["a"]}),
+    ));
+    values
+}
+
+fn str(value: &str) -> FieldValue {
+    FieldValue::Str(value.to_owned())
+}
+
+fn debug(value: &str) -> FieldValue {
+    FieldValue::Debug(value.to_owned())
+}
+
+#[test]
+fn every_variant_round_trips_through_json() {
+    for value in every_variant() {
+        let json = serde_json::to_string(&value).unwrap();
+        let decoded: FieldValue = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, value, "{}", json);
+    }
+}
+
+#[test]
+fn events_round_trip_through_json() {
+    let mut builder = TracingEvent::builder()
+        .target("my_app")
+        .file("src/main.rs")
+        .line(7)
+        .message("hello")
+        .timestamp(1_700_000_000_000_000_000)
+        .seq(3)
+        .thread("ThreadId(1)", Some("main".to_owned()))
+        .span_context(vec![1, 2]);
+    for (index, value) in every_variant().into_iter().enumerate() {
+        builder = builder.field(format!("field{}", index), value);
+    }
+    let event = builder.build();
+
+    let json = serde_json::to_string(&event).unwrap();
+    let decoded: TracingEvent = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, event);
+    assert!(decoded.semantically_eq(&event));
+}
+
+#[test]
+fn events_serialize_to_identical_json_every_time() {
+    let names = [
+        "zeta", "alpha", "mu", "beta", "omega", "delta", "kappa", "gamma",
+    ];
+    let mut builder = TracingEvent::builder().target("my_app").message("hello");
+    for (name, value) in names.iter().zip(every_variant()) {
+        builder = builder.field(*name, value);
+    }
+    let event = builder.build();
+
+    let json = serde_json::to_string(&event).unwrap();
+    for _ in 0..100 {
+        assert_eq!(serde_json::to_string(&event.clone()).unwrap(), json);
+        let decoded: TracingEvent = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&decoded).unwrap(), json);
+    }
+
+    // Fields are written in the order they were recorded, not sorted.
+    let positions: Vec<_> = names
+        .iter()
+        .map(|name| json.find(&format!("\"{}\":", name)).unwrap())
+        .collect();
+    assert!(
+        positions.windows(2).all(|pair| pair[0] < pair[1]),
+        "{}",
+        json
+    );
+}
+
+#[test]
+fn every_variant_is_semantically_equal_to_itself() {
+    for value in every_variant() {
+        assert!(value.semantically_eq(&value.clone()), "{:?}", value);
+    }
+}
+
+#[test]
+fn integers_equal_their_text_and_other_numbers() {
+    assert!(FieldValue::I64(1).semantically_eq(&debug("1")));
+    assert!(FieldValue::I64(1).semantically_eq(&str("1")));
+    assert!(FieldValue::I64(1).semantically_eq(&FieldValue::U64(1)));
+    assert!(FieldValue::I64(1).semantically_eq(&FieldValue::F64(1.0)));
+    assert!(!FieldValue::I64(-1).semantically_eq(&FieldValue::U64(u64::MAX)));
+    assert!(!FieldValue::I64(1).semantically_eq(&FieldValue::F64(1.5)));
+    assert!(!FieldValue::I64(1).semantically_eq(&FieldValue::Bool(true)));
+
+    assert!(FieldValue::U64(u64::MAX).semantically_eq(&debug("18446744073709551615")));
+    assert!(FieldValue::U64(2).semantically_eq(&FieldValue::F64(2.0)));
+    assert!(!FieldValue::U64(2).semantically_eq(&debug("2.0")));
+}
+
+#[test]
+fn floats_equal_their_text_and_other_numbers() {
+    assert!(FieldValue::F64(1.5).semantically_eq(&debug("1.5")));
+    assert!(FieldValue::F64(1.0).semantically_eq(&debug("1")));
+    assert!(FieldValue::F64(0.0).semantically_eq(&FieldValue::F64(-0.0)));
+    assert!(FieldValue::F64(f64::NAN).semantically_eq(&FieldValue::F64(-f64::NAN)));
+    assert!(!FieldValue::F64(f64::NAN).semantically_eq(&FieldValue::F64(1.0)));
+    assert!(!FieldValue::F64(f64::INFINITY).semantically_eq(&FieldValue::I64(i64::MAX)));
+}
+
+#[test]
+fn bools_equal_their_text_only() {
+    assert!(FieldValue::Bool(true).semantically_eq(&str("true")));
+    assert!(FieldValue::Bool(false).semantically_eq(&debug("false")));
+    assert!(!FieldValue::Bool(true).semantically_eq(&FieldValue::Bool(false)));
+    assert!(!FieldValue::Bool(false).semantically_eq(&FieldValue::U64(0)));
+}
+
+#[test]
+fn strings_and_debug_values_equal_by_text() {
+    assert!(str("hello").semantically_eq(&debug("hello")));
+    assert!(!str("hello").semantically_eq(&debug("\"hello\"")));
+    assert!(!str("hello").semantically_eq(&str("Hello")));
+}
+
+#[test]
+fn errors_equal_their_full_chain_as_text() {
+    assert!(error().semantically_eq(&str("request failed: connection reset")));
+    assert!(!error().semantically_eq(&str("request failed")));
+    assert!(!error().semantically_eq(&FieldValue::Error {
+        message: "request failed".to_owned(),
+        causes: Vec::new(),
+    }));
+}
+
+#[cfg(feature = "valuable")]
+#[test]
+fn nested_values_equal_their_json_text() {
+    let nested = FieldValue::Nested(serde_json::json!({"x": 1}));
+    assert!(nested.semantically_eq(&debug(r#"{"x":1}"#)));
+    assert!(!nested.semantically_eq(&FieldValue::I64(1)));
+}
+
+#[test]
+fn events_compare_fields_semantically() {
+    let typed = TracingEvent::builder()
+        .message("done")
+        .field("count", 3)
+        .field("ok", true)
+        .build();
+    let untyped = TracingEvent::builder()
+        .message("done")
+        .field("count", debug("3"))
+        .field("ok", debug("true"))
+        .build();
+
+    assert_ne!(typed, untyped);
+    assert!(typed.semantically_eq(&untyped));
+    assert!(!typed.semantically_eq(
+        &TracingEvent::builder()
+            .message("done")
+            .field("count", 3)
+            .build()
+    ));
+}
+
+#[test]
+fn repeated_fields_survive_collecting_and_building() {
+    let mut fields = TracingFields::new();
+    fields.push("x", FieldValue::I64(1));
+    fields.push("x", FieldValue::I64(2));
+
+    let collected: TracingFields = fields.clone().into_iter().collect();
+    assert_eq!(collected, fields);
+    assert_eq!(
+        collected.get_all("x").collect::<Vec<_>>(),
+        [&FieldValue::I64(1), &FieldValue::I64(2)]
+    );
+
+    let built = TracingEvent::builder()
+        .push_field("x", 1_i64)
+        .push_field("x", 2_i64)
+        .build();
+    assert_eq!(built.fields, fields);
+    let replaced = TracingEvent::builder()
+        .field("x", 1_i64)
+        .field("x", 2_i64)
+        .build();
+    assert_eq!(replaced.fields.get_all("x").count(), 1);
+}
@@ -0,0 +1,156 @@
+//! Checks that borrowed events serialize exactly like the owned events they
+//! convert to, whatever the layer's field options.
+
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use tracing_bridge::{
+    redact_keys, BridgeLayer, DebugFormat, EventSink, Location, SinkError, TracingEvent,
+    TracingEventRef,
+};
+use tracing_subscriber::layer::SubscriberExt;
+
+/// Serializes every borrowed event it receives both directly and through
+/// `to_owned`, keeping both encodings.
+#[derive(Clone, Default)]
+struct Compare {
+    encoded: Arc<Mutex<Vec<(String, String)>>>,
+}
+
+impl EventSink for Compare {
+    fn emit(&self, _event: TracingEvent) -> Result<(), SinkError> {
+        panic!("the layer should emit borrowed events");
+    }
+
+    fn emit_ref(&self, event: &TracingEventRef<'_>) -> Result<(), SinkError> {
+        let borrowed = serde_json::to_string(event).unwrap();
+        let owned = serde_json::to_string(&event.to_owned()).unwrap();
+        self.encoded.lock().unwrap().push((borrowed, owned));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+#[allow(dead_code)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug)]
+struct Chain(&'static str, Option<Box<Chain>>);
+
+impl fmt::Display for Chain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl std::error::Error for Chain {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.1.as_deref().map(|cause| cause as _)
+    }
+}
+
+fn emit_everything() {
+    let error = Chain(
+        "outer failure",
+        Some(Box::new(Chain("inner cause é and more", None))),
+    );
+    let span = tracing::info_span!("request", id = 1);
+    let _span = span.enter();
+    tracing::info!("plain message");
+    tracing::warn!(
+        signed = -3i64,
+        unsigned = 7u64,
+        float = f64::NAN,
+        infinite = f64::NEG_INFINITY,
+        flag = false,
+        password = "hunter2",
+        text = "a longer string value with ünïcödé",
+        control = "nul\0 esc\x1b[31m tab\t",
+        point = ?Point { x: 1, y: 2 },
+        shown = %"displayed",
+        error = &error as &(dyn std::error::Error + 'static),
+        repeated = 1,
+        repeated = 2,
+        "formatted {} with a ünïcödé message",
+        42,
+    );
+    tracing::error!(message = "explicit message", empty = tracing::field::Empty);
+    tracing::debug!(target: "other", no_message = true);
+}
+
+fn check(configure: impl FnOnce(BridgeLayer<Compare>) -> BridgeLayer<Compare>) {
+    let sink = Compare::default();
+    let encoded = sink.encoded.clone();
+    let subscriber = tracing_subscriber::registry().with(configure(BridgeLayer::new(sink)));
+    tracing::subscriber::with_default(subscriber, emit_everything);
+
+    let encoded = encoded.lock().unwrap();
+    assert_eq!(encoded.len(), 4);
+    for (borrowed, owned) in encoded.iter() {
+        assert_eq!(borrowed, owned);
+        let decoded: TracingEvent = serde_json::from_str(borrowed).unwrap();
+        assert_eq!(&serde_json::to_string(&decoded).unwrap(), owned);
+    }
+}
+
+#[test]
+fn serializes_like_owned_events() {
+    check(|layer| layer);
+}
+
+#[test]
+fn serializes_like_owned_events_when_truncating() {
+    for max_len in 0..40 {
+        check(|layer| layer.with_max_field_len(Some(max_len)));
+    }
+    check(|layer| layer.with_max_field_len(None));
+}
+
+#[test]
+fn serializes_like_owned_events_when_redacting() {
+    check(|layer| layer.with_redactor(redact_keys(["password", "error"])));
+}
+
+#[test]
+fn serializes_like_owned_events_with_pretty_debug() {
+    check(|layer| layer.with_debug_format(DebugFormat::Pretty));
+}
+
+#[test]
+fn serializes_like_owned_events_when_escaping_control_chars() {
+    for max_len in 0..40 {
+        check(|layer| {
+            layer
+                .with_escaped_control_chars(true)
+                .with_max_field_len(Some(max_len))
+        });
+    }
+    check(|layer| {
+        layer
+            .with_escaped_control_chars(true)
+            .with_debug_format(DebugFormat::Pretty)
+    });
+}
+
+#[test]
+fn serializes_like_owned_events_with_a_location_override() {
+    check(|layer| {
+        layer.with_location_override(|metadata| {
+            Some(Location {
+                file: Some("generated\\schema.rs".to_owned()),
+                line: metadata.line().map(|line| line + 1000),
+                module_path: None,
+            })
+        })
+    });
+}
+
+#[test]
+fn serializes_like_owned_events_with_a_target_rewrite() {
+    check(|layer| layer.with_target_rewrite(|target| format!("rewritten::{}", target).into()));
+}
@@ -0,0 +1,114 @@
+//! Routes events to several sinks with different level thresholds and
+//! target lists.
+
+use std::sync::{Arc, Mutex};
+
+use tracing_bridge::{
+    BridgeLayer, EventSink, FanOutSink, FilterConfig, TracingEvent, TracingLevel,
+};
+use tracing_subscriber::layer::SubscriberExt;
+
+/// Returns a sink that keeps the messages of the events it receives.
+fn collect() -> (impl EventSink, Arc<Mutex<Vec<String>>>) {
+    let messages = Arc::new(Mutex::new(Vec::new()));
+    let sink = {
+        let messages = messages.clone();
+        move |event: TracingEvent| messages.lock().unwrap().push(event.message.unwrap())
+    };
+    (sink, messages)
+}
+
+fn at_least(level: TracingLevel) -> FilterConfig {
+    FilterConfig {
+        min_level: Some(level),
+        ..FilterConfig::default()
+    }
+}
+
+#[test]
+fn sends_each_sink_the_events_its_filter_matches() {
+    let (paging, paged) = collect();
+    let (file, filed) = collect();
+    let (nothing, ignored) = collect();
+    let sink = FanOutSink::new()
+        .route(at_least(TracingLevel::Error), paging)
+        .route(FilterConfig::default(), file)
+        .route(
+            FilterConfig {
+                target_allow: vec!["elsewhere".to_owned()],
+                ..FilterConfig::default()
+            },
+            nothing,
+        );
+
+    let subscriber = tracing_subscriber::registry().with(BridgeLayer::new(sink));
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::debug!("debug");
+        tracing::warn!("warn");
+        tracing::error!("error");
+    });
+
+    assert_eq!(*paged.lock().unwrap(), ["error"]);
+    assert_eq!(*filed.lock().unwrap(), ["debug", "warn", "error"]);
+    assert!(ignored.lock().unwrap().is_empty());
+}
+
+#[test]
+fn splits_batches_between_sinks() {
+    let (warnings, warned) = collect();
+    let (everything, all) = collect();
+    let sink = FanOutSink::new()
+        .route(at_least(TracingLevel::Warn), warnings)
+        .route(FilterConfig::default(), everything);
+
+    let events = [TracingLevel::Info, TracingLevel::Warn, TracingLevel::Error]
+        .iter()
+        .map(|&level| {
+            TracingEvent::builder()
+                .level(level)
+                .message(level.as_str())
+                .build()
+        })
+        .collect();
+    sink.emit_batch(events).unwrap();
+
+    assert_eq!(*warned.lock().unwrap(), ["WARN", "ERROR"]);
+    assert_eq!(*all.lock().unwrap(), ["INFO", "WARN", "ERROR"]);
+}
+
+#[test]
+fn routes_on_rewritten_targets() {
+    let (grouped, kept) = collect();
+    let (noisy, denied) = collect();
+    let sink = FanOutSink::new()
+        .route(
+            FilterConfig {
+                target_allow: vec!["h2".to_owned()],
+                ..FilterConfig::default()
+            },
+            grouped,
+        )
+        .route(
+            FilterConfig {
+                target_deny: vec!["h2".to_owned()],
+                ..FilterConfig::default()
+            },
+            noisy,
+        );
+
+    let layer = BridgeLayer::new(sink).with_target_rewrite(|target| {
+        if target.starts_with("h2_codec") {
+            "h2".into()
+        } else {
+            target.into()
+        }
+    });
+    let subscriber = tracing_subscriber::registry().with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::info!(target: "h2_codec::framed_write", "frame");
+        tracing::info!(target: "my_app", "request");
+    });
+
+    assert_eq!(*kept.lock().unwrap(), ["frame"]);
+    assert_eq!(*denied.lock().unwrap(), ["request"]);
+}
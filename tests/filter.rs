@@ -0,0 +1,138 @@
+//! Changes what a running layer captures and checks which callsites filter
+//! configs match.
+
+use tracing_bridge::{BridgeLayer, FilterConfig, TracingEvent, TracingLevel};
+use tracing_subscriber::layer::SubscriberExt;
+
+/// Logs one event at each of a few levels.
+fn log_levels() {
+    tracing::debug!("debug");
+    tracing::info!("info");
+    tracing::warn!("warn");
+}
+
+#[test]
+fn follows_config_changes_made_through_the_handle() {
+    let (layer, captured) = BridgeLayer::capturing();
+    let layer = layer.with_min_level(TracingLevel::Warn);
+    let handle = layer.filter_handle();
+    let subscriber = tracing_subscriber::registry().with(layer);
+
+    let messages = || -> Vec<String> {
+        captured
+            .drain()
+            .into_iter()
+            .filter_map(|event| event.message)
+            .collect()
+    };
+    tracing::subscriber::with_default(subscriber, || {
+        log_levels();
+        assert_eq!(messages(), ["warn"]);
+
+        handle.update(|config| config.min_level = Some(TracingLevel::Debug));
+        log_levels();
+        assert_eq!(messages(), ["debug", "info", "warn"]);
+
+        handle.set(FilterConfig {
+            min_level: Some(TracingLevel::Info),
+            ..FilterConfig::default()
+        });
+        log_levels();
+        assert_eq!(messages(), ["info", "warn"]);
+        assert_eq!(handle.get().min_level, Some(TracingLevel::Info));
+    });
+}
+
+#[test]
+fn follows_config_changes_with_global_filtering() {
+    let (layer, captured) = BridgeLayer::capturing();
+    let layer = layer
+        .with_min_level(TracingLevel::Warn)
+        .with_global_filtering(true);
+    let handle = layer.filter_handle();
+    let subscriber = tracing_subscriber::registry().with(layer);
+
+    tracing::subscriber::with_default(subscriber, || {
+        // Cache the callsites' interest while they are rejected.
+        log_levels();
+        handle.update(|config| config.min_level = Some(TracingLevel::Debug));
+        log_levels();
+    });
+
+    let messages: Vec<_> = captured
+        .events()
+        .into_iter()
+        .filter_map(|event| event.message)
+        .collect();
+    assert_eq!(messages, ["warn", "debug", "info", "warn"]);
+}
+
+fn matches(config: &FilterConfig, target: &str) -> bool {
+    config.matches(&TracingEvent::builder().target(target).build().metadata)
+}
+
+fn allowing(targets: &[&str]) -> FilterConfig {
+    FilterConfig {
+        target_allow: targets.iter().map(|&target| target.to_owned()).collect(),
+        ..FilterConfig::default()
+    }
+}
+
+#[test]
+fn matches_targets_only_at_module_boundaries() {
+    let config = allowing(&["my_app"]);
+    assert!(matches(&config, "my_app"));
+    assert!(matches(&config, "my_app::db"));
+    assert!(matches(&config, "my_app::db::pool"));
+    assert!(!matches(&config, "my_app_other"));
+    assert!(!matches(&config, "my_ap"));
+    assert!(!matches(&config, "other::my_app"));
+}
+
+#[test]
+fn matches_prefixes_ending_in_a_separator() {
+    let config = allowing(&["my_app::"]);
+    assert!(matches(&config, "my_app::db"));
+    assert!(!matches(&config, "my_app"));
+    assert!(!matches(&config, "my_app_other::db"));
+}
+
+#[test]
+fn prefers_the_denylist_over_the_allowlist() {
+    let config = FilterConfig {
+        target_deny: vec!["my_app::db".to_owned()],
+        ..allowing(&["my_app"])
+    };
+    assert!(matches(&config, "my_app::http"));
+    assert!(!matches(&config, "my_app::db"));
+    assert!(!matches(&config, "my_app::db::pool"));
+    assert!(matches(&config, "my_app::dbx"));
+
+    let config = FilterConfig {
+        target_deny: vec!["my_app".to_owned()],
+        ..allowing(&["my_app"])
+    };
+    assert!(!matches(&config, "my_app"));
+}
+
+#[test]
+fn applies_target_lists_to_captured_events() {
+    let (layer, captured) = BridgeLayer::capturing();
+    let layer = layer
+        .with_target_allowlist(["my_app"])
+        .with_target_denylist(["my_app::db"]);
+    let subscriber = tracing_subscriber::registry().with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::info!(target: "my_app", "kept");
+        tracing::info!(target: "my_app::http", "kept");
+        tracing::info!(target: "my_app_other", "dropped");
+        tracing::info!(target: "my_app::db", "dropped");
+    });
+
+    let targets: Vec<_> = captured
+        .events()
+        .into_iter()
+        .map(|event| event.metadata.target)
+        .collect();
+    assert_eq!(targets, ["my_app", "my_app::http"]);
+}
@@ -0,0 +1,21 @@
+//! Serializes events with the flat view and checks field names never collide
+//! with the event's properties or with each other.
+#![cfg(feature = "json")]
+
+use tracing_bridge::{TracingEvent, TracingLevel};
+
+#[test]
+fn keeps_prefixed_names_apart_from_renamed_fields() {
+    let event = TracingEvent::builder()
+        .level(TracingLevel::Warn)
+        .field("level", "high")
+        .field("fields.level", "low")
+        .field("fields", "plain")
+        .build();
+    let flat = serde_json::to_value(event.flat()).unwrap();
+
+    assert_eq!(flat["level"], "Warn");
+    assert_eq!(flat["fields.level"], "high");
+    assert_eq!(flat["fields.fields.level"], "low");
+    assert_eq!(flat["fields"], "plain");
+}
@@ -0,0 +1,45 @@
+//! Forwards events received from elsewhere into a local subscriber, and
+//! checks they arrive as they were captured.
+
+use tracing_bridge::{BridgeLayer, FieldValue, TracingEvent, TracingLevel};
+use tracing_subscriber::layer::SubscriberExt;
+
+fn received() -> Vec<TracingEvent> {
+    vec![
+        TracingEvent::builder()
+            .level(TracingLevel::Warn)
+            .target("remote::db")
+            .message("connection slow")
+            .field("retries", 3_u64)
+            .build(),
+        TracingEvent::builder()
+            .level(TracingLevel::Error)
+            .target("remote::http")
+            .message("request failed")
+            .field("status", 503_i64)
+            .field("path", "/users")
+            .build(),
+    ]
+}
+
+#[test]
+fn forwards_events_into_the_local_subscriber() {
+    let (layer, captured) = BridgeLayer::capturing();
+    let subscriber = tracing_subscriber::registry().with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        tracing_bridge::forward(received().into_iter());
+    });
+
+    let forwarded = captured.events();
+    assert_eq!(forwarded.len(), 2);
+    for (forwarded, original) in forwarded.iter().zip(received()) {
+        assert_eq!(forwarded.metadata.level, original.metadata.level);
+        assert_eq!(forwarded.metadata.target, original.metadata.target);
+        assert_eq!(forwarded.message, original.message);
+        assert_eq!(forwarded.fields, original.fields);
+    }
+    assert_eq!(
+        forwarded[1].fields.get("status"),
+        Some(&FieldValue::I64(503))
+    );
+}
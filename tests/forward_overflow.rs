@@ -0,0 +1,47 @@
+//! Forwards events of more distinct shapes than get their own callsites.
+//! This has its own test binary, since the cap is process-wide and other
+//! tests would find it used up.
+
+use tracing_bridge::{BridgeLayer, FieldValue, TracingEvent, TracingLevel};
+use tracing_subscriber::layer::SubscriberExt;
+
+#[test]
+fn falls_back_to_a_shared_callsite_past_the_cap() {
+    let shape = |i: usize| {
+        TracingEvent::builder()
+            .level(TracingLevel::Warn)
+            .target(format!("peer::module{}", i))
+            .message("slow")
+            .field(format!("field{}", i), i as u64)
+            .build()
+    };
+    let (layer, captured) = BridgeLayer::capturing();
+    let subscriber = tracing_subscriber::registry().with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        tracing_bridge::forward((0..1030).map(shape));
+        // Shapes seen before the cap keep their own callsite.
+        tracing_bridge::forward(std::iter::once(shape(0)));
+    });
+
+    let forwarded = captured.events();
+    assert_eq!(forwarded.len(), 1031);
+    assert_eq!(forwarded[1023].metadata.target, "peer::module1023");
+
+    let overflow = &forwarded[1024];
+    assert_eq!(overflow.metadata.target, "tracing_bridge::replay");
+    assert_eq!(overflow.metadata.level, TracingLevel::Warn);
+    assert_eq!(overflow.message.as_deref(), Some("slow"));
+    assert_eq!(
+        overflow.fields.get("origin.target"),
+        Some(&FieldValue::Str("peer::module1024".to_owned()))
+    );
+    assert_eq!(
+        overflow.fields.get("origin.fields"),
+        Some(&FieldValue::Debug("{field1024=1024}".to_owned()))
+    );
+    assert_eq!(forwarded[1030].metadata.target, "peer::module0");
+    assert_eq!(
+        forwarded[1030].fields.get("field0"),
+        Some(&FieldValue::U64(0))
+    );
+}
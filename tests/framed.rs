@@ -0,0 +1,110 @@
+//! Writes events as length-prefixed frames and reads them back, including
+//! streams that were cut short or carry a bogus length.
+#![cfg(feature = "bincode")]
+
+use std::{convert::TryFrom, io};
+
+use tracing_bridge::{Frame, FramedReader, FramedWriter, StreamHeader, TracingEvent};
+
+fn event(seq: u64) -> TracingEvent {
+    TracingEvent::builder()
+        .message("tick")
+        .field("seq", seq)
+        .seq(seq)
+        .build()
+}
+
+fn written(events: impl IntoIterator<Item = TracingEvent>) -> Vec<u8> {
+    let mut writer = FramedWriter::new(Vec::new());
+    for event in events {
+        writer.write(&event).unwrap();
+    }
+    writer.into_inner()
+}
+
+fn read_all(bytes: &[u8]) -> Vec<TracingEvent> {
+    FramedReader::new(bytes)
+        .events()
+        .collect::<io::Result<_>>()
+        .unwrap()
+}
+
+#[test]
+fn reads_back_written_events() {
+    let events: Vec<_> = (0..3).map(event).collect();
+    assert_eq!(read_all(&written(events.clone())), events);
+    assert!(read_all(&[]).is_empty());
+}
+
+#[test]
+fn reads_the_header_as_the_first_frame() {
+    let header = StreamHeader::current();
+    let mut writer = FramedWriter::with_header(Vec::new(), &header).unwrap();
+    writer.write(&event(0)).unwrap();
+    let bytes = writer.into_inner();
+
+    let frames: Vec<_> = FramedReader::new(&bytes[..])
+        .collect::<io::Result<_>>()
+        .unwrap();
+    assert_eq!(
+        frames,
+        [Frame::Header(header), Frame::Event(Box::new(event(0)))]
+    );
+    assert_eq!(read_all(&bytes), [event(0)]);
+}
+
+#[test]
+fn reads_headers_where_streams_were_concatenated() {
+    let header = StreamHeader::current();
+    let mut writer = FramedWriter::with_header(written(Some(event(0))), &header).unwrap();
+    writer.write(&event(1)).unwrap();
+    let bytes = writer.into_inner();
+
+    let frames: Vec<_> = FramedReader::new(&bytes[..])
+        .collect::<io::Result<_>>()
+        .unwrap();
+    assert_eq!(
+        frames,
+        [
+            Frame::Event(Box::new(event(0))),
+            Frame::Header(header),
+            Frame::Event(Box::new(event(1))),
+        ]
+    );
+}
+
+#[test]
+fn ends_at_a_frame_cut_short_by_the_end_of_the_stream() {
+    let bytes = written((0..2).map(event));
+    let first_len = written(Some(event(0))).len();
+
+    // Cut inside the second frame's payload, and inside its length prefix.
+    assert_eq!(read_all(&bytes[..bytes.len() - 1]), [event(0)]);
+    assert_eq!(read_all(&bytes[..first_len + 2]), [event(0)]);
+}
+
+#[test]
+fn rejects_frames_longer_than_the_maximum() {
+    // A length prefix claiming almost 4 GiB, with no payload behind it.
+    let mut bytes = u32::MAX.to_be_bytes().to_vec();
+    bytes.extend_from_slice(b"junk");
+    let error = FramedReader::new(&bytes[..]).next().unwrap().unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+    // The oversized frame is skipped, so the events after it can still be
+    // read.
+    let mut bytes = written(Some(event(0)));
+    let big = TracingEvent::builder().message("x".repeat(1000)).build();
+    bytes.extend_from_slice(&written(Some(big)));
+    bytes.extend_from_slice(&written(Some(event(2))));
+    let max = u32::try_from(written(Some(event(0))).len()).unwrap();
+    let results: Vec<_> = FramedReader::new(&bytes[..])
+        .with_max_frame_len(max)
+        .events()
+        .map(|result| result.map_err(|error| error.kind()))
+        .collect();
+    assert_eq!(
+        results,
+        [Ok(event(0)), Err(io::ErrorKind::InvalidData), Ok(event(2))]
+    );
+}
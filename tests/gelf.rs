@@ -0,0 +1,48 @@
+//! Checks the GELF messages events convert to against what Graylog accepts.
+#![cfg(feature = "gelf")]
+
+use serde_json::json;
+use tracing_bridge::{TracingEvent, TracingLevel};
+
+#[test]
+fn converts_events_to_gelf_messages() {
+    let event = TracingEvent::builder()
+        .level(TracingLevel::Warn)
+        .target("my_app::db")
+        .message("connection slow\nretrying in 5s")
+        .field("retries", 3_u64)
+        .field("id", "abc")
+        .field("peer addr", "10.0.0.1")
+        .field("healthy", false)
+        .timestamp(1_700_000_000_250_000_000)
+        .build();
+
+    assert_eq!(
+        event.to_gelf_value("web-1"),
+        json!({
+            "version": "1.1",
+            "host": "web-1",
+            "short_message": "connection slow",
+            "full_message": "connection slow\nretrying in 5s",
+            "timestamp": 1_700_000_000.25,
+            "level": 4,
+            "_retries": 3,
+            "_id_": "abc",
+            "_peer_addr": "10.0.0.1",
+            "_healthy": "false",
+        })
+    );
+}
+
+#[test]
+fn falls_back_to_the_formatted_event_without_a_message() {
+    let event = TracingEvent::builder()
+        .target("my_app")
+        .field("rows", 10_i64)
+        .build();
+
+    let gelf = event.to_gelf_value("web-1");
+    assert_eq!(gelf["short_message"], json!(event.to_string()));
+    assert!(gelf.get("full_message").is_none());
+    assert!(gelf.get("timestamp").is_none());
+}
@@ -0,0 +1,81 @@
+//! Writes events as a JSON array and checks the document parses, however the
+//! sink is closed.
+#![cfg(feature = "json")]
+
+mod common;
+
+use common::Shared;
+use tracing_bridge::{BridgeLayer, EventSink, JsonArraySink, TracingEvent};
+use tracing_subscriber::layer::SubscriberExt;
+
+/// Parses everything written so far as one JSON document.
+fn document(written: &Shared) -> serde_json::Value {
+    serde_json::from_slice(&written.bytes()).unwrap()
+}
+
+fn event(seq: u64) -> TracingEvent {
+    TracingEvent::builder().message("tick").seq(seq).build()
+}
+
+#[test]
+fn closes_the_array_when_the_flush_guard_is_dropped() {
+    let written = Shared::default();
+    let layer = BridgeLayer::new(JsonArraySink::new(written.clone()));
+    let guard = layer.flush_guard();
+    let subscriber = tracing_subscriber::registry().with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::info!("first");
+        tracing::warn!(rows = 3, "second");
+    });
+    drop(guard);
+
+    let events: Vec<TracingEvent> = serde_json::from_value(document(&written)).unwrap();
+    let messages: Vec<_> = events
+        .iter()
+        .map(|event| event.message.as_deref())
+        .collect();
+    assert_eq!(messages, [Some("first"), Some("second")]);
+}
+
+#[test]
+fn separates_single_events_and_batches_with_commas() {
+    let sink = JsonArraySink::new(Vec::new());
+    sink.emit(event(0)).unwrap();
+    sink.emit_batch(vec![event(1), event(2)]).unwrap();
+    sink.emit_batch(Vec::new()).unwrap();
+    sink.emit(event(3)).unwrap();
+
+    let events: Vec<TracingEvent> = serde_json::from_slice(&sink.finish().unwrap()).unwrap();
+    assert_eq!(events, (0..4).map(event).collect::<Vec<_>>());
+}
+
+#[test]
+fn closes_the_array_when_dropped() {
+    let written = Shared::default();
+    let sink = JsonArraySink::new(written.clone());
+    sink.emit(event(0)).unwrap();
+    assert!(serde_json::from_slice::<serde_json::Value>(&written.bytes()).is_err());
+
+    drop(sink);
+    assert_eq!(document(&written).as_array().map(Vec::len), Some(1));
+}
+
+#[test]
+fn writes_an_empty_array_without_events() {
+    let written = Shared::default();
+    drop(JsonArraySink::new(written.clone()));
+    assert_eq!(document(&written), serde_json::json!([]));
+}
+
+#[test]
+fn rejects_events_once_closed() {
+    let written = Shared::default();
+    let sink = JsonArraySink::new(written.clone());
+    sink.emit(event(0)).unwrap();
+    sink.shutdown().unwrap();
+    sink.shutdown().unwrap();
+
+    assert!(sink.emit(event(1)).is_err());
+    drop(sink);
+    assert_eq!(document(&written).as_array().map(Vec::len), Some(1));
+}
@@ -0,0 +1,69 @@
+//! Reads back what the JSON Lines sink writes, and checks how each policy
+//! treats lines that aren't events.
+#![cfg(feature = "json")]
+
+use std::io::{Cursor, ErrorKind};
+
+use tracing_bridge::{
+    read_json_lines, EventSink, FieldValue, InvalidLinePolicy, JsonLinesSink, TracingEvent,
+};
+
+fn event(message: &str) -> TracingEvent {
+    TracingEvent::builder()
+        .message(message)
+        .field("n", FieldValue::U64(1))
+        .build()
+}
+
+/// Two events with a blank line, an invalid line and a CRLF line ending
+/// between them.
+fn input() -> String {
+    let first = serde_json::to_string(&event("first")).unwrap();
+    let second = serde_json::to_string(&event("second")).unwrap();
+    format!("{}\r\n\n{{\"truncated\n{}\n", first, second)
+}
+
+#[test]
+fn reads_back_what_the_sink_writes() {
+    let sink = JsonLinesSink::new(Vec::new());
+    let events = vec![event("first"), event("second"), event("third")];
+    for event in &events {
+        sink.emit(event.clone()).unwrap();
+    }
+
+    let written = sink.into_inner();
+    let read: Vec<TracingEvent> = read_json_lines(Cursor::new(written))
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(read, events);
+}
+
+#[test]
+fn reports_invalid_lines_and_carries_on() {
+    let results: Vec<_> = read_json_lines(Cursor::new(input())).collect();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_ref().unwrap(), &event("first"));
+    let error = results[1].as_ref().unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+    assert!(error.to_string().starts_with("line 3: "), "{}", error);
+    assert_eq!(results[2].as_ref().unwrap(), &event("second"));
+}
+
+#[test]
+fn skips_and_counts_invalid_lines() {
+    let mut reader =
+        read_json_lines(Cursor::new(input())).with_invalid_lines(InvalidLinePolicy::Skip);
+    let events: Vec<TracingEvent> = reader.by_ref().map(Result::unwrap).collect();
+    assert_eq!(events, vec![event("first"), event("second")]);
+    assert_eq!(reader.skipped(), 1);
+}
+
+#[test]
+fn stops_at_the_first_invalid_line() {
+    let results: Vec<_> = read_json_lines(Cursor::new(input()))
+        .with_invalid_lines(InvalidLinePolicy::Stop)
+        .collect();
+    assert_eq!(results.len(), 2);
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+}
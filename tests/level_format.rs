@@ -0,0 +1,146 @@
+//! Serializes levels as priorities and checks they read back to the same
+//! level.
+#![cfg(feature = "json")]
+
+use serde::de::DeserializeSeed;
+use tracing_bridge::{
+    read_json_lines, EventFormat, FormattedEvent, JsonLinesSerializer, LevelFormat, PrioritySchema,
+    TracingEvent, TracingLevel, WriterSink,
+};
+
+const LEVELS: [TracingLevel; 5] = [
+    TracingLevel::Trace,
+    TracingLevel::Debug,
+    TracingLevel::Info,
+    TracingLevel::Warn,
+    TracingLevel::Error,
+];
+
+fn in_format(format: LevelFormat) -> EventFormat {
+    EventFormat::default().with_level_format(format)
+}
+
+fn event(level: TracingLevel) -> TracingEvent {
+    TracingEvent::builder().level(level).message("tick").build()
+}
+
+/// Writes `event` as JSON with its level in `format`.
+fn to_json(event: &TracingEvent, format: LevelFormat) -> String {
+    serde_json::to_string(&FormattedEvent::new(event, in_format(format))).unwrap()
+}
+
+/// Reads an event from JSON with its level in `format`.
+fn from_json(json: &str, format: LevelFormat) -> serde_json::Result<TracingEvent> {
+    in_format(format).deserialize(&mut serde_json::Deserializer::from_str(json))
+}
+
+#[test]
+fn writes_names_by_default() {
+    let json = serde_json::to_value(event(TracingLevel::Warn)).unwrap();
+    assert_eq!(json["metadata"]["level"], "Warn");
+
+    let json = to_json(&event(TracingLevel::Warn), LevelFormat::Name);
+    assert_eq!(
+        json,
+        serde_json::to_string(&event(TracingLevel::Warn)).unwrap()
+    );
+}
+
+#[test]
+fn reads_back_levels_written_as_priorities() {
+    let format = LevelFormat::Priority(PrioritySchema::Otel);
+    for &level in &LEVELS {
+        let json = to_json(&event(level), format);
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            value["metadata"]["level"],
+            level.to_priority(PrioritySchema::Otel)
+        );
+        assert_eq!(from_json(&json, format).unwrap(), event(level));
+    }
+}
+
+#[test]
+fn reads_shared_priorities_as_the_most_severe_level() {
+    let format = LevelFormat::Priority(PrioritySchema::Syslog);
+    let read: Vec<_> = LEVELS
+        .iter()
+        .map(|&level| {
+            let json = to_json(&event(level), format);
+            from_json(&json, format).unwrap().metadata.level
+        })
+        .collect();
+    assert_eq!(
+        read,
+        [
+            TracingLevel::Debug,
+            TracingLevel::Debug,
+            TracingLevel::Info,
+            TracingLevel::Warn,
+            TracingLevel::Error,
+        ]
+    );
+}
+
+#[test]
+fn reads_names_and_rejects_unknown_priorities() {
+    let format = LevelFormat::Priority(PrioritySchema::Custom([10, 20, 30, 40, 50]));
+    let json = |level: serde_json::Value| {
+        let mut json = serde_json::to_value(event(TracingLevel::Info)).unwrap();
+        json["metadata"]["level"] = level;
+        json.to_string()
+    };
+    let level = |json: &str| from_json(json, format).map(|event| event.metadata.level);
+
+    assert_eq!(level(&json("Info".into())).unwrap(), TracingLevel::Info);
+    assert_eq!(level(&json(40.into())).unwrap(), TracingLevel::Warn);
+    assert!(level(&json(35.into())).is_err());
+    assert!(level(&json((-1).into())).is_err());
+
+    // Without the format, numbers aren't levels.
+    assert!(serde_json::from_str::<TracingEvent>(&json(40.into())).is_err());
+}
+
+#[cfg(feature = "bincode")]
+#[test]
+fn reads_back_framed_levels_written_in_each_format() {
+    use tracing_bridge::{FramedReader, FramedWriter};
+
+    let formats = [
+        LevelFormat::Name,
+        LevelFormat::Priority(PrioritySchema::Otel),
+    ];
+    for &format in &formats {
+        let mut writer = FramedWriter::new(Vec::new()).with_level_format(format);
+        for &level in &LEVELS {
+            writer.write(&event(level)).unwrap();
+        }
+        let bytes = writer.into_inner();
+
+        let read: Vec<_> = FramedReader::new(&bytes[..])
+            .with_level_format(format)
+            .events()
+            .collect::<std::io::Result<_>>()
+            .unwrap();
+        let expected: Vec<_> = LEVELS.iter().map(|&level| event(level)).collect();
+        assert_eq!(read, expected, "{:?}", format);
+    }
+}
+
+#[test]
+fn writes_levels_in_the_format_of_the_sink() {
+    let format = LevelFormat::Priority(PrioritySchema::Syslog);
+    let sink = WriterSink::new(JsonLinesSerializer, Vec::new()).with_level_format(format);
+    tracing_bridge::EventSink::emit(&sink, event(TracingLevel::Error)).unwrap();
+    let written = sink.into_inner();
+
+    let line: serde_json::Value = serde_json::from_slice(&written).unwrap();
+    assert_eq!(line["metadata"]["level"], 3);
+
+    let read = read_json_lines(&written[..])
+        .with_level_format(format)
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(read, event(TracingLevel::Error));
+}
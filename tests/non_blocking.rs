@@ -0,0 +1,132 @@
+//! Checks that the non-blocking sink writes every event it accepts, in order,
+//! and drops rather than waits when asked to.
+#![cfg(feature = "json")]
+
+use std::{
+    io::{self, Write},
+    sync::{mpsc, Arc, Mutex},
+};
+
+use tracing_bridge::{EventSink, NonBlockingSink, OverflowPolicy, SinkErrorKind, TracingEvent};
+
+/// A writer whose output can be read back while the sink owns it, and which
+/// can be made to block until released.
+#[derive(Clone, Default)]
+struct Shared {
+    written: Arc<Mutex<Vec<u8>>>,
+    gate: Option<Gate>,
+}
+
+/// Reports each write as it starts, then blocks it until the sender of
+/// `open` is dropped.
+#[derive(Clone)]
+struct Gate {
+    entered: mpsc::Sender<()>,
+    open: Arc<Mutex<mpsc::Receiver<()>>>,
+}
+
+impl Shared {
+    /// Returns a gated writer, a sender to drop to open the gate, and a
+    /// receiver told whenever a write starts.
+    fn gated() -> (Self, mpsc::Sender<()>, mpsc::Receiver<()>) {
+        let (opener, open) = mpsc::channel();
+        let (entered, writes) = mpsc::channel();
+        let gate = Gate {
+            entered,
+            open: Arc::new(Mutex::new(open)),
+        };
+        let writer = Self {
+            gate: Some(gate),
+            ..Self::default()
+        };
+        (writer, opener, writes)
+    }
+
+    fn messages(&self) -> Vec<String> {
+        let written = self.written.lock().unwrap();
+        std::str::from_utf8(&written)
+            .unwrap()
+            .lines()
+            .map(|line| {
+                let event: TracingEvent = serde_json::from_str(line).unwrap();
+                event.message.unwrap()
+            })
+            .collect()
+    }
+}
+
+impl Write for Shared {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if let Some(gate) = &self.gate {
+            let _ = gate.entered.send(());
+            let _ = gate.open.lock().unwrap().recv();
+        }
+        self.written.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn event(message: usize) -> TracingEvent {
+    TracingEvent::builder().message(message.to_string()).build()
+}
+
+fn numbers(range: std::ops::Range<usize>) -> Vec<String> {
+    range.map(|n| n.to_string()).collect()
+}
+
+#[test]
+fn writes_every_event_in_order_by_flush() {
+    let writer = Shared::default();
+    let sink = NonBlockingSink::new(writer.clone(), 4, OverflowPolicy::Block);
+    for n in 0..100 {
+        sink.emit(event(n)).unwrap();
+    }
+    sink.flush().unwrap();
+    assert_eq!(writer.messages(), numbers(0..100));
+}
+
+#[test]
+fn drops_events_when_the_channel_is_full() {
+    let (writer, opener, writes) = Shared::gated();
+    let sink = NonBlockingSink::new(writer.clone(), 1, OverflowPolicy::Drop);
+
+    // Once the writer thread is blocked writing the first event, one more
+    // fills the channel and the rest are dropped.
+    sink.emit(event(0)).unwrap();
+    writes.recv().unwrap();
+    sink.emit(event(1)).unwrap();
+    for n in 2..10 {
+        let error = sink.emit(event(n)).unwrap_err();
+        assert_eq!(error.kind(), SinkErrorKind::Full);
+    }
+    assert_eq!(sink.dropped(), 8);
+
+    drop(opener);
+    sink.shutdown().unwrap();
+    assert_eq!(writer.messages(), numbers(0..2));
+}
+
+#[test]
+fn writes_synchronously_after_shutdown() {
+    let writer = Shared::default();
+    let sink = NonBlockingSink::new(writer.clone(), 4, OverflowPolicy::Block);
+    sink.emit(event(0)).unwrap();
+    sink.shutdown().unwrap();
+    sink.emit(event(1)).unwrap();
+    assert_eq!(writer.messages(), numbers(0..2));
+}
+
+#[test]
+fn writes_queued_events_when_dropped() {
+    let writer = Shared::default();
+    let sink = NonBlockingSink::new(writer.clone(), 64, OverflowPolicy::Block);
+    for n in 0..50 {
+        sink.emit(event(n)).unwrap();
+    }
+    drop(sink);
+    assert_eq!(writer.messages(), numbers(0..50));
+}
@@ -0,0 +1,47 @@
+//! Pins down that levels are ordered by severity, the reverse of
+//! `tracing_core::Level`.
+
+use tracing_bridge::TracingLevel;
+use tracing_core::Level;
+
+const BY_SEVERITY: [TracingLevel; 5] = [
+    TracingLevel::Trace,
+    TracingLevel::Debug,
+    TracingLevel::Info,
+    TracingLevel::Warn,
+    TracingLevel::Error,
+];
+
+#[test]
+fn orders_levels_from_least_to_most_severe() {
+    assert!(TracingLevel::Trace < TracingLevel::Debug);
+    assert!(TracingLevel::Debug < TracingLevel::Info);
+    assert!(TracingLevel::Info < TracingLevel::Warn);
+    assert!(TracingLevel::Warn < TracingLevel::Error);
+
+    let mut shuffled = [
+        TracingLevel::Warn,
+        TracingLevel::Trace,
+        TracingLevel::Error,
+        TracingLevel::Info,
+        TracingLevel::Debug,
+    ];
+    shuffled.sort();
+    assert_eq!(shuffled, BY_SEVERITY);
+    assert_eq!(BY_SEVERITY.iter().max(), Some(&TracingLevel::Error));
+}
+
+#[test]
+fn reverses_the_order_of_tracing_levels() {
+    for a in BY_SEVERITY {
+        for b in BY_SEVERITY {
+            assert_eq!(
+                a.cmp(&b),
+                Level::from(&a).cmp(&Level::from(&b)).reverse(),
+                "{} vs {}",
+                a,
+                b
+            );
+        }
+    }
+}
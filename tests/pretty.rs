@@ -0,0 +1,101 @@
+//! Formats captured events with `format_pretty` and compares them with what
+//! `tracing_subscriber::fmt` wrote for the same events.
+
+mod common;
+
+use std::{error::Error, fmt};
+
+use common::Shared;
+use tracing_bridge::{BridgeLayer, TracingEvent, TracingLevel};
+use tracing_subscriber::layer::SubscriberExt;
+
+#[derive(Debug)]
+struct Outer(Inner);
+
+impl fmt::Display for Outer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("request failed")
+    }
+}
+
+impl Error for Outer {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+#[derive(Debug)]
+struct Inner;
+
+impl fmt::Display for Inner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("connection \x1b[31mreset")
+    }
+}
+
+impl Error for Inner {}
+
+/// Stands in for application code, logging every kind of value.
+fn log_everything() {
+    tracing::info!(a = 1, b = "x", c = 1.0, "msg \x1b[1m here");
+    tracing::error!(a = -1);
+    tracing::trace!(r#type = 1, d = ?[1], "m");
+    tracing::warn!(ok = true, code = 7_u64);
+    tracing::debug!(err = &Outer(Inner) as &dyn Error, "failed");
+    tracing::debug!(err = &Inner as &dyn Error);
+}
+
+/// Returns the lines the fmt layer wrote, without timestamps, next to the
+/// captured events formatted with `format_pretty`.
+fn both(with_ansi: bool) -> (Vec<String>, Vec<String>) {
+    let written = Shared::default();
+    let (layer, captured) = BridgeLayer::capturing();
+    let subscriber = tracing_subscriber::registry().with(layer).with(
+        tracing_subscriber::fmt::layer()
+            .without_time()
+            .with_ansi(with_ansi)
+            .with_writer(written.clone()),
+    );
+    tracing::subscriber::with_default(subscriber, log_everything);
+
+    let expected = String::from_utf8(written.bytes()).unwrap();
+    let formatted = captured
+        .events()
+        .into_iter()
+        .map(|mut event| {
+            event.timestamp = None;
+            event.format_pretty(with_ansi)
+        })
+        .collect();
+    (expected.lines().map(str::to_owned).collect(), formatted)
+}
+
+#[test]
+fn matches_the_fmt_layer_without_ansi() {
+    let (expected, formatted) = both(false);
+    assert_eq!(formatted, expected);
+}
+
+#[test]
+fn matches_the_fmt_layer_with_ansi() {
+    let (expected, formatted) = both(true);
+    assert_eq!(formatted, expected);
+}
+
+#[test]
+fn writes_timestamps_with_microseconds() {
+    let event = TracingEvent::builder()
+        .level(TracingLevel::Error)
+        .target("my_app")
+        .timestamp(1_714_566_600_123_456_789)
+        .build();
+    assert_eq!(
+        event.format_pretty(false),
+        "2024-05-01T12:30:00.123456Z ERROR my_app: "
+    );
+    assert_eq!(
+        event.format_pretty(true),
+        "\x1b[2m2024-05-01T12:30:00.123456Z\x1b[0m \x1b[31mERROR\x1b[0m \
+         \x1b[2mmy_app\x1b[0m\x1b[2m:\x1b[0m "
+    );
+}
@@ -0,0 +1,104 @@
+//! Drives a rate limited layer with a manual clock and checks which events
+//! get through and how suppressed ones are reported.
+
+use std::time::{Duration, SystemTime};
+
+use tracing_bridge::{BridgeLayer, CapturingSink, DropStats, FieldValue, ManualClock};
+use tracing_subscriber::layer::SubscriberExt;
+
+/// A callsite that logs far more often than the limit allows.
+fn storm(times: usize) {
+    for i in 0..times {
+        tracing::error!(i, "disk full");
+    }
+}
+
+/// A well-behaved callsite.
+fn heartbeat() {
+    tracing::info!("heartbeat");
+}
+
+/// Runs `f` with a layer allowing two events per second per callsite, and
+/// the clock it uses.
+fn limited(f: impl FnOnce(&ManualClock)) -> (CapturingSink, DropStats) {
+    let clock = ManualClock::new(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000));
+    let (layer, captured) = BridgeLayer::capturing();
+    let layer = layer.with_clock(clock.clone()).with_rate_limit(2);
+    let drop_stats = layer.drop_stats();
+    let subscriber = tracing_subscriber::registry().with(layer);
+    tracing::subscriber::with_default(subscriber, || f(&clock));
+    (captured, drop_stats)
+}
+
+fn messages(captured: &CapturingSink) -> Vec<String> {
+    captured
+        .drain()
+        .into_iter()
+        .filter_map(|event| event.message)
+        .collect()
+}
+
+#[test]
+fn allows_bursts_up_to_the_limit_per_callsite() {
+    let (captured, drop_stats) = limited(|_| {
+        storm(5);
+        heartbeat();
+    });
+
+    assert_eq!(messages(&captured), ["disk full", "disk full", "heartbeat"]);
+    assert_eq!(drop_stats.snapshot().rate_limited, 3);
+}
+
+#[test]
+fn refills_the_bucket_over_time() {
+    let (captured, _) = limited(|clock| {
+        storm(3);
+        clock.advance(Duration::from_millis(500));
+        storm(3);
+    });
+    // Half a second refills one of the two tokens.
+    assert_eq!(messages(&captured).len(), 3);
+}
+
+#[test]
+fn reports_suppressed_events_once_a_second() {
+    let (captured, _) = limited(|clock| {
+        storm(5);
+        clock.advance(Duration::from_millis(500));
+        heartbeat();
+        clock.advance(Duration::from_millis(500));
+        // The storm has stopped, so another callsite triggers the report.
+        heartbeat();
+    });
+
+    let events = captured.drain();
+    let summaries: Vec<_> = events
+        .iter()
+        .filter(|event| event.metadata.target == "tracing_bridge::rate_limit")
+        .collect();
+    assert_eq!(summaries.len(), 1);
+    assert_eq!(
+        summaries[0].fields.get("suppressed"),
+        Some(&FieldValue::U64(3))
+    );
+    assert_eq!(
+        summaries[0].fields.get("callsite.target"),
+        Some(&FieldValue::Str("rate_limit".to_owned()))
+    );
+    // The report comes just before the event that triggered it.
+    let last: Vec<_> = events[events.len() - 2..]
+        .iter()
+        .map(|event| event.metadata.target.as_str())
+        .collect();
+    assert_eq!(last, ["tracing_bridge::rate_limit", "rate_limit"]);
+}
+
+#[test]
+fn reports_nothing_when_nothing_was_suppressed() {
+    let (captured, _) = limited(|clock| {
+        storm(2);
+        clock.advance(Duration::from_secs(2));
+        heartbeat();
+    });
+    assert_eq!(messages(&captured), ["disk full", "disk full", "heartbeat"]);
+}
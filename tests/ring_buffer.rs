@@ -0,0 +1,63 @@
+//! Keeps the most recent events in a ring buffer and reads them back, as a
+//! panic hook would.
+
+use std::{
+    panic::{self, PanicHookInfo},
+    sync::{Arc, Mutex},
+};
+
+use tracing_bridge::{BridgeLayer, EventSink, RingBufferSink, TracingEvent};
+use tracing_subscriber::layer::SubscriberExt;
+
+fn event(seq: u64) -> TracingEvent {
+    TracingEvent::builder().message("tick").seq(seq).build()
+}
+
+fn seqs(events: Vec<TracingEvent>) -> Vec<u64> {
+    events.into_iter().filter_map(|event| event.seq).collect()
+}
+
+#[test]
+fn keeps_the_most_recent_events() {
+    let sink = RingBufferSink::new(3);
+    for seq in 0..2 {
+        sink.emit(event(seq)).unwrap();
+    }
+    assert_eq!(seqs(sink.snapshot()), [0, 1]);
+
+    sink.emit_batch((2..5).map(event).collect()).unwrap();
+    assert_eq!(seqs(sink.snapshot()), [2, 3, 4]);
+    // Taking a snapshot leaves the events in place.
+    assert_eq!(seqs(sink.snapshot()), [2, 3, 4]);
+}
+
+#[test]
+fn keeps_nothing_with_no_capacity() {
+    let sink = RingBufferSink::new(0);
+    sink.emit(event(0)).unwrap();
+    assert!(sink.snapshot().is_empty());
+}
+
+#[test]
+fn snapshots_from_a_panic_hook() {
+    let recent = RingBufferSink::new(2);
+    let dumped = Arc::new(Mutex::new(Vec::new()));
+    let hook = {
+        let (recent, dumped) = (recent.clone(), dumped.clone());
+        move |_: &PanicHookInfo<'_>| *dumped.lock().unwrap() = recent.snapshot()
+    };
+
+    let subscriber = tracing_subscriber::registry().with(BridgeLayer::new(recent.clone()));
+    tracing::subscriber::with_default(subscriber, || {
+        for attempt in 0..3 {
+            tracing::warn!(attempt, "retrying");
+        }
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(hook));
+        let result = panic::catch_unwind(|| panic!("gave up"));
+        panic::set_hook(default_hook);
+        assert!(result.is_err());
+    });
+
+    assert_eq!(seqs(dumped.lock().unwrap().clone()), [1, 2]);
+}
@@ -0,0 +1,42 @@
+//! Keeps the published event schema from changing unnoticed.
+#![cfg(feature = "schemars")]
+
+use tracing_bridge::{event_json_schema, TracingEvent, EVENT_SCHEMA_VERSION};
+
+/// If this fails, the schema changed: bump `EVENT_SCHEMA_VERSION` and add the
+/// new schema next to the old ones, rather than editing a published file.
+#[test]
+fn matches_the_published_schema() {
+    let path = format!(
+        "{}/schema/event.v{}.json",
+        env!("CARGO_MANIFEST_DIR"),
+        EVENT_SCHEMA_VERSION
+    );
+    let published = std::fs::read_to_string(&path).unwrap();
+    let published: serde_json::Value = serde_json::from_str(&published).unwrap();
+    assert_eq!(event_json_schema(), published);
+}
+
+/// Returns the sorted keys of a JSON object, or the sorted strings of a JSON
+/// array.
+fn names(value: &serde_json::Value) -> Vec<String> {
+    let mut names: Vec<String> = match value {
+        serde_json::Value::Object(object) => object.keys().cloned().collect(),
+        serde_json::Value::Array(array) => array
+            .iter()
+            .map(|name| name.as_str().unwrap().to_owned())
+            .collect(),
+        _ => panic!("expected an object or an array, got {}", value),
+    };
+    names.sort();
+    names
+}
+
+#[test]
+fn requires_every_serialized_property() {
+    let event = TracingEvent::builder().message("hello").build();
+    let serialized = names(&serde_json::to_value(&event).unwrap());
+    let schema = event_json_schema();
+    assert_eq!(names(&schema["properties"]), serialized);
+    assert_eq!(names(&schema["required"]), serialized);
+}
@@ -0,0 +1,111 @@
+//! Composes each built-in serializer with an in-memory writer and checks the
+//! bytes decode back to the events written.
+
+mod common;
+
+use common::Shared;
+use tracing_bridge::{EventSerializer, EventSink, SinkError, TracingEvent, WriterSink};
+
+fn events() -> Vec<TracingEvent> {
+    (0..3)
+        .map(|seq| {
+            TracingEvent::builder()
+                .target("my_app::db")
+                .message("query")
+                .field("rows", seq * 10)
+                .seq(seq as u64)
+                .build()
+        })
+        .collect()
+}
+
+/// Writes the first event on its own and the others as a batch, returning
+/// everything written.
+fn write_all(serializer: impl EventSerializer) -> Vec<u8> {
+    let written = Shared::default();
+    let sink = WriterSink::new(serializer, written.clone());
+    let mut events = events();
+    let rest = events.split_off(1);
+    sink.emit(events.remove(0)).unwrap();
+    sink.emit_batch(rest).unwrap();
+    sink.flush().unwrap();
+    written.bytes()
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn writes_json_documents() {
+    let bytes = write_all(tracing_bridge::JsonSerializer);
+    let decoded: Vec<TracingEvent> = serde_json::Deserializer::from_slice(&bytes)
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(decoded, events());
+    assert!(!bytes.contains(&b'\n'));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn writes_json_lines() {
+    let bytes = write_all(tracing_bridge::JsonLinesSerializer);
+    let decoded: Vec<TracingEvent> = tracing_bridge::read_json_lines(&bytes[..])
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(decoded, events());
+}
+
+#[cfg(feature = "bincode")]
+#[test]
+fn writes_bincode() {
+    let bytes = write_all(tracing_bridge::BincodeSerializer);
+    let mut reader = &bytes[..];
+    let decoded: Vec<TracingEvent> = (0..3)
+        .map(|_| bincode::deserialize_from(&mut reader).unwrap())
+        .collect();
+    assert_eq!(decoded, events());
+    assert!(reader.is_empty());
+}
+
+#[cfg(feature = "msgpack")]
+#[test]
+fn writes_msgpack() {
+    let bytes = write_all(tracing_bridge::MsgpackSerializer);
+    let mut reader = &bytes[..];
+    let decoded: Vec<TracingEvent> = (0..3)
+        .map(|_| rmp_serde::from_read(&mut reader).unwrap())
+        .collect();
+    assert_eq!(decoded, events());
+    assert!(reader.is_empty());
+}
+
+/// Fails to encode events with a `fail` field, and writes the others as
+/// their messages.
+struct Picky;
+
+impl EventSerializer for Picky {
+    fn serialize(&self, event: &TracingEvent, buf: &mut Vec<u8>) -> Result<(), SinkError> {
+        if event.fields.get("fail").is_some() {
+            return Err(SinkError::failed("unencodable event"));
+        }
+        buf.extend_from_slice(event.message.as_deref().unwrap_or_default().as_bytes());
+        Ok(())
+    }
+}
+
+#[test]
+fn writes_with_a_custom_serializer() {
+    assert_eq!(write_all(Picky), b"queryqueryquery");
+}
+
+#[test]
+fn writes_nothing_from_a_batch_that_fails_to_encode() {
+    let written = Shared::default();
+    let sink = WriterSink::new(Picky, written.clone());
+    let good = TracingEvent::builder().message("good").build();
+    let bad = TracingEvent::builder().field("fail", true).build();
+
+    sink.emit(good.clone()).unwrap();
+    let error = sink.emit_batch(vec![good, bad]).unwrap_err();
+    assert_eq!(error.events(), 2);
+    assert_eq!(written.bytes(), b"good");
+}
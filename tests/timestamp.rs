@@ -0,0 +1,164 @@
+//! Serializes events with each timestamp format and checks they read back to
+//! the same instant.
+#![cfg(feature = "json")]
+
+mod common;
+
+use common::Shared;
+use serde_json::json;
+use tracing_bridge::{
+    read_json_lines, EventFormat, FormattedEvent, JsonLinesSerializer, TimestampFormat,
+    TracingEvent, WriterSink,
+};
+
+/// 2024-05-01T12:30:00.123Z, a whole number of milliseconds so that every
+/// format keeps it exactly.
+const NANOS: u64 = 1_714_566_600_123_000_000;
+
+const FORMATS: [TimestampFormat; 3] = [
+    TimestampFormat::EpochNanos,
+    TimestampFormat::EpochMillis,
+    TimestampFormat::Rfc3339,
+];
+
+fn in_format(format: TimestampFormat) -> EventFormat {
+    EventFormat::default().with_timestamp_format(format)
+}
+
+fn event(timestamp: u64) -> TracingEvent {
+    TracingEvent::builder()
+        .message("tick")
+        .timestamp(timestamp)
+        .build()
+}
+
+#[test]
+fn writes_timestamps_in_each_format() {
+    let written: Vec<_> = FORMATS
+        .iter()
+        .map(|&format| {
+            let event = event(NANOS);
+            let json = serde_json::to_value(FormattedEvent::new(&event, in_format(format)));
+            json.unwrap()["timestamp"].clone()
+        })
+        .collect();
+    assert_eq!(
+        written,
+        vec![
+            json!(NANOS),
+            json!(1_714_566_600_123_u64),
+            json!("2024-05-01T12:30:00.123000000Z"),
+        ]
+    );
+
+    // The event itself always serializes its timestamp as nanoseconds.
+    let json = serde_json::to_value(event(NANOS)).unwrap();
+    assert_eq!(json["timestamp"], NANOS);
+}
+
+#[test]
+fn serializes_like_the_event_in_the_default_format() {
+    let event = event(NANOS);
+    let formatted = FormattedEvent::new(&event, EventFormat::default());
+    assert_eq!(
+        serde_json::to_string(&formatted).unwrap(),
+        serde_json::to_string(&event).unwrap()
+    );
+    #[cfg(feature = "bincode")]
+    assert_eq!(
+        bincode::serialize(&formatted).unwrap(),
+        bincode::serialize(&event).unwrap()
+    );
+}
+
+/// Writes `events` as JSON Lines with timestamps in `format`.
+fn json_lines(events: &[TracingEvent], format: TimestampFormat) -> String {
+    let mut lines = String::new();
+    for event in events {
+        let formatted = FormattedEvent::new(event, in_format(format));
+        lines += &serde_json::to_string(&formatted).unwrap();
+        lines.push('\n');
+    }
+    lines
+}
+
+#[test]
+fn reads_back_json_timestamps_written_in_each_format() {
+    for &format in &FORMATS {
+        let lines = json_lines(&[event(NANOS)], format);
+        let read: Vec<_> = read_json_lines(lines.as_bytes())
+            .with_timestamp_format(format)
+            .collect::<std::io::Result<_>>()
+            .unwrap();
+        assert_eq!(read, [event(NANOS)], "{:?}", format);
+    }
+
+    // Strings are read as RFC 3339 in any format, and without one.
+    let lines = json_lines(&[event(NANOS)], TimestampFormat::Rfc3339);
+    let read = read_json_lines(lines.as_bytes()).next().unwrap().unwrap();
+    assert_eq!(read.timestamp, Some(NANOS));
+    let read: TracingEvent = serde_json::from_str(&lines).unwrap();
+    assert_eq!(read.timestamp, Some(NANOS));
+}
+
+#[test]
+fn reads_numbers_in_the_format_of_the_reader() {
+    let lines = json_lines(&[event(NANOS)], TimestampFormat::EpochMillis);
+    let read = read_json_lines(lines.as_bytes()).next().unwrap().unwrap();
+    assert_eq!(read.timestamp, Some(NANOS / 1_000_000));
+}
+
+#[cfg(feature = "bincode")]
+#[test]
+fn reads_back_framed_timestamps_written_in_each_format() {
+    use tracing_bridge::{FramedReader, FramedWriter};
+
+    for &format in &FORMATS {
+        let mut writer = FramedWriter::new(Vec::new()).with_timestamp_format(format);
+        writer.write(&event(NANOS)).unwrap();
+        let bytes = writer.into_inner();
+
+        let read: Vec<_> = FramedReader::new(&bytes[..])
+            .with_timestamp_format(format)
+            .events()
+            .collect::<std::io::Result<_>>()
+            .unwrap();
+        assert_eq!(read, [event(NANOS)], "{:?}", format);
+    }
+}
+
+#[test]
+fn drops_sub_millisecond_precision_as_millis() {
+    let lines = json_lines(&[event(NANOS + 456_789)], TimestampFormat::EpochMillis);
+    let read = read_json_lines(lines.as_bytes())
+        .with_timestamp_format(TimestampFormat::EpochMillis)
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(read.timestamp, Some(NANOS));
+}
+
+#[test]
+fn reads_rfc3339_timestamps_with_an_offset() {
+    let read: TracingEvent = serde_json::from_value(json!({
+        "metadata": event(0).metadata,
+        "message": null,
+        "fields": {},
+        "timestamp": "2024-05-01T14:30:00.123+02:00",
+    }))
+    .unwrap();
+    assert_eq!(read.timestamp, Some(NANOS));
+}
+
+#[test]
+fn writes_timestamps_in_the_format_of_the_sink() {
+    use tracing_bridge::EventSink;
+
+    let written = Shared::default();
+    let sink = WriterSink::new(JsonLinesSerializer, written.clone())
+        .with_timestamp_format(TimestampFormat::Rfc3339);
+    sink.emit(event(NANOS)).unwrap();
+
+    let line: serde_json::Value = serde_json::from_slice(&written.bytes()).unwrap();
+    assert_eq!(line["timestamp"], "2024-05-01T12:30:00.123000000Z");
+}